@@ -5,6 +5,7 @@ use clap::{Parser, Subcommand};
 use edgefirst_client::{
     AnnotationSetID, AnnotationType, Client, Dataset, DatasetID, Error, FileType, Parameter,
     Progress, SchemaField, SnapshotID, StartTrainingRequest, TaskID, TrainingSession,
+    coco::MaskFormat,
 };
 use inquire::{Password, PasswordDisplayMode};
 use std::{
@@ -223,7 +224,8 @@ enum Command {
         #[clap(long, value_delimiter = ',')]
         groups: Vec<String>,
 
-        /// Annotation types to download: box2d, box3d, polygon, raster.
+        /// Annotation types to download: box2d, box3d, polygon, raster,
+        /// keypoints.
         /// `mask` and `seg` are accepted as aliases for `polygon` (so
         /// `--types mask` selects vector polygons, not raster masks — use
         /// `raster` for those). Downloads all types if not specified.
@@ -709,6 +711,11 @@ enum Command {
         #[clap(long, value_delimiter = ',')]
         groups: Vec<String>,
 
+        /// Encode all masks as `polygon` or `rle` (default: keep polygons as
+        /// polygons and raster masks as RLE)
+        #[clap(long, value_parser = parse_mask_format)]
+        mask_format: Option<MaskFormat>,
+
         /// Pretty-print JSON output
         #[clap(long)]
         pretty: bool,
@@ -771,7 +778,8 @@ enum Command {
         #[clap(long, action = clap::ArgAction::Set, default_value_t = true)]
         images: bool,
 
-        /// Batch size for uploads
+        /// Number of samples per request (with --update, a request is also
+        /// closed once it would carry more than 500 annotations)
         #[clap(long, default_value = "100")]
         batch_size: usize,
 
@@ -818,6 +826,10 @@ enum Command {
         #[clap(long, action = clap::ArgAction::Set, default_value_t = true)]
         masks: bool,
 
+        /// Encode masks as `polygon` (default) or `rle`
+        #[clap(long, value_parser = parse_mask_format)]
+        mask_format: Option<MaskFormat>,
+
         /// Include images in output (creates ZIP)
         #[clap(long)]
         images: bool,
@@ -1054,7 +1066,7 @@ async fn handle_organization(client: &Client) -> Result<(), Error> {
 }
 
 async fn handle_projects(client: &Client, name: Option<String>) -> Result<(), Error> {
-    let projects = client.projects(name.as_deref()).await?;
+    let projects = client.projects(name.as_deref(), None, false).await?;
     for project in projects {
         println!(
             "[{}] {}: {}",
@@ -1122,15 +1134,17 @@ async fn handle_datasets(
 ) -> Result<(), Error> {
     if let Some(project_id) = project_id {
         let datasets = client
-            .datasets(project_id.try_into()?, name.as_deref())
+            .datasets(Some(project_id.try_into()?), name.as_deref(), None, false)
             .await?;
         for dataset in datasets {
             print_dataset_details(client, &dataset, labels, annotation_sets).await?;
         }
     } else {
-        let projects = client.projects(None).await?;
+        let projects = client.projects(None, None, false).await?;
         for project in projects {
-            let datasets = client.datasets(project.id(), name.as_deref()).await?;
+            let datasets = client
+                .datasets(Some(project.id()), name.as_deref(), None, false)
+                .await?;
             for dataset in datasets {
                 println!(
                     "[{}] {}: {}",
@@ -1306,6 +1320,7 @@ async fn handle_download_dataset(
             flatten,
             Some(tx),
             tag.as_deref(),
+            Default::default(),
         )
         .await?;
     Ok(())
@@ -1326,11 +1341,17 @@ fn parse_annotation_type(value: &str) -> Result<AnnotationType, String> {
     AnnotationType::try_from(value).map_err(|_| {
         format!(
             "invalid annotation type '{value}': valid types are box2d, box3d, \
-             polygon, raster (mask and seg are aliases for polygon)"
+             polygon, raster, keypoints (mask and seg are aliases for polygon)"
         )
     })
 }
 
+/// Parse a `--mask-format` value (`polygon` or `rle`).
+fn parse_mask_format(value: &str) -> Result<MaskFormat, String> {
+    MaskFormat::try_from(value)
+        .map_err(|_| format!("invalid mask format '{value}': valid formats are polygon, rle"))
+}
+
 async fn handle_download_annotations(
     client: &Client,
     annotation_set_id: String,
@@ -1378,7 +1399,14 @@ async fn handle_download_annotations(
     match format {
         Some(ext) if ext == "json" => {
             let annotations = client
-                .annotations(annotation_set_id, &groups, &types, Some(tx), tag.as_deref())
+                .annotations(
+                    annotation_set_id,
+                    &groups,
+                    &types,
+                    Some(tx),
+                    tag.as_deref(),
+                    &[],
+                )
                 .await?;
             let mut file = File::create(&output)?;
             file.write_all(serde_json::to_string_pretty(&annotations)?.as_bytes())?;
@@ -1396,6 +1424,7 @@ async fn handle_download_annotations(
                         &types,
                         Some(tx),
                         tag.as_deref(),
+                        false,
                     )
                     .await?;
                 IpcWriter::new(File::create(output).unwrap())
@@ -3287,9 +3316,8 @@ async fn handle_upload_dataset(
     // the next batch, never while uploading.
     let queue = std::sync::Arc::new(tokio::sync::Mutex::new(batches.into_iter().enumerate()));
 
-    let mut workers: tokio::task::JoinSet<
-        Result<Vec<edgefirst_client::SamplesPopulateResult>, Error>,
-    > = tokio::task::JoinSet::new();
+    let mut workers: tokio::task::JoinSet<Result<edgefirst_client::PopulateReport, Error>> =
+        tokio::task::JoinSet::new();
 
     for _ in 0..n_workers {
         let client = (*client).clone();
@@ -3298,7 +3326,7 @@ async fn handle_upload_dataset(
         #[cfg(feature = "profiling")]
         let upload_span = upload_span.clone();
         workers.spawn(async move {
-            let mut results = Vec::new();
+            let mut report = edgefirst_client::PopulateReport::default();
             loop {
                 // Hold the lock only for `.next()`, then release before uploading.
                 let next = { queue.lock().await.next() };
@@ -3314,6 +3342,7 @@ async fn handle_upload_dataset(
                     annotation_set_id_parsed,
                     batch,
                     Some(tx.clone()),
+                    None,
                 );
                 #[cfg(feature = "profiling")]
                 let fut = fut.instrument(tracing::info_span!(
@@ -3322,9 +3351,11 @@ async fn handle_upload_dataset(
                     batch_num = batch_index + 1,
                     size = batch_size
                 ));
-                results.extend(fut.await?);
+                let batch_report = fut.await?;
+                report.succeeded.extend(batch_report.succeeded);
+                report.failed.extend(batch_report.failed);
             }
-            Ok(results)
+            Ok(report)
         });
     }
 
@@ -3335,10 +3366,14 @@ async fn handle_upload_dataset(
     // Collect results, aborting the whole upload on the first batch error to
     // preserve the previous serial `?` semantics.
     let mut all_results: Vec<edgefirst_client::SamplesPopulateResult> = Vec::new();
+    let mut failed: Vec<edgefirst_client::SampleError> = Vec::new();
     let mut first_error: Option<Error> = None;
     while let Some(joined) = workers.join_next().await {
         match joined {
-            Ok(Ok(batch_results)) => all_results.extend(batch_results),
+            Ok(Ok(report)) => {
+                all_results.extend(report.succeeded);
+                failed.extend(report.failed);
+            }
             Ok(Err(e)) => {
                 first_error.get_or_insert(e);
                 workers.abort_all();
@@ -3397,6 +3432,21 @@ async fn handle_upload_dataset(
         println!("  ... and {} more", all_results.len() - 10);
     }
 
+    if !failed.is_empty() {
+        eprintln!("Failed to upload {} samples", failed.len());
+        for failure in failed.iter().take(10) {
+            eprintln!("  {}", failure);
+        }
+        if failed.len() > 10 {
+            eprintln!("  ... and {} more", failed.len() - 10);
+        }
+        return Err(Error::PopulateFailed {
+            failed: failed.len(),
+            total: failed.len() + all_results.len(),
+            first_error: failed[0].to_string(),
+        });
+    }
+
     Ok(())
 }
 
@@ -3419,13 +3469,15 @@ async fn handle_experiments(
     let projects = if let Some(project_id) = project_id {
         vec![client.project(project_id.try_into()?).await?]
     } else {
-        client.projects(None).await?
+        client.projects(None, None, false).await?
     };
 
     for project in projects {
         println!("{}", project.name());
 
-        let experiments = client.experiments(project.id(), name.as_deref()).await?;
+        let experiments = client
+            .experiments(Some(project.id()), name.as_deref(), None, false)
+            .await?;
         for experiment in experiments {
             println!(
                 "    [{}] {}: {}",
@@ -3479,9 +3531,11 @@ async fn handle_training_sessions(
             print_training_session_with_artifacts(client, &session).await?;
         }
     } else {
-        let projects = client.projects(None).await?;
+        let projects = client.projects(None, None, false).await?;
         for project in projects {
-            let trainers = client.experiments(project.id(), None).await?;
+            let trainers = client
+                .experiments(Some(project.id()), None, None, false)
+                .await?;
             for trainer in trainers {
                 let sessions = client
                     .training_sessions(trainer.id(), name.as_deref())
@@ -3571,7 +3625,7 @@ async fn handle_upload_artifact(
             .unwrap()
     });
     let session = client.training_session(session_id.try_into()?).await?;
-    session.upload_artifact(client, &name, path).await?;
+    session.upload_artifact(client, &name, path, None).await?;
     Ok(())
 }
 
@@ -3950,7 +4004,9 @@ async fn monitor_task(client: &Client, task_id: TaskID) -> Result<(), Error> {
 }
 
 async fn handle_validation_sessions(client: &Client, project_id: String) -> Result<(), Error> {
-    let sessions = client.validation_sessions(project_id.try_into()?).await?;
+    let sessions = client
+        .validation_sessions(Some(project_id.try_into()?))
+        .await?;
     for session in sessions {
         println!(
             "[{}] {}: {}",
@@ -4882,6 +4938,7 @@ async fn handle_arrow_to_coco(
     output: PathBuf,
     masks: bool,
     groups: Vec<String>,
+    mask_format: Option<MaskFormat>,
     pretty: bool,
 ) -> Result<(), Error> {
     use chrono::Datelike;
@@ -4906,6 +4963,7 @@ async fn handle_arrow_to_coco(
     let options = ArrowToCocoOptions {
         include_masks: masks,
         groups,
+        mask_format,
         info: Some(CocoInfo {
             description: Some("Converted from EdgeFirst format".to_string()),
             version: Some("1.0".to_string()),
@@ -4958,7 +5016,7 @@ struct CocoCliImportArgs {
     masks: bool,
     /// Include images in upload.
     images: bool,
-    /// Batch size for uploads.
+    /// Number of samples per request.
     batch_size: usize,
     /// Maximum concurrent uploads.
     concurrency: usize,
@@ -5336,6 +5394,7 @@ async fn handle_export_coco(
     output: PathBuf,
     groups: Vec<String>,
     masks: bool,
+    mask_format: Option<MaskFormat>,
     images: bool,
     pretty: bool,
 ) -> Result<(), Error> {
@@ -5370,6 +5429,7 @@ async fn handle_export_coco(
     let options = CocoExportOptions {
         groups,
         include_masks: masks,
+        mask_format,
         include_images: images,
         output_zip,
         pretty_json: pretty,
@@ -5908,6 +5968,7 @@ async fn main() -> Result<(), Error> {
             output,
             masks,
             groups,
+            mask_format,
             pretty,
         } => {
             return handle_arrow_to_coco(
@@ -5915,6 +5976,7 @@ async fn main() -> Result<(), Error> {
                 output.clone(),
                 *masks,
                 groups.clone(),
+                *mask_format,
                 *pretty,
             )
             .await;
@@ -5986,10 +6048,11 @@ async fn main() -> Result<(), Error> {
     } else if let Some(ref token_server) = effective_token_server {
         // Using token - its server takes priority
         if let Some(ref requested_server) = args.server {
-            // Normalize: "" and "saas" are equivalent
+            // Normalize: "" and "saas" are equivalent, and self-hosted
+            // servers are compared by full URL
             let requested_normalized = match requested_server.as_str() {
                 "" | "saas" => "saas",
-                s => s,
+                s => s.trim_end_matches('/'),
             };
             if requested_normalized != token_server {
                 eprintln!(
@@ -6385,6 +6448,7 @@ async fn main() -> Result<(), Error> {
             output,
             groups,
             masks,
+            mask_format,
             images,
             pretty,
         } => {
@@ -6395,6 +6459,7 @@ async fn main() -> Result<(), Error> {
                 output,
                 groups,
                 masks,
+                mask_format,
                 images,
                 pretty,
             )
@@ -6474,12 +6539,17 @@ mod tests {
             parse_annotation_type("raster").unwrap(),
             AnnotationType::Mask
         );
+        assert_eq!(
+            parse_annotation_type("keypoints").unwrap(),
+            AnnotationType::Keypoints
+        );
     }
 
     #[test]
     fn test_parse_annotation_type_invalid_is_rejected() {
-        // Unknown values must error rather than silently defaulting to box2d
-        // (the library's infallible `From<String>` behaviour).
+        // Unknown values must error rather than pass through as
+        // `AnnotationType::Other` (the library's infallible `From<String>`
+        // behaviour).
         let err = parse_annotation_type("keypoint").unwrap_err();
         assert!(err.contains("keypoint"));
         // The message surfaces the canonical types, including the distinct
@@ -4035,7 +4035,9 @@ fn test_snapshot_restore() -> Result<(), Box<dyn std::error::Error>> {
     println!("│ STEP 7: Fetch Restored Samples via Library API                  │");
     println!("└─────────────────────────────────────────────────────────────────┘");
 
-    use edgefirst_client::{AnnotationSetID, AnnotationType, Client as EdgeFirstClient, DatasetID};
+    use edgefirst_client::{
+        AnnotationSetID, AnnotationType, Client as EdgeFirstClient, DatasetID, SamplesQuery,
+    };
 
     let api_client = EdgeFirstClient::new()?.with_token_path(None)?;
     let rt = tokio::runtime::Runtime::new()?;
@@ -4051,16 +4053,16 @@ fn test_snapshot_restore() -> Result<(), Box<dyn std::error::Error>> {
     // Fetch all samples using the library API directly
     let samples = rt.block_on(api_client.samples(
         dataset_id,
-        Some(annotation_set_id),
-        &[
-            AnnotationType::Box2d,
-            AnnotationType::Box3d,
-            AnnotationType::Polygon,
-        ],
-        &[], // All groups
-        &[], // No file type filter
-        None,
         None,
+        SamplesQuery {
+            annotation_set_id: Some(annotation_set_id),
+            annotation_types: vec![
+                AnnotationType::Box2d,
+                AnnotationType::Box3d,
+                AnnotationType::Polygon,
+            ],
+            ..Default::default()
+        },
     ))?;
 
     println!("✓ Fetched {} samples from API", samples.len());
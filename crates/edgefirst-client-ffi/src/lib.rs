@@ -39,7 +39,10 @@
 
 uniffi::setup_scaffolding!();
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
 use async_compat::CompatExt;
 use edgefirst_client as core;
@@ -106,6 +109,20 @@ pub enum ClientError {
     /// The server rejected the payload as too large.
     #[error("Payload too large: {message}")]
     PayloadTooLarge { message: String },
+    /// The annotation set is locked and its annotations cannot be modified.
+    #[error("Annotation set locked: {annotation_set_id}")]
+    AnnotationSetLocked { annotation_set_id: String },
+    /// The snapshot is not available for download yet, or failed
+    /// processing. Carries the server's snapshot status.
+    #[error("Snapshot not ready: {status}")]
+    SnapshotNotReady { status: String },
+    /// The server does not implement the endpoint the call relies on,
+    /// usually because it predates the feature.
+    #[error("Not supported by server: {feature}")]
+    UnsupportedByServer { feature: String },
+    /// The operation or request did not finish within its timeout.
+    #[error("Timed out after {seconds}s")]
+    Timeout { seconds: f64 },
 }
 
 impl From<core::Error> for ClientError {
@@ -133,17 +150,18 @@ impl From<core::Error> for ClientError {
                 message: format!("Invalid annotation type: {}", msg),
             },
             core::Error::StorageError(msg) => ClientError::StorageError { message: msg },
-            core::Error::RpcError(code, msg) => {
-                if code == -32001 || code == -32002 {
-                    ClientError::AuthenticationError { message: msg }
-                } else if code == -32004 {
-                    ClientError::NotFound { message: msg }
-                } else {
-                    ClientError::InternalError {
-                        message: format!("RPC error {}: {}", code, msg),
-                    }
-                }
-            }
+            core::Error::RpcError {
+                kind,
+                code,
+                message,
+            } => match kind {
+                core::RpcErrorKind::Unauthenticated => ClientError::AuthenticationError { message },
+                core::RpcErrorKind::Forbidden => ClientError::PermissionDenied { message },
+                core::RpcErrorKind::NotFound => ClientError::NotFound { message },
+                _ => ClientError::InternalError {
+                    message: format!("RPC error {}: {}", code, message),
+                },
+            },
             core::Error::TaskNotFound(id) => ClientError::TaskNotFound {
                 task_id: id.to_string(),
             },
@@ -154,6 +172,21 @@ impl From<core::Error> for ClientError {
                     None => method,
                 },
             },
+            core::Error::UrlExpired(_)
+            | core::Error::UploadUrlExpired(_)
+            | core::Error::ChecksumMismatch { .. } => ClientError::NetworkError {
+                message: err.to_string(),
+            },
+            core::Error::AnnotationSetLocked(id) => ClientError::AnnotationSetLocked {
+                annotation_set_id: id.to_string(),
+            },
+            core::Error::SnapshotNotReady { status } => ClientError::SnapshotNotReady { status },
+            core::Error::UnsupportedByServer { feature } => {
+                ClientError::UnsupportedByServer { feature }
+            }
+            core::Error::Timeout(limit) => ClientError::Timeout {
+                seconds: limit.as_secs_f64(),
+            },
             _ => ClientError::InternalError {
                 message: err.to_string(),
             },
@@ -244,6 +277,45 @@ pub trait ProgressCallback: Send + Sync {
     fn on_progress(&self, current: u64, total: u64, status: Option<String>);
 }
 
+/// Callback interface for the phases of a long-running operation.
+///
+/// Pass it to `download_dataset` next to a `ProgressCallback` to learn when
+/// the download moves from listing samples to writing files and finishing.
+///
+/// # Thread safety
+///
+/// Callbacks are invoked from a background Tokio task.  The implementation
+/// must be `Send + Sync`.
+#[uniffi::export(callback_interface)]
+pub trait PhaseCallback: Send + Sync {
+    /// Called once when the operation enters `phase`.
+    fn on_phase(&self, phase: ProgressPhase);
+}
+
+/// Phase of a long-running operation, reported with each progress event.
+#[derive(uniffi::Enum, Clone, Debug, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// Listing samples from the server.
+    Enumerating,
+    /// Writing sample files to disk.
+    Downloading,
+    /// All work finished; emitted once at the end.
+    Finalizing,
+    /// The operation does not report phases.
+    Other,
+}
+
+impl From<core::ProgressPhase> for ProgressPhase {
+    fn from(phase: core::ProgressPhase) -> Self {
+        match phase {
+            core::ProgressPhase::Enumerating => ProgressPhase::Enumerating,
+            core::ProgressPhase::Downloading => ProgressPhase::Downloading,
+            core::ProgressPhase::Finalizing => ProgressPhase::Finalizing,
+            core::ProgressPhase::Other => ProgressPhase::Other,
+        }
+    }
+}
+
 /// Spawn a Tokio task that forwards `core::Progress` events from an mpsc
 /// channel to a foreign `ProgressCallback`.
 ///
@@ -265,6 +337,77 @@ fn spawn_progress_bridge(
     tx
 }
 
+/// Like [`spawn_progress_bridge`], and also reports each change of
+/// `core::ProgressPhase` to a foreign `PhaseCallback`. Returns `None` when
+/// neither callback is given.
+fn spawn_phased_progress_bridge(
+    rt: &tokio::runtime::Runtime,
+    progress: Option<Box<dyn ProgressCallback>>,
+    phases: Option<Box<dyn PhaseCallback>>,
+) -> Option<tokio::sync::mpsc::Sender<core::Progress>> {
+    if progress.is_none() && phases.is_none() {
+        return None;
+    }
+    let progress: Option<Arc<dyn ProgressCallback>> = progress.map(Arc::from);
+    let phases: Option<Arc<dyn PhaseCallback>> = phases.map(Arc::from);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<core::Progress>(8);
+    rt.spawn(async move {
+        let mut last_phase = None;
+        while let Some(p) = rx.recv().await {
+            if let Some(callback) = &phases
+                && last_phase != Some(p.phase)
+            {
+                last_phase = Some(p.phase);
+                callback.on_phase(p.phase.into());
+            }
+            if let Some(callback) = &progress {
+                callback.on_progress(p.current as u64, p.total as u64, p.status);
+            }
+        }
+    });
+    Some(tx)
+}
+
+/// Callback interface for per-file download events.
+///
+/// Pass it to `download_dataset` next to a `ProgressCallback`, which keeps
+/// reporting aggregate progress. Files download concurrently, so events for
+/// different files interleave; a file's start and complete events carry the
+/// same `name`.
+///
+/// # Thread safety
+///
+/// Callbacks are invoked from a background Tokio task.  The implementation
+/// must be `Send + Sync`.
+#[uniffi::export(callback_interface)]
+pub trait FileProgressCallback: Send + Sync {
+    /// Called before a file is downloaded.
+    fn on_file_start(&self, name: String);
+    /// Called once a file has been written to disk, with its size in bytes.
+    fn on_file_complete(&self, name: String, bytes: u64);
+}
+
+/// Spawn a Tokio task that forwards `core::FileEvent`s to a foreign
+/// `FileProgressCallback`; see [`spawn_progress_bridge`].
+fn spawn_file_event_bridge(
+    rt: &tokio::runtime::Runtime,
+    callback: Box<dyn FileProgressCallback>,
+) -> tokio::sync::mpsc::Sender<core::FileEvent> {
+    let callback: Arc<dyn FileProgressCallback> = Arc::from(callback);
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<core::FileEvent>(8);
+    rt.spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                core::FileEvent::Started { name } => callback.on_file_start(name),
+                core::FileEvent::Completed { name, bytes } => {
+                    callback.on_file_complete(name, bytes)
+                }
+            }
+        }
+    });
+    tx
+}
+
 // =============================================================================
 // ID Types
 // =============================================================================
@@ -661,6 +804,10 @@ pub enum AnnotationType {
     Polygon,
     /// Raster pixel masks for semantic/instance segmentation
     Mask,
+    /// Pose keypoints
+    Keypoints,
+    /// A type this client does not know, holding its name as given
+    Other { name: String },
 }
 
 impl From<core::AnnotationType> for AnnotationType {
@@ -670,6 +817,8 @@ impl From<core::AnnotationType> for AnnotationType {
             core::AnnotationType::Box3d => AnnotationType::Box3d,
             core::AnnotationType::Polygon => AnnotationType::Polygon,
             core::AnnotationType::Mask => AnnotationType::Mask,
+            core::AnnotationType::Keypoints => AnnotationType::Keypoints,
+            core::AnnotationType::Other(name) => AnnotationType::Other { name },
         }
     }
 }
@@ -681,6 +830,8 @@ impl From<AnnotationType> for core::AnnotationType {
             AnnotationType::Box3d => core::AnnotationType::Box3d,
             AnnotationType::Polygon => core::AnnotationType::Polygon,
             AnnotationType::Mask => core::AnnotationType::Mask,
+            AnnotationType::Keypoints => core::AnnotationType::Keypoints,
+            AnnotationType::Other { name } => core::AnnotationType::Other(name),
         }
     }
 }
@@ -822,6 +973,8 @@ pub struct AnnotationSet {
     pub name: String,
     pub description: String,
     pub created: Option<String>,
+    /// Whether the set is locked (read-only).
+    pub locked: bool,
 }
 
 impl From<core::AnnotationSet> for AnnotationSet {
@@ -832,6 +985,7 @@ impl From<core::AnnotationSet> for AnnotationSet {
             name: a.name().to_string(),
             description: a.description().to_string(),
             created: a.created().map(|dt| dt.to_rfc3339()),
+            locked: a.is_locked(),
         }
     }
 }
@@ -2126,6 +2280,78 @@ impl From<Polygon> for core::Polygon {
     }
 }
 
+/// Visibility flag of a keypoint, as in COCO.
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    /// Not labeled; the point's coordinates carry no meaning.
+    NotLabeled,
+    /// Labeled but occluded.
+    Occluded,
+    /// Labeled and visible.
+    Visible,
+}
+
+impl From<core::Visibility> for Visibility {
+    fn from(v: core::Visibility) -> Self {
+        match v {
+            core::Visibility::NotLabeled => Visibility::NotLabeled,
+            core::Visibility::Occluded => Visibility::Occluded,
+            core::Visibility::Visible => Visibility::Visible,
+        }
+    }
+}
+
+impl From<Visibility> for core::Visibility {
+    fn from(v: Visibility) -> Self {
+        match v {
+            Visibility::NotLabeled => core::Visibility::NotLabeled,
+            Visibility::Occluded => core::Visibility::Occluded,
+            Visibility::Visible => core::Visibility::Visible,
+        }
+    }
+}
+
+/// A keypoint with normalized coordinates and its visibility.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    pub visibility: Visibility,
+}
+
+/// Pose keypoints, in the order the dataset's skeleton refers to them.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct Keypoints {
+    pub points: Vec<Keypoint>,
+}
+
+impl From<core::Keypoints> for Keypoints {
+    fn from(k: core::Keypoints) -> Self {
+        Self {
+            points: k
+                .points
+                .into_iter()
+                .map(|(x, y, v)| Keypoint {
+                    x,
+                    y,
+                    visibility: v.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Keypoints> for core::Keypoints {
+    fn from(k: Keypoints) -> Self {
+        core::Keypoints::new(
+            k.points
+                .into_iter()
+                .map(|p| (p.x, p.y, p.visibility.into()))
+                .collect(),
+        )
+    }
+}
+
 /// A file associated with a sample (e.g., LiDAR point cloud, radar data).
 #[derive(uniffi::Record, Clone, Debug)]
 pub struct SampleFile {
@@ -2223,6 +2449,8 @@ pub struct Annotation {
     pub polygon: Option<Polygon>,
     /// Raster mask as raw PNG bytes.
     pub mask: Option<Vec<u8>>,
+    /// Pose keypoints.
+    pub keypoints: Option<Keypoints>,
     /// Confidence score for the 2D bounding box prediction.
     pub box2d_score: Option<f32>,
     /// Confidence score for the 3D bounding box prediction.
@@ -2249,6 +2477,7 @@ impl From<core::Annotation> for Annotation {
             box3d: a.box3d().map(|b| Box3d::from(b.clone())),
             polygon: a.polygon().map(|p| Polygon::from(p.clone())),
             mask: a.mask().map(|m| m.as_bytes().to_vec()),
+            keypoints: a.keypoints().map(|k| Keypoints::from(k.clone())),
             box2d_score: a.box2d_score(),
             box3d_score: a.box3d_score(),
             polygon_score: a.polygon_score(),
@@ -2290,6 +2519,7 @@ impl TryFrom<Annotation> for core::Annotation {
             })?;
             ann.set_mask(Some(mask));
         }
+        ann.set_keypoints(a.keypoints.map(core::Keypoints::from));
         ann.set_box2d_score(a.box2d_score);
         ann.set_box3d_score(a.box3d_score);
         ann.set_polygon_score(a.polygon_score);
@@ -2423,6 +2653,103 @@ impl From<core::Sample> for Sample {
     }
 }
 
+/// Filters for `Client::samples`; every field is optional.
+#[derive(uniffi::Record, Clone, Debug)]
+pub struct SamplesQuery {
+    /// Annotation set to include annotations from.
+    #[uniffi(default)]
+    pub annotation_set_id: Option<AnnotationSetId>,
+    /// Annotation types to include; empty includes none.
+    #[uniffi(default)]
+    pub annotation_types: Vec<AnnotationType>,
+    /// Sample groups to keep (e.g. "train", "val"); empty keeps all.
+    #[uniffi(default)]
+    pub groups: Vec<String>,
+    /// File types to include metadata for.
+    #[uniffi(default)]
+    pub file_types: Vec<FileType>,
+    /// Dataset version to list instead of the current one.
+    #[uniffi(default)]
+    pub version: Option<String>,
+    /// `true` keeps only samples with annotations in `annotation_set_id`,
+    /// `false` only samples without any. Requires `annotation_set_id`.
+    #[uniffi(default)]
+    pub labeled: Option<bool>,
+    /// Fetch image width and height.
+    #[uniffi(default = true)]
+    pub dimensions: bool,
+    /// Fetch annotations from `annotation_set_id`.
+    #[uniffi(default = true)]
+    pub annotations: bool,
+    /// Fetch the image URL and sensor files of `file_types`.
+    #[uniffi(default = true)]
+    pub files: bool,
+    /// Keep only samples dated at or after this (ISO 8601).
+    #[uniffi(default)]
+    pub start_date: Option<String>,
+    /// Keep only samples dated at or before this (ISO 8601). Undated samples
+    /// are dropped when either bound is set.
+    #[uniffi(default)]
+    pub end_date: Option<String>,
+}
+
+impl TryFrom<SamplesQuery> for core::SamplesQuery {
+    type Error = ClientError;
+
+    fn try_from(q: SamplesQuery) -> Result<Self, Self::Error> {
+        let parse_date = |date: Option<String>| {
+            date.map(|d| {
+                chrono::DateTime::parse_from_rfc3339(&d)
+                    .map(|d| d.with_timezone(&chrono::Utc))
+                    .map_err(|e| ClientError::InvalidParameters {
+                        message: format!("Invalid date {d}: {e}"),
+                    })
+            })
+            .transpose()
+        };
+        let fields = [
+            (q.dimensions, core::SampleFields::DIMENSIONS),
+            (q.annotations, core::SampleFields::ANNOTATIONS),
+            (q.files, core::SampleFields::FILES),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(core::SampleFields::IDS, |fields, (_, field)| fields | field);
+        let file_types: Vec<core::FileType> = q.file_types.into_iter().map(Into::into).collect();
+        Ok(Self {
+            annotation_set_id: q.annotation_set_id.map(Into::into),
+            annotation_types: q.annotation_types.into_iter().map(Into::into).collect(),
+            groups: q.groups,
+            types: core::FileType::expand_types(&file_types),
+            version: q.version,
+            labeled: q.labeled,
+            fields,
+            start_date: parse_date(q.start_date)?,
+            end_date: parse_date(q.end_date)?,
+        })
+    }
+}
+
+/// Process-wide Tokio runtime behind the blocking `Client` methods.
+///
+/// Built on first use and shared by every client, so chaining builders such
+/// as `Client::new().with_server(..).with_login(..)` starts one runtime
+/// instead of one per call.
+fn shared_runtime() -> Result<Arc<tokio::runtime::Runtime>, ClientError> {
+    static RUNTIME: OnceLock<Arc<tokio::runtime::Runtime>> = OnceLock::new();
+
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime.clone());
+    }
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
+        message: e.to_string(),
+    })?;
+    // A concurrent first call may win the race; its runtime is kept and this
+    // one dropped. Constructors are synchronous, so the drop is never inside
+    // an async context.
+    Ok(RUNTIME.get_or_init(|| Arc::new(runtime)).clone())
+}
+
 // =============================================================================
 // Factory Functions
 // =============================================================================
@@ -2446,9 +2773,7 @@ impl From<core::Sample> for Sample {
 pub fn create_client_with_storage(
     storage: Box<dyn TokenStorage>,
 ) -> Result<Arc<Client>, ClientError> {
-    let runtime = tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-        message: e.to_string(),
-    })?;
+    let runtime = shared_runtime()?;
     let bridge: Arc<dyn core::TokenStorage> = Arc::new(FfiTokenStorageBridge {
         inner: Arc::from(storage),
     });
@@ -2464,7 +2789,9 @@ pub fn create_client_with_storage(
 #[derive(uniffi::Object)]
 pub struct Client {
     inner: core::Client,
-    runtime: tokio::runtime::Runtime,
+    /// Runtime for the blocking methods, shared by every client; see
+    /// [`shared_runtime`].
+    runtime: Arc<tokio::runtime::Runtime>,
 }
 
 #[uniffi::export]
@@ -2472,9 +2799,7 @@ impl Client {
     /// Create a new client with default file token storage.
     #[uniffi::constructor]
     pub fn new() -> Result<Arc<Self>, ClientError> {
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-            message: e.to_string(),
-        })?;
+        let runtime = shared_runtime()?;
         let inner = core::Client::new()?;
         Ok(Arc::new(Self { inner, runtime }))
     }
@@ -2482,13 +2807,22 @@ impl Client {
     /// Create a new client with in-memory token storage (no persistence).
     #[uniffi::constructor]
     pub fn with_memory_storage() -> Result<Arc<Self>, ClientError> {
-        let runtime = tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-            message: e.to_string(),
-        })?;
+        let runtime = shared_runtime()?;
         let inner = core::Client::new()?.with_memory_storage();
         Ok(Arc::new(Self { inner, runtime }))
     }
 
+    /// Create a new client that reads its token from the environment
+    /// variable `var`, `EDGEFIRST_TOKEN` when not given. Tokens from a later
+    /// login are kept in memory only.
+    #[uniffi::constructor]
+    pub fn with_env_storage(var: Option<String>) -> Result<Arc<Self>, ClientError> {
+        let runtime = shared_runtime()?;
+        let var = var.as_deref().unwrap_or(core::EnvTokenStorage::DEFAULT_VAR);
+        let inner = core::Client::new()?.with_env_storage(var);
+        Ok(Arc::new(Self { inner, runtime }))
+    }
+
     /// Returns a new client connected to the specified server instance.
     ///
     /// Server names: "" or "saas" → production, "test", "stage", "dev", or
@@ -2497,9 +2831,139 @@ impl Client {
         let inner = self.inner.with_server(&name)?;
         Ok(Arc::new(Self {
             inner,
-            runtime: tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-                message: e.to_string(),
-            })?,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client connected to the Studio server at a full URL,
+    /// such as an on-premises deployment with a custom port. Short server
+    /// names are rejected.
+    pub fn with_server_url(self: Arc<Self>, url: String) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_server_url(&url)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that sends every request through the proxy at
+    /// `url` (`http://`, `https://`, `socks5://` or `socks5h://`, with
+    /// optional embedded credentials). `NO_PROXY` is still honoured.
+    pub fn with_proxy(self: Arc<Self>, url: String) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_proxy(&url)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that accepts plain `http://` server URLs to
+    /// remote hosts in `with_server`. Off by default because the token is
+    /// then sent in the clear.
+    pub fn with_allow_insecure(self: Arc<Self>, allow: bool) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_allow_insecure(allow);
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that takes the server clock to be `seconds`
+    /// ahead of the local clock (behind when negative) when checking the
+    /// token's expiry.
+    pub fn with_clock_skew(self: Arc<Self>, seconds: i64) -> Result<Arc<Self>, ClientError> {
+        let skew = chrono::TimeDelta::try_seconds(seconds).ok_or_else(|| {
+            ClientError::InvalidParameters {
+                message: format!("clock skew out of range: {}", seconds),
+            }
+        })?;
+        let inner = self.inner.with_clock_skew(skew);
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that measures the clock skew from the `Date`
+    /// header of API responses when `enabled`.
+    pub fn with_server_clock(self: Arc<Self>, enabled: bool) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_server_clock(enabled);
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Server clock minus local clock in whole seconds, as set by
+    /// `with_clock_skew` or measured by `with_server_clock`.
+    pub fn clock_skew(&self) -> i64 {
+        self.inner.clock_skew().num_seconds()
+    }
+
+    /// Returns a new client that speaks HTTP/2 on API calls when `enabled`,
+    /// or HTTP/1.1 (the default) otherwise. File transfers stay on HTTP/1.1.
+    pub fn with_http2(self: Arc<Self>, enabled: bool) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_http2(enabled)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that keeps idle connections open for `seconds`
+    /// and sends TCP keep-alive probes after the same idle time. Defaults
+    /// are 90 seconds idle and 15 seconds before probing.
+    pub fn with_keepalive(self: Arc<Self>, seconds: u32) -> Result<Arc<Self>, ClientError> {
+        let inner = self
+            .inner
+            .with_keepalive(std::time::Duration::from_secs(u64::from(seconds)))?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that fails API requests taking longer than
+    /// `seconds` with `ClientError::Timeout`, instead of the
+    /// `EDGEFIRST_TIMEOUT` default of 30 seconds.
+    pub fn with_timeout(self: Arc<Self>, seconds: u32) -> Result<Arc<Self>, ClientError> {
+        let inner = self
+            .inner
+            .with_timeout(std::time::Duration::from_secs(u64::from(seconds)))?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that gzip-compresses text files such as logs and
+    /// JSON in multipart uploads at `level` (0-9), or sends them as they are
+    /// when `None`. Only enable it against a server that accepts
+    /// gzip-encoded parts.
+    pub fn with_upload_compression(
+        self: Arc<Self>,
+        level: Option<u32>,
+    ) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_upload_compression(level)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Gzip level used for text file uploads, or `None` when off.
+    pub fn upload_compression(&self) -> Option<u32> {
+        self.inner.upload_compression()
+    }
+
+    /// Returns a new client that uploads training session files larger than
+    /// `bytes` in presigned multipart parts of `bytes` each (default 100 MiB,
+    /// at least 5 MiB).
+    pub fn with_upload_part_size(self: Arc<Self>, bytes: u64) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_upload_part_size(bytes as usize)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
         }))
     }
 
@@ -2508,12 +2972,94 @@ impl Client {
         let inner = self.inner.with_token(&token)?;
         Ok(Arc::new(Self {
             inner,
-            runtime: tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-                message: e.to_string(),
-            })?,
+            runtime: self.runtime.clone(),
+        }))
+    }
+
+    /// Returns a new client that keeps downloaded sample files in `path`.
+    ///
+    /// Dataset and sample downloads serve files from the cache when present,
+    /// so repeated downloads fetch no unchanged files. Entries are matched
+    /// by the file's `ETag`, so a file replaced on the server is fetched
+    /// again; use `clear_cache` to discard them.
+    pub fn with_cache_dir(self: Arc<Self>, path: String) -> Result<Arc<Self>, ClientError> {
+        let inner = self.inner.with_cache_dir(&path)?;
+        Ok(Arc::new(Self {
+            inner,
+            runtime: self.runtime.clone(),
         }))
     }
 
+    /// Total size in bytes of the files in the download cache; 0 when no
+    /// cache is configured.
+    pub fn cache_size(&self) -> Result<u64, ClientError> {
+        Ok(self.runtime.block_on(self.inner.cache_size())?)
+    }
+
+    /// Remove every file from the download cache.
+    pub fn clear_cache(&self) -> Result<(), ClientError> {
+        self.runtime.block_on(self.inner.clear_cache())?;
+        Ok(())
+    }
+
+    /// Download the files of a dataset to `output` (blocking).
+    ///
+    /// `groups` limits the download to the given groups (empty for all).
+    /// With `flatten` every file is written directly under `output` instead
+    /// of a directory per sequence. `progress` receives aggregate progress
+    /// counted in samples; `files` receives a start and a complete event for
+    /// every file; `phases` is told when the download enters each
+    /// `ProgressPhase`. Pass `None` for any of them to skip it.
+    ///
+    /// With `skip_existing_by_size`, files already in `output` whose size
+    /// matches the stored file are not downloaded again. This resumes an
+    /// interrupted download cheaply but, unlike a checksum, does not notice
+    /// a changed file of the same size.
+    ///
+    /// `concurrency` caps how many samples download at once; `None` keeps
+    /// the `MAX_TASKS` default and `0` is treated as `1`.
+    ///
+    /// With `verify_checksums`, files are checked against the checksum
+    /// storage reports for them and downloaded once more on a mismatch; a
+    /// second mismatch fails with a network error.
+    #[allow(clippy::too_many_arguments)]
+    pub fn download_dataset(
+        &self,
+        dataset_id: DatasetId,
+        groups: Vec<String>,
+        file_types: Vec<FileType>,
+        output: String,
+        flatten: bool,
+        skip_existing_by_size: bool,
+        concurrency: Option<u32>,
+        verify_checksums: bool,
+        progress: Option<Box<dyn ProgressCallback>>,
+        files: Option<Box<dyn FileProgressCallback>>,
+        phases: Option<Box<dyn PhaseCallback>>,
+    ) -> Result<(), ClientError> {
+        let file_types: Vec<core::FileType> = file_types.into_iter().map(Into::into).collect();
+        let progress = spawn_phased_progress_bridge(&self.runtime, progress, phases);
+        let files = files.map(|cb| spawn_file_event_bridge(&self.runtime, cb));
+        Ok(self
+            .runtime
+            .block_on(self.inner.download_dataset_with_file_events(
+                dataset_id.into(),
+                &groups,
+                &core::FileType::expand_types(&file_types),
+                output.into(),
+                flatten,
+                progress,
+                files,
+                None,
+                core::DownloadOptions {
+                    concurrency: concurrency.map(|n| n as usize),
+                    verify_checksums,
+                    skip_existing_by_size,
+                    ..Default::default()
+                },
+            ))?)
+    }
+
     /// Authenticate with username and password (blocking).
     pub fn with_login(
         self: Arc<Self>,
@@ -2525,9 +3071,7 @@ impl Client {
             .block_on(self.inner.with_login(&username, &password))?;
         Ok(Arc::new(Self {
             inner,
-            runtime: tokio::runtime::Runtime::new().map_err(|e| ClientError::InternalError {
-                message: e.to_string(),
-            })?,
+            runtime: self.runtime.clone(),
         }))
     }
 
@@ -2543,6 +3087,22 @@ impl Client {
         Ok(())
     }
 
+    /// Renew the session token before it expires.
+    ///
+    /// Returns the new expiry as an RFC 3339 string.
+    pub fn renew_token(&self) -> Result<String, ClientError> {
+        let expires = self.runtime.block_on(self.inner.renew_token())?;
+        Ok(expires.to_rfc3339())
+    }
+
+    /// Check the token's expiry locally, without a network call.
+    ///
+    /// The signature is not verified; use `verify_token` for an
+    /// authoritative answer.
+    pub fn token_valid_offline(&self) -> bool {
+        self.runtime.block_on(self.inner.token_valid_offline())
+    }
+
     /// Get the current server URL.
     pub fn url(&self) -> String {
         self.inner.url().to_string()
@@ -2562,7 +3122,7 @@ impl Client {
     pub fn projects(&self, name: Option<String>) -> Result<Vec<Project>, ClientError> {
         let projects = self
             .runtime
-            .block_on(self.inner.projects(name.as_deref()))?;
+            .block_on(self.inner.projects(name.as_deref(), None, false))?;
         Ok(projects.into_iter().map(Project::from).collect())
     }
 
@@ -2582,9 +3142,12 @@ impl Client {
         project_id: ProjectId,
         name: Option<String>,
     ) -> Result<Vec<Dataset>, ClientError> {
-        let datasets = self
-            .runtime
-            .block_on(self.inner.datasets(project_id.into(), name.as_deref()))?;
+        let datasets = self.runtime.block_on(self.inner.datasets(
+            Some(project_id.into()),
+            name.as_deref(),
+            None,
+            false,
+        ))?;
         Ok(datasets.into_iter().map(Dataset::from).collect())
     }
 
@@ -2619,6 +3182,23 @@ impl Client {
         Ok(labels.into_iter().map(Label::from).collect())
     }
 
+    /// Get the samples of a dataset matching `query` (blocking).
+    ///
+    /// `progress` receives progress counted in samples fetched.
+    pub fn samples(
+        &self,
+        dataset_id: DatasetId,
+        query: SamplesQuery,
+        progress: Option<Box<dyn ProgressCallback>>,
+    ) -> Result<Vec<Sample>, ClientError> {
+        let query = core::SamplesQuery::try_from(query)?;
+        let tx = progress.map(|cb| spawn_progress_bridge(&self.runtime, cb));
+        let samples = self
+            .runtime
+            .block_on(self.inner.samples(dataset_id.into(), tx, query))?;
+        Ok(samples.into_iter().map(Sample::from).collect())
+    }
+
     /// Update image dimensions for existing samples in a dataset.
     ///
     /// Accepts a list of sample dimension updates and sends them to the server.
@@ -2668,9 +3248,12 @@ impl Client {
         project_id: ProjectId,
         name: Option<String>,
     ) -> Result<Vec<Experiment>, ClientError> {
-        let experiments = self
-            .runtime
-            .block_on(self.inner.experiments(project_id.into(), name.as_deref()))?;
+        let experiments = self.runtime.block_on(self.inner.experiments(
+            Some(project_id.into()),
+            name.as_deref(),
+            None,
+            false,
+        ))?;
         Ok(experiments.into_iter().map(Experiment::from).collect())
     }
 
@@ -2680,6 +3263,28 @@ impl Client {
         Ok(experiment.into())
     }
 
+    /// Create an experiment in a project.
+    pub fn create_experiment(
+        &self,
+        project_id: ProjectId,
+        name: String,
+        description: Option<String>,
+    ) -> Result<Experiment, ClientError> {
+        let experiment = self.runtime.block_on(self.inner.create_experiment(
+            project_id.into(),
+            &name,
+            description.as_deref(),
+        ))?;
+        Ok(experiment.into())
+    }
+
+    /// Delete an experiment by ID.
+    pub fn delete_experiment(&self, id: ExperimentId) -> Result<(), ClientError> {
+        self.runtime
+            .block_on(self.inner.delete_experiment(id.into()))?;
+        Ok(())
+    }
+
     // =========================================================================
     // Training Sessions
     // =========================================================================
@@ -2727,7 +3332,7 @@ impl Client {
     ) -> Result<Vec<Arc<ValidationSession>>, ClientError> {
         let sessions = self
             .runtime
-            .block_on(self.inner.validation_sessions(project_id.into()))?;
+            .block_on(self.inner.validation_sessions(Some(project_id.into())))?;
         Ok(sessions
             .into_iter()
             .map(|s| Arc::new(ValidationSession::new(s)))
@@ -3077,11 +3682,7 @@ impl Client {
             let inner = self.inner.with_login(&username, &password).await?;
             Ok(Arc::new(Self {
                 inner,
-                runtime: tokio::runtime::Runtime::new().map_err(|e| {
-                    ClientError::InternalError {
-                        message: e.to_string(),
-                    }
-                })?,
+                runtime: self.runtime.clone(),
             }))
         }
         .compat()
@@ -3101,7 +3702,7 @@ impl Client {
     /// List projects, optionally filtered by name (async).
     pub async fn projects_async(&self, name: Option<String>) -> Result<Vec<Project>, ClientError> {
         async {
-            let projects = self.inner.projects(name.as_deref()).await?;
+            let projects = self.inner.projects(name.as_deref(), None, false).await?;
             Ok(projects.into_iter().map(Project::from).collect())
         }
         .compat()
@@ -3127,7 +3728,7 @@ impl Client {
         async {
             let datasets = self
                 .inner
-                .datasets(project_id.into(), name.as_deref())
+                .datasets(Some(project_id.into()), name.as_deref(), None, false)
                 .await?;
             Ok(datasets.into_iter().map(Dataset::from).collect())
         }
@@ -3145,6 +3746,52 @@ impl Client {
         .await
     }
 
+    /// Download the files of a dataset to `output` (async).
+    ///
+    /// See `download_dataset` for the arguments and callbacks.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn download_dataset_async(
+        &self,
+        dataset_id: DatasetId,
+        groups: Vec<String>,
+        file_types: Vec<FileType>,
+        output: String,
+        flatten: bool,
+        skip_existing_by_size: bool,
+        concurrency: Option<u32>,
+        verify_checksums: bool,
+        progress: Option<Box<dyn ProgressCallback>>,
+        files: Option<Box<dyn FileProgressCallback>>,
+        phases: Option<Box<dyn PhaseCallback>>,
+    ) -> Result<(), ClientError> {
+        let file_types: Vec<core::FileType> = file_types.into_iter().map(Into::into).collect();
+        let progress = spawn_phased_progress_bridge(&self.runtime, progress, phases);
+        let files = files.map(|cb| spawn_file_event_bridge(&self.runtime, cb));
+        async {
+            Ok(self
+                .inner
+                .download_dataset_with_file_events(
+                    dataset_id.into(),
+                    &groups,
+                    &core::FileType::expand_types(&file_types),
+                    output.into(),
+                    flatten,
+                    progress,
+                    files,
+                    None,
+                    core::DownloadOptions {
+                        concurrency: concurrency.map(|n| n as usize),
+                        verify_checksums,
+                        skip_existing_by_size,
+                        ..Default::default()
+                    },
+                )
+                .await?)
+        }
+        .compat()
+        .await
+    }
+
     /// Get annotation sets for a dataset, optionally at a specific version (async).
     pub async fn annotation_sets_async(
         &self,
@@ -3179,6 +3826,21 @@ impl Client {
         .await
     }
 
+    /// Get the samples of a dataset matching `query` (async).
+    pub async fn samples_async(
+        &self,
+        dataset_id: DatasetId,
+        query: SamplesQuery,
+    ) -> Result<Vec<Sample>, ClientError> {
+        async {
+            let query = core::SamplesQuery::try_from(query)?;
+            let samples = self.inner.samples(dataset_id.into(), None, query).await?;
+            Ok(samples.into_iter().map(Sample::from).collect())
+        }
+        .compat()
+        .await
+    }
+
     /// Update image dimensions for existing samples (async).
     pub async fn update_sample_dimensions_async(
         &self,
@@ -3227,7 +3889,7 @@ impl Client {
         async {
             let experiments = self
                 .inner
-                .experiments(project_id.into(), name.as_deref())
+                .experiments(Some(project_id.into()), name.as_deref(), None, false)
                 .await?;
             Ok(experiments.into_iter().map(Experiment::from).collect())
         }
@@ -3245,6 +3907,34 @@ impl Client {
         .await
     }
 
+    /// Create an experiment in a project (async).
+    pub async fn create_experiment_async(
+        &self,
+        project_id: ProjectId,
+        name: String,
+        description: Option<String>,
+    ) -> Result<Experiment, ClientError> {
+        async {
+            let experiment = self
+                .inner
+                .create_experiment(project_id.into(), &name, description.as_deref())
+                .await?;
+            Ok(experiment.into())
+        }
+        .compat()
+        .await
+    }
+
+    /// Delete an experiment by ID (async).
+    pub async fn delete_experiment_async(&self, id: ExperimentId) -> Result<(), ClientError> {
+        async {
+            self.inner.delete_experiment(id.into()).await?;
+            Ok(())
+        }
+        .compat()
+        .await
+    }
+
     /// List training sessions in an experiment (async).
     pub async fn training_sessions_async(
         &self,
@@ -3294,7 +3984,10 @@ impl Client {
         project_id: ProjectId,
     ) -> Result<Vec<Arc<ValidationSession>>, ClientError> {
         async {
-            let sessions = self.inner.validation_sessions(project_id.into()).await?;
+            let sessions = self
+                .inner
+                .validation_sessions(Some(project_id.into()))
+                .await?;
             Ok(sessions
                 .into_iter()
                 .map(|s| Arc::new(ValidationSession::new(s)))
@@ -3349,6 +4042,17 @@ impl Client {
         .await
     }
 
+    /// Renew the session token (async), returning the new expiry as an
+    /// RFC 3339 string.
+    pub async fn renew_token_async(&self) -> Result<String, ClientError> {
+        async {
+            let expires = self.inner.renew_token().await?;
+            Ok(expires.to_rfc3339())
+        }
+        .compat()
+        .await
+    }
+
     /// Clear authentication token and log out (async).
     pub async fn logout_async(&self) -> Result<(), ClientError> {
         async {
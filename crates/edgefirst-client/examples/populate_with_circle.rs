@@ -43,7 +43,7 @@ async fn main() -> Result<(), Error> {
 
     // Find the project
     println!("\nFinding project '{}'...", project_name);
-    let projects = client.projects(Some(project_name)).await?;
+    let projects = client.projects(Some(project_name), None, false).await?;
     if projects.is_empty() {
         eprintln!("Error: Project '{}' not found", project_name);
         std::process::exit(1);
@@ -59,7 +59,9 @@ async fn main() -> Result<(), Error> {
 
     // Find the dataset
     println!("\nFinding dataset '{}'...", dataset_name);
-    let datasets = client.datasets(project.id(), Some(dataset_name)).await?;
+    let datasets = client
+        .datasets(Some(project.id()), Some(dataset_name), None, false)
+        .await?;
 
     let dataset = datasets.first().ok_or_else(|| {
         Error::InvalidParameters(format!(
@@ -188,8 +190,10 @@ async fn main() -> Result<(), Error> {
             Some(annotation_set.id()),
             vec![sample],
             Some(tx),
+            None,
         )
         .await
+        .and_then(|report| report.into_result())
     {
         Ok(results) => {
             println!("✓ API call successful!");
@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 // Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
 
-use crate::{AnnotationSet, Client, Dataset, Error, Progress, Sample, client};
+use crate::{
+    AnnotationSet, Client, Dataset, Error, Progress, Sample, client,
+    compression::{UploadSource, encoded_part},
+};
 use chrono::{DateTime, Utc};
-use log::trace;
+use log::{debug, trace};
 use reqwest::multipart::{Form, Part};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{collections::HashMap, fmt::Display, path::PathBuf, str::FromStr};
@@ -66,8 +69,9 @@ pub enum Parameter {
     Object(HashMap<String, Parameter>),
 }
 
+/// Token returned by `auth.login`, `auth.refresh` and `auth.verify_token`.
 #[derive(Deserialize)]
-pub struct LoginResult {
+pub struct TokenResponse {
     pub(crate) token: String,
 }
 
@@ -480,6 +484,79 @@ typeid!(
     "se"
 );
 
+/// Ordering for project, dataset and experiment listings.
+///
+/// Listings are sorted client-side as the list RPCs have no ordering
+/// parameter. Projects and experiments carry no creation timestamp, so
+/// [`SortKey::Created`] orders them by ID, which the server assigns in
+/// creation order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Case-insensitive by name.
+    Name,
+    /// Oldest first.
+    Created,
+}
+
+/// A window into a listing: skip `offset` items, then take up to `limit`.
+///
+/// The default is the first 50 items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub limit: usize,
+    pub offset: usize,
+}
+
+impl Page {
+    pub fn new(limit: usize, offset: usize) -> Self {
+        Page { limit, offset }
+    }
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Page {
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+/// One [`Page`] of a listing.
+///
+/// `total` counts every item matching the listing's filter, not only those
+/// in `items`. `next_offset` is the offset of the following page, or `None`
+/// when this page reaches the end.
+#[derive(Debug, Clone)]
+pub struct PagedResult<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub next_offset: Option<usize>,
+}
+
+impl<T> PagedResult<T> {
+    /// Cuts `page` out of the complete, already ordered listing `items`.
+    pub(crate) fn from_listing(items: Vec<T>, page: Page) -> Self {
+        let total = items.len();
+        let items: Vec<T> = items
+            .into_iter()
+            .skip(page.offset)
+            .take(page.limit)
+            .collect();
+        let end = page.offset.saturating_add(items.len());
+        PagedResult {
+            next_offset: (!items.is_empty() && end < total).then_some(end),
+            items,
+            total,
+        }
+    }
+
+    /// Whether items remain after this page.
+    pub fn has_more(&self) -> bool {
+        self.next_offset.is_some()
+    }
+}
+
 /// The project class represents a project in the EdgeFirst Studio.  A project
 /// contains datasets, experiments, and other resources related to a specific
 /// task or workflow.
@@ -514,7 +591,7 @@ impl Project {
         client: &client::Client,
         name: Option<&str>,
     ) -> Result<Vec<Dataset>, Error> {
-        client.datasets(self.id, name).await
+        client.datasets(Some(self.id), name, None, false).await
     }
 
     pub async fn experiments(
@@ -522,13 +599,26 @@ impl Project {
         client: &client::Client,
         name: Option<&str>,
     ) -> Result<Vec<Experiment>, Error> {
-        client.experiments(self.id, name).await
+        client.experiments(Some(self.id), name, None, false).await
     }
 }
 
 #[derive(Deserialize, Debug)]
 pub struct SamplesCountResult {
     pub total: u64,
+    /// Sample count per group name. Filled by
+    /// [`Client::samples_count_by_group`](crate::Client::samples_count_by_group);
+    /// empty for a plain `samples_count`.
+    #[serde(default)]
+    pub groups: HashMap<String, u64>,
+}
+
+impl SamplesCountResult {
+    /// Number of samples in the group `name`, or 0 when the group has none
+    /// or the breakdown was not requested.
+    pub fn group_count(&self, name: &str) -> u64 {
+        self.groups.get(name).copied().unwrap_or(0)
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -572,6 +662,42 @@ pub struct SamplesUpdateDimensionsParams {
     pub samples: Vec<SampleDimensionUpdate>,
 }
 
+/// Parameters for the `samples.update` API call. Fields left as `None` are
+/// not sent and keep their value on the server.
+#[derive(Serialize, Clone, Debug)]
+pub struct SamplesUpdateParams {
+    pub sample_id: SampleID,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_number: Option<u32>,
+}
+
+/// Parameters for the `samples.presign` API call.
+#[derive(Serialize, Clone, Debug)]
+pub struct SamplesPresignParams {
+    pub dataset_id: DatasetID,
+    pub files: Vec<PresignFile>,
+}
+
+/// A file to presign an upload URL for.
+#[derive(Serialize, Clone, Debug)]
+pub struct PresignFile {
+    pub filename: String,
+    #[serde(rename = "type")]
+    pub file_type: String,
+    /// Storage key of an earlier URL for this file, so the new URL targets
+    /// the same object instead of a new one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
 /// Result from the `samples.update_dimensions` API call.
 #[derive(Deserialize, Debug)]
 pub struct SamplesUpdateDimensionsResult {
@@ -605,6 +731,141 @@ pub struct SamplesPopulateResult {
     pub urls: Vec<PresignedUrl>,
 }
 
+/// A sample whose files could not be uploaded during a populate call.
+///
+/// The server has already created the sample by the time its uploads run, so
+/// a failed sample exists in the dataset but is missing some or all of its
+/// files.
+#[derive(Debug, Clone)]
+pub struct SampleError {
+    /// UUID of the sample that failed
+    pub uuid: String,
+    /// Image name of the sample, if one was given
+    pub image_name: Option<String>,
+    /// Description of the first upload failure for this sample
+    pub message: String,
+}
+
+impl Display for SampleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.image_name {
+            Some(name) => write!(f, "{} ({}): {}", self.uuid, name, self.message),
+            None => write!(f, "{}: {}", self.uuid, self.message),
+        }
+    }
+}
+
+/// Per-sample outcome of
+/// [`Client::populate_samples`](crate::Client::populate_samples).
+///
+/// File uploads are tracked per sample, so one sample failing to upload does
+/// not hide the samples that went through. Failures of the populate request
+/// itself are still returned as an [`Error`] for the whole call.
+#[derive(Debug, Clone, Default)]
+pub struct PopulateReport {
+    /// Samples that were created with all their files uploaded, in server
+    /// order
+    pub succeeded: Vec<SamplesPopulateResult>,
+    /// Samples whose file uploads failed
+    pub failed: Vec<SampleError>,
+}
+
+impl PopulateReport {
+    /// Returns `true` when every sample succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Converts the report into the successful results, or
+    /// [`Error::PopulateFailed`] if any sample failed.
+    pub fn into_result(self) -> Result<Vec<SamplesPopulateResult>, Error> {
+        match self.failed.first() {
+            None => Ok(self.succeeded),
+            Some(first) => Err(Error::PopulateFailed {
+                failed: self.failed.len(),
+                total: self.failed.len() + self.succeeded.len(),
+                first_error: first.to_string(),
+            }),
+        }
+    }
+}
+
+/// How much a [`ValidationIssue`] matters for training.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth a look, but training can go ahead.
+    Warning,
+    /// Training on the dataset will fail or give wrong results.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// The problem a [`ValidationIssue`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationIssueKind {
+    /// A sample has no image width or height, so pixel and normalized
+    /// coordinates cannot be converted.
+    MissingDimensions,
+    /// A dataset label that no annotation in the annotation set uses.
+    UnusedLabel,
+    /// A sample with no annotations in the annotation set.
+    UnannotatedSample,
+    /// A dataset group with no samples.
+    EmptyGroup,
+}
+
+impl ValidationIssueKind {
+    /// The severity issues of this kind are reported with.
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssueKind::MissingDimensions => Severity::Error,
+            ValidationIssueKind::UnusedLabel
+            | ValidationIssueKind::UnannotatedSample
+            | ValidationIssueKind::EmptyGroup => Severity::Warning,
+        }
+    }
+}
+
+/// A structural problem found by
+/// [`Client::validate_dataset`](crate::Client::validate_dataset).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// How much the issue matters for training
+    pub severity: Severity,
+    /// What kind of problem was found
+    pub kind: ValidationIssueKind,
+    /// The sample, label or group the issue is about: the sample name (or
+    /// UUID when it has none), the label name or the group name
+    pub subject: String,
+    /// Human-readable description of the issue
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub(crate) fn new(kind: ValidationIssueKind, subject: String, message: String) -> Self {
+        ValidationIssue {
+            severity: kind.severity(),
+            kind,
+            subject,
+            message,
+        }
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
 /// A presigned URL for uploading a file to S3.
 #[derive(Deserialize, Debug, Clone)]
 pub struct PresignedUrl {
@@ -616,6 +877,19 @@ pub struct PresignedUrl {
     pub url: String,
 }
 
+/// Parameters for the `samples.add_files` API.
+///
+/// Links additional sensor files to an existing sample. `files` maps the
+/// server file type (e.g. `lidar.pcd`) to the basename being uploaded; the
+/// response is a [`SamplesPopulateResult`] carrying one presigned URL per
+/// file.
+#[derive(Serialize, Clone, Debug)]
+pub struct SamplesAddFilesParams {
+    pub sample_id: SampleID,
+    pub presigned_urls: bool,
+    pub files: HashMap<String, String>,
+}
+
 // ============================================================================
 // Annotation API Types
 // ============================================================================
@@ -625,8 +899,9 @@ pub struct PresignedUrl {
 /// This struct represents annotations in the format expected by the server,
 /// which differs from our client-side `Annotation` struct. Key differences:
 /// - Uses `image_id` (server) vs `sample_id` (client)
-/// - Uses `type` string ("box", "seg") vs `AnnotationType` enum
-/// - Coordinates are stored as separate `x`, `y`, `w`, `h` fields
+/// - Uses `type` string ("box", "3dbox", "seg") vs `AnnotationType` enum
+/// - Coordinates are stored as separate `x`, `y`, `w`, `h` fields, plus `z`
+///   and `l` for 3D boxes
 /// - Polygon is stored as a JSON string
 #[derive(Serialize, Clone, Debug)]
 pub struct ServerAnnotation {
@@ -639,7 +914,8 @@ pub struct ServerAnnotation {
     /// Label name (alternative to label_id)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label_name: Option<String>,
-    /// Annotation type: "box" for bounding box, "seg" for segmentation
+    /// Annotation type: "box" for bounding box, "3dbox" for 3D box, "seg" for
+    /// segmentation
     #[serde(rename = "type")]
     pub annotation_type: String,
     /// Bounding box X coordinate (normalized 0-1, center)
@@ -650,6 +926,12 @@ pub struct ServerAnnotation {
     pub w: f64,
     /// Bounding box height (normalized 0-1)
     pub h: f64,
+    /// 3D box Z coordinate (center), only for "3dbox"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z: Option<f64>,
+    /// 3D box length, only for "3dbox"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub l: Option<f64>,
     /// Confidence score (0-1)
     pub score: f64,
     /// Polygon data as JSON string (for segmentation)
@@ -662,6 +944,84 @@ pub struct ServerAnnotation {
     /// Object tracking reference (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_reference: Option<String>,
+    /// Free-form annotation attributes such as `occluded` or `truncated`
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, serde_json::Value>,
+}
+
+/// An annotation that [`Client::upload_annotations`](crate::Client::upload_annotations)
+/// did not send.
+#[derive(Debug, Clone)]
+pub struct AnnotationError {
+    /// Position of the annotation in the uploaded list
+    pub index: usize,
+    /// Why the annotation was left out
+    pub message: String,
+}
+
+impl Display for AnnotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "annotation {}: {}", self.index, self.message)
+    }
+}
+
+/// Per-annotation outcome of
+/// [`Client::upload_annotations`](crate::Client::upload_annotations).
+///
+/// Annotations that cannot be matched to a sample or converted for the
+/// server are collected here instead of failing the call. Failures of the
+/// upload requests themselves are still returned as an [`Error`].
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationUploadReport {
+    /// Number of annotations added to the set
+    pub uploaded: usize,
+    /// Annotations that were not sent, in input order
+    pub failed: Vec<AnnotationError>,
+}
+
+impl AnnotationUploadReport {
+    /// Returns `true` when every annotation was uploaded.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Changes to a tracked object's annotations for
+/// [`Client::update_annotations_by_object`](crate::Client::update_annotations_by_object).
+///
+/// Fields left `None` keep their current value. With `sample_id` set the
+/// changes apply to the object's annotation on that frame only, otherwise
+/// to every frame the object appears in.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationChanges {
+    /// Frame to change, or `None` for every frame of the object
+    pub sample_id: Option<SampleID>,
+    /// New label name
+    pub label: Option<String>,
+    /// New 2D box
+    pub box2d: Option<crate::Box2d>,
+    /// New 3D box
+    pub box3d: Option<crate::Box3d>,
+    /// New polygon
+    pub polygon: Option<crate::Polygon>,
+}
+
+impl AnnotationChanges {
+    /// Applies the changes to `annotation`.
+    pub(crate) fn apply(&self, annotation: &mut crate::Annotation) {
+        if let Some(label) = &self.label {
+            annotation.set_label(Some(label.clone()));
+        }
+        if let Some(box2d) = &self.box2d {
+            annotation.set_box2d(Some(box2d.clone()));
+        }
+        if let Some(box3d) = &self.box3d {
+            annotation.set_box3d(Some(box3d.clone()));
+        }
+        if let Some(polygon) = &self.polygon {
+            annotation.set_polygon(Some(polygon.clone()));
+        }
+    }
 }
 
 /// Parameters for the `annotation.add_bulk` API.
@@ -728,6 +1088,15 @@ impl Snapshot {
         &self.status
     }
 
+    /// The snapshot status parsed into a [`SnapshotStatus`].
+    ///
+    /// [`status`](Self::status) keeps the raw server string, which is the
+    /// only way to tell apart statuses that map to
+    /// [`SnapshotStatus::Other`].
+    pub fn status_kind(&self) -> SnapshotStatus {
+        SnapshotStatus::from(self.status.as_str())
+    }
+
     pub fn path(&self) -> &str {
         &self.path
     }
@@ -737,6 +1106,59 @@ impl Snapshot {
     }
 }
 
+/// Lifecycle state of a [`Snapshot`].
+///
+/// Parsing is case-insensitive and never fails: the server reports a
+/// finished snapshot as either `available` or `completed`, and a failed one
+/// as either `failed` or `error`; any other status becomes
+/// [`SnapshotStatus::Other`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotStatus {
+    /// Queued and not yet being processed.
+    Pending,
+    /// Being uploaded, converted or restored.
+    Processing,
+    /// Ready to download or restore.
+    Available,
+    /// Processing failed.
+    Failed,
+    /// A status this client version does not recognize.
+    Other,
+}
+
+impl From<&str> for SnapshotStatus {
+    fn from(s: &str) -> Self {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pending" => SnapshotStatus::Pending,
+            "processing" => SnapshotStatus::Processing,
+            "available" | "completed" => SnapshotStatus::Available,
+            "failed" | "error" => SnapshotStatus::Failed,
+            _ => SnapshotStatus::Other,
+        }
+    }
+}
+
+impl FromStr for SnapshotStatus {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl Display for SnapshotStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let value = match self {
+            SnapshotStatus::Pending => "pending",
+            SnapshotStatus::Processing => "processing",
+            SnapshotStatus::Available => "available",
+            SnapshotStatus::Failed => "failed",
+            SnapshotStatus::Other => "other",
+        };
+        write!(f, "{}", value)
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct SnapshotRestore {
     pub project_id: ProjectID,
@@ -840,6 +1262,29 @@ impl Experiment {
     ) -> Result<Vec<TrainingSession>, Error> {
         client.training_sessions(self.id, name).await
     }
+
+    /// Summarizes the training sessions of this experiment by `metric`, see
+    /// [`Client::experiment_summary`](crate::Client::experiment_summary).
+    pub async fn summary(
+        &self,
+        client: &client::Client,
+        metric: &str,
+    ) -> Result<ExperimentSummary, Error> {
+        client.experiment_summary(self.id, metric).await
+    }
+}
+
+/// Training sessions of an experiment ranked by one metric, from
+/// [`Client::experiment_summary`](crate::Client::experiment_summary).
+#[derive(Clone)]
+pub struct ExperimentSummary {
+    /// Number of training sessions in the experiment.
+    pub session_count: usize,
+    /// Session with the highest value of the metric, `None` when no session
+    /// has published it.
+    pub best_session: Option<TrainingSession>,
+    /// The metric value of `best_session`.
+    pub best_metric: Option<f64>,
 }
 
 #[derive(Serialize, Debug)]
@@ -854,7 +1299,7 @@ pub struct PublishMetrics {
     pub metrics: HashMap<String, Parameter>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 struct TrainingSessionParams {
     #[serde(default)]
     model_params: HashMap<String, Parameter>,
@@ -862,7 +1307,7 @@ struct TrainingSessionParams {
     dataset_params: DatasetParams,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct TrainingSession {
     id: TrainingSessionID,
     #[serde(rename = "trainer_id")]
@@ -1010,14 +1455,22 @@ impl TrainingSession {
     /// Uploads an artifact to the training session.  The filename will
     /// be used as the name of the file in the training session while path is
     /// the local path to the file to upload.
+    ///
+    /// Files larger than [`Client::upload_part_size`] are uploaded in parts;
+    /// see [`upload`](Self::upload). `progress` receives the bytes uploaded.
     pub async fn upload_artifact(
         &self,
         client: &client::Client,
         filename: &str,
         path: PathBuf,
+        progress: Option<tokio::sync::mpsc::Sender<Progress>>,
     ) -> Result<(), Error> {
-        self.upload(client, &[(format!("artifacts/{}", filename), path)])
-            .await
+        self.upload(
+            client,
+            &[(format!("artifacts/{}", filename), path)],
+            progress,
+        )
+        .await
     }
 
     /// Downloads a checkpoint file from the training session.
@@ -1038,14 +1491,22 @@ impl TrainingSession {
     /// Uploads a checkpoint file to the training session.  The filename will
     /// be used as the name of the file in the training session while path is
     /// the local path to the file to upload.
+    ///
+    /// Files larger than [`Client::upload_part_size`] are uploaded in parts;
+    /// see [`upload`](Self::upload). `progress` receives the bytes uploaded.
     pub async fn upload_checkpoint(
         &self,
         client: &client::Client,
         filename: &str,
         path: PathBuf,
+        progress: Option<tokio::sync::mpsc::Sender<Progress>>,
     ) -> Result<(), Error> {
-        self.upload(client, &[(format!("checkpoints/{}", filename), path)])
-            .await
+        self.upload(
+            client,
+            &[(format!("checkpoints/{}", filename), path)],
+            progress,
+        )
+        .await
     }
 
     /// Downloads a file from the training session.  Should only be used for
@@ -1068,10 +1529,124 @@ impl TrainingSession {
             .await
     }
 
+    /// Uploads files to the training session, each `(name, path)` pair
+    /// storing the local file `path` as `name`.
+    ///
+    /// Files up to [`Client::upload_part_size`] are posted together in one
+    /// request. Larger files, such as model weights, go straight to storage
+    /// through a presigned multipart upload whose parts are retried on their
+    /// own. Re-running an interrupted upload resumes it when the server still
+    /// holds its parts. Servers without multipart training uploads receive
+    /// the larger files in a `trainer.upload.files` request as well.
+    ///
+    /// `progress` receives `Progress { current, total, status: None }`
+    /// events where `total` is the size of all files on disk and `current`
+    /// the bytes uploaded so far; a final `current == total` event follows a
+    /// successful upload.
     pub async fn upload(
         &self,
         client: &client::Client,
         files: &[(String, PathBuf)],
+        progress: Option<tokio::sync::mpsc::Sender<Progress>>,
+    ) -> Result<(), Error> {
+        use std::sync::{Arc, atomic::AtomicUsize};
+
+        let mut total = 0;
+        let mut posted = Vec::new();
+        let mut chunked = Vec::new();
+        for (name, path) in files {
+            let len = tokio::fs::metadata(path).await?.len() as usize;
+            total += len;
+            if len > client.upload_part_size() {
+                chunked.push((name, path, len));
+            } else {
+                posted.push((name, path, len));
+            }
+        }
+        let current = Arc::new(AtomicUsize::new(0));
+
+        self.post_files_with_progress(client, &posted, total, &current, &progress)
+            .await?;
+
+        let mut chunked = chunked.into_iter();
+        let mut unsupported = Vec::new();
+        for (name, path, len) in chunked.by_ref() {
+            match client
+                .upload_training_file(
+                    self.id(),
+                    name,
+                    path,
+                    total,
+                    current.clone(),
+                    progress.clone(),
+                )
+                .await
+            {
+                Ok(()) => {}
+                Err(Error::UnsupportedByServer { feature }) => {
+                    debug!(
+                        "{} is not supported by the server; posting {} instead",
+                        feature, name
+                    );
+                    unsupported.push((name, path, len));
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        unsupported.extend(chunked);
+        self.post_files_with_progress(client, &unsupported, total, &current, &progress)
+            .await?;
+
+        if let Some(tx) = progress {
+            let _ = tx
+                .send(Progress {
+                    current: total,
+                    total,
+                    status: None,
+                    ..Default::default()
+                })
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Posts `files` with [`post_files`][Self::post_files], if there are
+    /// any, and reports their bytes to `progress` once they are stored.
+    async fn post_files_with_progress(
+        &self,
+        client: &client::Client,
+        files: &[(&String, &PathBuf, usize)],
+        total: usize,
+        current: &std::sync::atomic::AtomicUsize,
+        progress: &Option<tokio::sync::mpsc::Sender<Progress>>,
+    ) -> Result<(), Error> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        let paths: Vec<_> = files.iter().map(|(name, path, _)| (*name, *path)).collect();
+        self.post_files(client, &paths).await?;
+
+        let bytes: usize = files.iter().map(|(_, _, len)| len).sum();
+        let current = current.fetch_add(bytes, std::sync::atomic::Ordering::SeqCst) + bytes;
+        if let Some(tx) = progress {
+            let _ = tx
+                .send(Progress {
+                    current,
+                    total,
+                    status: None,
+                    ..Default::default()
+                })
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Posts `files` in one `trainer.upload.files` multipart request.
+    async fn post_files(
+        &self,
+        client: &client::Client,
+        files: &[(&String, &PathBuf)],
     ) -> Result<(), Error> {
         let mut parts = Form::new().part(
             "params",
@@ -1079,8 +1654,20 @@ impl TrainingSession {
         );
 
         for (name, path) in files {
-            let file_part = Part::file(path).await?.file_name(name.to_owned());
-            parts = parts.part("file", file_part);
+            let file_part = match client.upload_compression() {
+                Some(level) => {
+                    let source = UploadSource::open(path, Some(level)).await?;
+                    let body = reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(
+                        source.reader,
+                    ));
+                    encoded_part(
+                        Part::stream_with_length(body, source.len as u64),
+                        source.gzip,
+                    )
+                }
+                None => Part::file(path).await?,
+            };
+            parts = parts.part("file", file_part.file_name(name.to_string()));
         }
 
         let result = client.post_multipart("trainer.upload.files", parts).await?;
@@ -1240,6 +1827,9 @@ impl ValidationSession {
     ///   total, status: None }` events as bytes are streamed to the server.
     ///   `total` equals the sum of all file sizes in bytes; `current` tracks
     ///   aggregate bytes sent across all files using a shared atomic counter.
+    ///   Text files are gzip-compressed when
+    ///   [`Client::with_upload_compression`] is set, and then count by their
+    ///   compressed size.
     ///
     /// # Returns
     /// `Ok(())` on success.
@@ -1261,14 +1851,14 @@ impl ValidationSession {
         };
         use tokio_util::io::ReaderStream;
 
-        // Pre-compute total size across all files.
+        // Pre-compute total size across all files, as sent after any
+        // compression.
         let mut total: usize = 0;
         let mut file_meta = Vec::with_capacity(files.len());
         for (name, path) in files {
-            let f = tokio::fs::File::open(path).await?;
-            let len = f.metadata().await?.len() as usize;
-            total += len;
-            file_meta.push((name.clone(), f, len));
+            let source = UploadSource::open(path, client.upload_compression()).await?;
+            total += source.len;
+            file_meta.push((name.clone(), source));
         }
 
         // Shared atomic counter so all file parts bump the same sent counter.
@@ -1279,8 +1869,8 @@ impl ValidationSession {
             form = form.text("folder", folder.to_owned());
         }
 
-        for (name, file, len) in file_meta {
-            let reader_stream = ReaderStream::new(file);
+        for (name, source) in file_meta {
+            let reader_stream = ReaderStream::new(source.reader);
             let sent_clone = sent.clone();
             let progress_clone = progress.clone();
             let progress_stream = reader_stream.inspect(move |chunk_result| {
@@ -1296,20 +1886,21 @@ impl ValidationSession {
                             current,
                             total,
                             status: None,
+                            ..Default::default()
                         });
                     }
                 }
             });
             let body = reqwest::Body::wrap_stream(progress_stream);
-            let part = Part::stream_with_length(body, len as u64).file_name(name);
-            form = form.part("file", part);
+            let part = Part::stream_with_length(body, source.len as u64).file_name(name);
+            form = form.part("file", encoded_part(part, source.gzip));
         }
 
         let result = match client.post_multipart("val.data.upload", form).await {
             Ok(_) => Ok(()),
-            Err(Error::RpcError(code, msg)) => {
-                Err(client::map_rpc_error("val.data.upload", code, msg, None))
-            }
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error("val.data.upload", code, msg, None)),
             Err(e) => Err(e),
         };
 
@@ -1325,6 +1916,7 @@ impl ValidationSession {
                     current: total,
                     total,
                     status: None,
+                    ..Default::default()
                 })
                 .await;
         }
@@ -1366,9 +1958,9 @@ impl ValidationSession {
             .await
         {
             Ok(()) => Ok(()),
-            Err(Error::RpcError(code, msg)) => {
-                Err(client::map_rpc_error("val.data.download", code, msg, None))
-            }
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error("val.data.download", code, msg, None)),
             Err(e) => Err(e),
         }
     }
@@ -1393,9 +1985,9 @@ impl ValidationSession {
         };
         match client.rpc("val.data.list".to_owned(), Some(&req)).await {
             Ok(r) => Ok(r),
-            Err(Error::RpcError(code, msg)) => {
-                Err(client::map_rpc_error("val.data.list", code, msg, None))
-            }
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error("val.data.list", code, msg, None)),
             Err(e) => Err(e),
         }
     }
@@ -1860,7 +2452,9 @@ impl TaskInfo {
         };
         match client.rpc("task.data.list".to_owned(), Some(&req)).await {
             Ok(r) => Ok(r),
-            Err(Error::RpcError(code, msg)) => Err(client::map_rpc_error(
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error(
                 "task.data.list",
                 code,
                 msg,
@@ -1881,6 +2475,9 @@ impl TaskInfo {
     /// * `progress` - Optional progress channel. Emits `Progress { current,
     ///   total, status: None }` events as bytes are streamed to the server.
     ///   `total` equals the file size in bytes; `current` tracks bytes sent.
+    ///   A text file is gzip-compressed when
+    ///   [`Client::with_upload_compression`] is set, and `total` is then its
+    ///   compressed size.
     ///
     /// # Returns
     /// `Ok(())` on success.
@@ -1910,11 +2507,11 @@ impl TaskInfo {
             .ok_or_else(|| Error::InvalidParameters("path must have a UTF-8 filename".into()))?
             .to_owned();
 
-        let file = tokio::fs::File::open(path).await?;
-        let total = file.metadata().await?.len() as usize;
+        let source = UploadSource::open(path, client.upload_compression()).await?;
+        let total = source.len;
         let sent = Arc::new(AtomicUsize::new(0));
 
-        let reader_stream = ReaderStream::new(file);
+        let reader_stream = ReaderStream::new(source.reader);
         let sent_clone = sent.clone();
         let progress_clone = progress.clone();
         let progress_stream = reader_stream.inspect(move |chunk_result| {
@@ -1930,13 +2527,17 @@ impl TaskInfo {
                         current,
                         total,
                         status: None,
+                        ..Default::default()
                     });
                 }
             }
         });
 
         let body = reqwest::Body::wrap_stream(progress_stream);
-        let file_part = Part::stream_with_length(body, total as u64).file_name(file_name);
+        let file_part = encoded_part(
+            Part::stream_with_length(body, total as u64).file_name(file_name),
+            source.gzip,
+        );
 
         let mut form = Form::new().text("task_id", self.id().value().to_string());
         if let Some(folder) = folder.filter(|s| !s.is_empty()) {
@@ -1946,7 +2547,9 @@ impl TaskInfo {
 
         let result = match client.post_multipart("task.data.upload", form).await {
             Ok(_) => Ok(()),
-            Err(Error::RpcError(code, msg)) => Err(client::map_rpc_error(
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error(
                 "task.data.upload",
                 code,
                 msg,
@@ -1966,6 +2569,7 @@ impl TaskInfo {
                     current: total,
                     total,
                     status: None,
+                    ..Default::default()
                 })
                 .await;
         }
@@ -2019,7 +2623,9 @@ impl TaskInfo {
             .await
         {
             Ok(()) => Ok(()),
-            Err(Error::RpcError(code, msg)) => Err(client::map_rpc_error(
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error(
                 "task.data.download",
                 code,
                 msg,
@@ -2075,7 +2681,9 @@ impl TaskInfo {
         let _resp: serde_json::Value =
             match client.rpc("task.chart.add".to_owned(), Some(&req)).await {
                 Ok(r) => r,
-                Err(Error::RpcError(code, msg)) => {
+                Err(Error::RpcError {
+                    code, message: msg, ..
+                }) => {
                     return Err(client::map_rpc_error(
                         "task.chart.add",
                         code,
@@ -2115,7 +2723,9 @@ impl TaskInfo {
         };
         match client.rpc("task.chart.list".to_owned(), Some(&req)).await {
             Ok(r) => Ok(r),
-            Err(Error::RpcError(code, msg)) => Err(client::map_rpc_error(
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error(
                 "task.chart.list",
                 code,
                 msg,
@@ -2157,7 +2767,9 @@ impl TaskInfo {
         };
         match client.rpc("task.chart.get".to_owned(), Some(&req)).await {
             Ok(r) => Ok(r),
-            Err(Error::RpcError(code, msg)) => Err(client::map_rpc_error(
+            Err(Error::RpcError {
+                code, message: msg, ..
+            }) => Err(client::map_rpc_error(
                 "task.chart.get",
                 code,
                 msg,
@@ -3111,6 +3723,37 @@ mod tests {
         assert_eq!(value, 333444);
     }
 
+    // ========== SnapshotStatus Tests ==========
+    #[test]
+    fn test_snapshot_status_parses_known_statuses() {
+        let cases = [
+            ("pending", SnapshotStatus::Pending),
+            ("processing", SnapshotStatus::Processing),
+            ("available", SnapshotStatus::Available),
+            ("completed", SnapshotStatus::Available),
+            ("Failed", SnapshotStatus::Failed),
+            ("error", SnapshotStatus::Failed),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(raw.parse::<SnapshotStatus>().unwrap(), expected, "{raw}");
+        }
+        assert_eq!(SnapshotStatus::Available.to_string(), "available");
+    }
+
+    #[test]
+    fn test_snapshot_status_unknown_maps_to_other() {
+        let snapshot: Snapshot = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "description": "drive",
+            "status": "restoring",
+            "path": "",
+            "date": "2026-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(snapshot.status_kind(), SnapshotStatus::Other);
+        assert_eq!(snapshot.status(), "restoring");
+    }
+
     // ========== TaskID Tests ==========
     #[test]
     fn test_task_id_from_u64() {
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! Local cache for downloaded sample files.
+//!
+//! Enabled with [`Client::with_cache_dir`](crate::Client::with_cache_dir).
+//! Files are stored under the cache directory by the SHA-1 of their storage
+//! key, the download URL without its query string, together with the
+//! object's `ETag`. Presigned URLs carry a fresh signature in the query on
+//! every listing, so keying on the full URL would never hit; the path
+//! identifies the stored object and the `ETag` its current contents, so a
+//! file replaced under the same key misses the cache and is fetched again.
+//! The entry for the replaced contents stays until
+//! [`Client::clear_cache`](crate::Client::clear_cache) is called.
+
+use crate::Error;
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Directory of cached files, shared by clones of a [`Client`](crate::Client).
+#[derive(Debug)]
+pub(crate) struct FileCache {
+    dir: PathBuf,
+}
+
+impl FileCache {
+    /// Opens the cache at `dir`, creating the directory if needed.
+    pub(crate) fn new(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(FileCache { dir })
+    }
+
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached bytes for `url` with `etag`, or `None` on a miss.
+    pub(crate) async fn get(&self, url: &str, etag: &str) -> Option<Vec<u8>> {
+        fs::read(self.entry_path(url, etag)).await.ok()
+    }
+
+    /// Stores `data` for `url` with `etag`.
+    ///
+    /// The data is written to a temporary file and renamed into place, so
+    /// concurrent downloads of the same key never expose a partial entry.
+    pub(crate) async fn put(&self, url: &str, etag: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.entry_path(url, etag);
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&tmp, data).await?;
+        if let Err(err) = fs::rename(&tmp, &path).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    /// Total size in bytes of the cached files.
+    pub(crate) async fn size(&self) -> Result<u64, Error> {
+        let mut total = 0;
+        let mut entries = fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() && is_cache_file(&entry.file_name()) {
+                total += metadata.len();
+            }
+        }
+        Ok(total)
+    }
+
+    /// Removes every cached file, keeping the directory itself.
+    ///
+    /// Only files named as the cache names them are removed, so other files
+    /// in a directory shared with the cache are left alone.
+    pub(crate) async fn clear(&self) -> Result<(), Error> {
+        let mut entries = fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_file() && is_cache_file(&entry.file_name()) {
+                fs::remove_file(entry.path()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, url: &str, etag: &str) -> PathBuf {
+        let mut hasher = Sha1::new();
+        hasher.update(storage_key(url).as_bytes());
+        hasher.update(b"\n");
+        hasher.update(etag.as_bytes());
+        let name: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(name)
+    }
+}
+
+/// Whether `name` is a cache entry (40 hex digits) or one of the
+/// `<entry>.<uuid>.tmp` files [`FileCache::put`] writes first.
+fn is_cache_file(name: &std::ffi::OsStr) -> bool {
+    let Some(name) = name.to_str() else {
+        return false;
+    };
+    let entry = match name.strip_suffix(".tmp") {
+        Some(tmp) => tmp.split_once('.').map_or(tmp, |(entry, _)| entry),
+        None => name,
+    };
+    entry.len() == 40 && entry.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The part of `url` that identifies the stored object: everything but the
+/// query string and fragment.
+fn storage_key(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_key_ignores_presigned_query() {
+        assert_eq!(
+            storage_key("https://bucket.s3.amazonaws.com/ds/a.jpg?X-Amz-Signature=1"),
+            storage_key("https://bucket.s3.amazonaws.com/ds/a.jpg?X-Amz-Signature=2"),
+        );
+        assert_ne!(
+            storage_key("https://bucket.s3.amazonaws.com/ds/a.jpg"),
+            storage_key("https://bucket.s3.amazonaws.com/ds/b.jpg"),
+        );
+    }
+
+    #[tokio::test]
+    async fn put_get_size_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FileCache::new(dir.path().join("cache")).unwrap();
+        let url = "https://example.com/a.bin?sig=1";
+
+        assert!(cache.get(url, "\"v1\"").await.is_none());
+        cache.put(url, "\"v1\"", b"hello").await.unwrap();
+        assert_eq!(
+            cache
+                .get("https://example.com/a.bin?sig=2", "\"v1\"")
+                .await
+                .as_deref(),
+            Some(&b"hello"[..])
+        );
+        assert!(
+            cache.get(url, "\"v2\"").await.is_none(),
+            "a replaced file must miss"
+        );
+        assert_eq!(cache.size().await.unwrap(), 5);
+
+        cache.clear().await.unwrap();
+        assert!(cache.get(url, "\"v1\"").await.is_none());
+        assert_eq!(cache.size().await.unwrap(), 0);
+        assert!(cache.dir().is_dir());
+    }
+
+    #[tokio::test]
+    async fn clear_keeps_files_the_cache_did_not_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FileCache::new(dir.path()).unwrap();
+        cache
+            .put("https://example.com/a.bin", "\"v1\"", b"cached")
+            .await
+            .unwrap();
+        let stale_tmp = dir
+            .path()
+            .join(format!("{}.{}.tmp", "a".repeat(40), uuid::Uuid::new_v4()));
+        std::fs::write(&stale_tmp, b"partial").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"mine").unwrap();
+        std::fs::write(dir.path().join("a".repeat(40) + ".jpg"), b"mine").unwrap();
+
+        assert_eq!(cache.size().await.unwrap(), 13);
+        cache.clear().await.unwrap();
+
+        let mut left: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["a".repeat(40) + ".jpg", "notes.txt".to_string()]);
+    }
+}
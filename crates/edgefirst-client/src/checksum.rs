@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! Integrity checks for downloaded sample files.
+//!
+//! Storage reports a file's checksum in the `x-amz-checksum-sha256` or
+//! `x-amz-checksum-sha1` header when the object was uploaded with one and
+//! the request asked for it with [`CHECKSUM_MODE`], or when storage sends it
+//! unasked. A presigned URL only accepts [`CHECKSUM_MODE`] if it was signed
+//! with it, see [`checksum_mode_signed`]. The
+//! `ETag` of a single-part upload is its MD5, but multipart uploads use a
+//! different scheme and MD5 is not available here, so `ETag` is not checked.
+//! Studio sample metadata carries no checksums.
+
+use crate::Error;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use reqwest::header::HeaderMap;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Request header asking S3-compatible storage to return the stored checksum
+/// of an object with a `GET`, which it otherwise leaves out.
+pub(crate) const CHECKSUM_MODE: (&str, &str) = ("x-amz-checksum-mode", "ENABLED");
+
+/// Whether the presigned `url` lists [`CHECKSUM_MODE`] in its
+/// `X-Amz-SignedHeaders`.
+///
+/// SigV4 rejects a request carrying an `x-amz-*` header the URL was not
+/// signed with, so the header is only sent when it was.
+pub(crate) fn checksum_mode_signed(url: &str) -> bool {
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    url.query_pairs()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("X-Amz-SignedHeaders"))
+        .any(|(_, headers)| {
+            headers
+                .split(';')
+                .any(|header| header.eq_ignore_ascii_case(CHECKSUM_MODE.0))
+        })
+}
+
+/// A digest storage reported for a file, as lowercase hex.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Checksum {
+    Sha1(String),
+    Sha256(String),
+}
+
+impl Checksum {
+    /// Reads the strongest checksum from the headers of a storage response.
+    /// Malformed values are ignored.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let decode = |name: &str| {
+            let value = headers.get(name)?.to_str().ok()?;
+            STANDARD.decode(value.trim()).ok().map(|bytes| hex(&bytes))
+        };
+        decode("x-amz-checksum-sha256")
+            .filter(|digest| digest.len() == 64)
+            .map(Checksum::Sha256)
+            .or_else(|| {
+                decode("x-amz-checksum-sha1")
+                    .filter(|digest| digest.len() == 40)
+                    .map(Checksum::Sha1)
+            })
+    }
+
+    /// Checks `data` against the digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ChecksumMismatch`] with both digests when they differ.
+    pub(crate) fn verify(&self, data: &[u8]) -> Result<(), Error> {
+        let (expected, actual) = match self {
+            Checksum::Sha1(expected) => (expected, hex(&Sha1::digest(data))),
+            Checksum::Sha256(expected) => (expected, hex(&Sha256::digest(data))),
+        };
+        if *expected == actual {
+            return Ok(());
+        }
+        let algorithm = match self {
+            Checksum::Sha1(_) => "sha1",
+            Checksum::Sha256(_) => "sha256",
+        };
+        Err(Error::ChecksumMismatch {
+            expected: format!("{}:{}", algorithm, expected),
+            actual: format!("{}:{}", algorithm, actual),
+        })
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (*name, value.parse().unwrap()))
+            .map(|(name, value)| (reqwest::header::HeaderName::from_static(name), value))
+            .collect()
+    }
+
+    #[test]
+    fn from_headers_prefers_sha256() {
+        let sha256 = STANDARD.encode(Sha256::digest(b"hello"));
+        let sha1 = STANDARD.encode(Sha1::digest(b"hello"));
+        let checksum = Checksum::from_headers(&headers(&[
+            ("x-amz-checksum-sha1", &sha1),
+            ("x-amz-checksum-sha256", &sha256),
+        ]))
+        .unwrap();
+        assert!(matches!(checksum, Checksum::Sha256(_)));
+        checksum.verify(b"hello").unwrap();
+
+        let checksum = Checksum::from_headers(&headers(&[("x-amz-checksum-sha1", &sha1)])).unwrap();
+        assert_eq!(
+            checksum,
+            Checksum::Sha1("aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d".to_string())
+        );
+        checksum.verify(b"hello").unwrap();
+    }
+
+    #[test]
+    fn checksum_mode_signed_reads_signed_headers() {
+        assert!(checksum_mode_signed(
+            "https://bucket.s3.amazonaws.com/a.jpg?X-Amz-Algorithm=AWS4-HMAC-SHA256\
+             &X-Amz-SignedHeaders=host%3Bx-amz-checksum-mode&X-Amz-Signature=abc"
+        ));
+        assert!(!checksum_mode_signed(
+            "https://bucket.s3.amazonaws.com/a.jpg?X-Amz-Algorithm=AWS4-HMAC-SHA256\
+             &X-Amz-SignedHeaders=host&X-Amz-Signature=abc"
+        ));
+        assert!(!checksum_mode_signed(
+            "https://cdn.example.com/a.jpg?Signature=abc"
+        ));
+        assert!(!checksum_mode_signed("not a url"));
+    }
+
+    #[test]
+    fn from_headers_ignores_etag_and_malformed_values() {
+        assert!(Checksum::from_headers(&headers(&[("etag", "\"5d41402abc4b2a76\"")])).is_none());
+        assert!(
+            Checksum::from_headers(&headers(&[("x-amz-checksum-sha256", "not base64!")])).is_none()
+        );
+    }
+
+    #[test]
+    fn verify_reports_both_digests() {
+        let checksum = Checksum::Sha1("aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d".to_string());
+        let err = checksum.verify(b"world").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ChecksumMismatch { expected, actual }
+                if expected == "sha1:aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d"
+                    && actual == "sha1:7c211433f02071597741e6ff5a8ea34789abbf43"
+        ));
+    }
+}
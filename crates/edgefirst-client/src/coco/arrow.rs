@@ -9,10 +9,10 @@
 use super::{
     convert::{
         box2d_to_coco_bbox, coco_bbox_to_box2d, coco_segmentation_to_mask_data,
-        coco_segmentation_to_polygon, polygon_to_coco_polygon,
+        coco_segmentation_to_polygon, convert_segmentation, polygon_to_coco_polygon,
     },
     reader::CocoReader,
-    types::{CocoImage, CocoIndex, CocoInfo, CocoSegmentation},
+    types::{CocoImage, CocoIndex, CocoInfo, CocoSegmentation, MaskFormat},
     writer::{CocoDatasetBuilder, CocoWriter},
 };
 use crate::{Annotation, Box2d, Error, Polygon, Progress, Sample};
@@ -61,6 +61,9 @@ pub struct ArrowToCocoOptions {
     pub groups: Vec<String>,
     /// Include segmentation masks in output.
     pub include_masks: bool,
+    /// Encoding for segmentation masks. `None` keeps each mask's stored
+    /// encoding: polygons as COCO polygons and PNG masks as RLE.
+    pub mask_format: Option<MaskFormat>,
     /// COCO info section.
     pub info: Option<CocoInfo>,
 }
@@ -70,6 +73,7 @@ impl Default for ArrowToCocoOptions {
         Self {
             groups: vec![],
             include_masks: true,
+            mask_format: None,
             info: None,
         }
     }
@@ -129,6 +133,7 @@ pub async fn coco_to_arrow<P: AsRef<Path>>(
                 current: 0,
                 total: total_images,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -164,6 +169,7 @@ pub async fn coco_to_arrow<P: AsRef<Path>>(
                         current: c,
                         total,
                         status: None,
+                        ..Default::default()
                     })
                     .await;
             }
@@ -182,7 +188,7 @@ pub async fn coco_to_arrow<P: AsRef<Path>>(
     }
 
     // Convert to DataFrame
-    let df = crate::samples_dataframe(&all_samples)?;
+    let df = crate::samples_dataframe(&all_samples, false)?;
 
     // Build schema-level metadata
     let mut metadata: BTreeMap<PlSmallStr, PlSmallStr> = BTreeMap::new();
@@ -447,6 +453,7 @@ pub async fn arrow_to_coco<P: AsRef<Path>>(
                 current: 0,
                 total: total_rows,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -758,6 +765,10 @@ pub async fn arrow_to_coco<P: AsRef<Path>>(
         } else {
             None
         };
+        let segmentation = match (segmentation, options.mask_format) {
+            (Some(seg), Some(format)) => convert_segmentation(seg, format, width, height)?,
+            (segmentation, _) => segmentation,
+        };
 
         // Determine the score: use first non-null from available score columns
         let score: Option<f64> = mask_scores[i]
@@ -792,6 +803,7 @@ pub async fn arrow_to_coco<P: AsRef<Path>>(
                     current: i + 1,
                     total: total_rows,
                     status: None,
+                    ..Default::default()
                 })
                 .await;
             last_progress_update = i;
@@ -807,6 +819,7 @@ pub async fn arrow_to_coco<P: AsRef<Path>>(
                 current: total_rows,
                 total: total_rows,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -1286,6 +1299,8 @@ mod tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1335,6 +1350,8 @@ mod tests {
                     10.0, 10.0, 60.0, 10.0, 60.0, 60.0, 10.0, 60.0,
                 ]])),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1383,6 +1400,8 @@ mod tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1540,6 +1559,8 @@ mod tests {
                     100.0, 50.0, 300.0, 50.0, 300.0, 200.0, 100.0, 200.0,
                 ]])),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             categories: vec![CocoCategory {
                 id: 1,
@@ -1609,6 +1630,8 @@ mod tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 CocoAnnotation {
                     id: large_id,
@@ -1619,6 +1642,8 @@ mod tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             categories: vec![CocoCategory {
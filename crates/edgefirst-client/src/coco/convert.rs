@@ -10,8 +10,8 @@
 //! - **EdgeFirst JSON**: Normalized 0-1, top-left origin for Box2d
 //! - **EdgeFirst Arrow**: Normalized 0-1, center-point for box2d column
 
-use super::types::{CocoCompressedRle, CocoRle, CocoSegmentation};
-use crate::{Box2d, Error, MaskData, Polygon};
+use super::types::{CocoCompressedRle, CocoRle, CocoSegmentation, MaskFormat};
+use crate::{Box2d, Error, Keypoints, MaskData, Polygon, Visibility};
 
 // =============================================================================
 // Bounding Box Conversion
@@ -192,6 +192,145 @@ pub fn polygon_to_coco_polygon(
         .collect()
 }
 
+/// Compute COCO bounding box from polygon contours.
+///
+/// When the server doesn't return bounding box coordinates for segmentation
+/// annotations, we compute them from the polygon bounds.
+pub(crate) fn compute_bbox_from_polygon(
+    polygon: &Polygon,
+    width: u32,
+    height: u32,
+) -> Option<[f64; 4]> {
+    if polygon.rings.is_empty() {
+        return None;
+    }
+
+    let mut min_x = f32::MAX;
+    let mut min_y = f32::MAX;
+    let mut max_x = f32::MIN;
+    let mut max_y = f32::MIN;
+
+    for ring in &polygon.rings {
+        for &(x, y) in ring {
+            if x.is_finite() && y.is_finite() {
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+
+    if min_x == f32::MAX || min_y == f32::MAX {
+        return None;
+    }
+
+    // Convert normalized coordinates to COCO pixel coordinates [x, y, w, h]
+    let x = (min_x * width as f32) as f64;
+    let y = (min_y * height as f32) as f64;
+    let w = ((max_x - min_x) * width as f32) as f64;
+    let h = ((max_y - min_y) * height as f32) as f64;
+
+    if w > 0.0 && h > 0.0 {
+        Some([x, y, w, h])
+    } else {
+        None
+    }
+}
+
+// =============================================================================
+// Keypoint Conversion
+// =============================================================================
+
+/// Convert EdgeFirst `Keypoints` to COCO's flat keypoint list.
+///
+/// # Arguments
+/// * `keypoints` - EdgeFirst `Keypoints` with normalized coordinates
+/// * `image_width` - Image width in pixels
+/// * `image_height` - Image height in pixels
+///
+/// # Returns
+/// `[x1, y1, v1, x2, y2, v2, ...]` in pixels. Points that are not labeled
+/// are written as `0, 0, 0`, as COCO expects.
+pub fn keypoints_to_coco_keypoints(
+    keypoints: &Keypoints,
+    image_width: u32,
+    image_height: u32,
+) -> Vec<f64> {
+    let img_w = image_width as f64;
+    let img_h = image_height as f64;
+
+    keypoints
+        .points
+        .iter()
+        .flat_map(|&(x, y, v)| match v {
+            Visibility::NotLabeled => [0.0, 0.0, 0.0],
+            _ => [x as f64 * img_w, y as f64 * img_h, u8::from(v) as f64],
+        })
+        .collect()
+}
+
+/// Compute COCO bounding box around the labeled keypoints.
+///
+/// Used for keypoint annotations that come without a box or polygon.
+pub(crate) fn compute_bbox_from_keypoints(
+    keypoints: &Keypoints,
+    width: u32,
+    height: u32,
+) -> Option<[f64; 4]> {
+    let rings = vec![
+        keypoints
+            .points
+            .iter()
+            .filter(|(_, _, v)| *v != Visibility::NotLabeled)
+            .map(|&(x, y, _)| (x, y))
+            .collect(),
+    ];
+    compute_bbox_from_polygon(&Polygon::new(rings), width, height)
+}
+
+/// Convert a Polygon to a polygon string for the server API.
+///
+/// The server expects a 3D array format: `[[[x1,y1],[x2,y2],...], ...]`
+/// where each point is an `[x, y]` pair. This matches how the server
+/// parses polygons in `annotations_handler.go`:
+/// ```go
+/// var polygons [][][]float64
+/// json.Unmarshal([]byte(ann.Polygon), &polygons)
+/// ```
+///
+/// **Note:** This function filters out NaN and Infinity values which would
+/// serialize as `null` in JSON and cause parsing failures on the server.
+///
+/// Returns `None` when no ring has 3 points left, so callers send a `box`
+/// instead of a `seg` without a polygon.
+pub(crate) fn polygon_to_polygon_string(polygon: &crate::Polygon) -> Option<String> {
+    let rings = valid_rings(polygon);
+    if rings.is_empty() {
+        return None;
+    }
+    serde_json::to_string(&rings).ok()
+}
+
+/// The rings of `polygon` as `[x, y]` pairs, without NaN or Infinity
+/// points, keeping only rings with at least 3 points left.
+fn valid_rings(polygon: &crate::Polygon) -> Vec<Vec<[f32; 2]>> {
+    // Convert Vec<Vec<(f32, f32)>> to Vec<Vec<[f32; 2]>> for proper JSON
+    // serialization Filter out any NaN or Infinity values which would become
+    // "null" in JSON
+    polygon
+        .rings
+        .iter()
+        .map(|ring| {
+            ring.iter()
+                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                .map(|&(x, y)| [x, y])
+                .collect()
+        })
+        .filter(|ring: &Vec<[f32; 2]>| ring.len() >= 3) // Need at least 3 points for a valid polygon
+        .collect()
+}
+
 // =============================================================================
 // RLE Decoding
 // =============================================================================
@@ -252,7 +391,8 @@ pub fn decode_rle(rle: &CocoRle) -> Result<(Vec<u8>, u32, u32), Error> {
 
 /// Decode LEB128 encoded string to counts array.
 ///
-/// Based on pycocotools encoding.
+/// Based on pycocotools' `rleFrString`: the first three counts are stored
+/// as they are, later ones relative to the count two before them.
 fn decode_leb128(s: &str) -> Result<Vec<u32>, Error> {
     let bytes = s.as_bytes();
     let mut counts = Vec::new();
@@ -292,17 +432,50 @@ fn decode_leb128(s: &str) -> Result<Vec<u32>, Error> {
         counts.push(value);
     }
 
-    // Convert from diff encoding to absolute counts
-    let mut result = Vec::with_capacity(counts.len());
-    let mut prev: i64 = 0;
-    for diff in counts {
-        prev += diff;
-        result.push(prev.max(0) as u32);
+    // From the fourth count on, each value is stored relative to the count
+    // two before it (the previous run of the same colour).
+    let mut result: Vec<u32> = Vec::with_capacity(counts.len());
+    for (i, diff) in counts.into_iter().enumerate() {
+        let count = if i > 2 {
+            diff + result[i - 2] as i64
+        } else {
+            diff
+        };
+        result.push(count.max(0) as u32);
     }
 
     Ok(result)
 }
 
+/// Encode a counts array as a LEB128-style string, the inverse of
+/// [`decode_leb128`] and of pycocotools' `rleToString`.
+fn encode_leb128(counts: &[u32]) -> String {
+    let mut s = String::new();
+    for (i, &count) in counts.iter().enumerate() {
+        let mut value = count as i64;
+        if i > 2 {
+            value -= counts[i - 2] as i64;
+        }
+        loop {
+            let mut chunk = (value & 0x1F) as u8;
+            value >>= 5;
+            let more = if chunk & 0x10 != 0 {
+                value != -1
+            } else {
+                value != 0
+            };
+            if more {
+                chunk |= 0x20;
+            }
+            s.push((chunk + 48) as char);
+            if !more {
+                break;
+            }
+        }
+    }
+    s
+}
+
 /// Decode compressed RLE (LEB128) to binary mask.
 pub fn decode_compressed_rle(compressed: &CocoCompressedRle) -> Result<(Vec<u8>, u32, u32), Error> {
     let counts = decode_leb128(&compressed.counts)?;
@@ -315,6 +488,20 @@ pub fn decode_compressed_rle(compressed: &CocoCompressedRle) -> Result<(Vec<u8>,
     decode_rle(&rle)
 }
 
+/// Encode a binary mask (row-major, 0/1 values) as compressed COCO RLE, the
+/// `counts` string pycocotools writes.
+pub fn encode_compressed_rle(
+    mask: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<CocoCompressedRle, Error> {
+    let rle = encode_rle(mask, width, height)?;
+    Ok(CocoCompressedRle {
+        counts: encode_leb128(&rle.counts),
+        size: rle.size,
+    })
+}
+
 // =============================================================================
 // Contour Extraction
 // =============================================================================
@@ -569,6 +756,143 @@ pub fn encode_rle(mask: &[u8], width: u32, height: u32) -> Result<CocoRle, Error
     })
 }
 
+// =============================================================================
+// Mask Format Conversion
+// =============================================================================
+
+/// How [`rasterize_polygon`] fills a polygon made of several rings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+    /// Even-odd rule over all rings together: a ring inside another cuts a
+    /// hole and self-intersecting rings fill as in an SVG `evenodd` path.
+    EvenOdd,
+    /// Each ring is filled on its own by the even-odd rule and the results
+    /// are merged, COCO's reading of several polygons as disjoint parts of
+    /// one object.
+    Union,
+}
+
+/// Rasterize polygon rings in pixel coordinates into a row-major binary
+/// mask of `width` x `height` (0/1 values).
+///
+/// A pixel is filled when its centre is inside the polygon by `rule`. Rings
+/// with fewer than three points are ignored.
+pub fn rasterize_polygon(
+    rings: &[Vec<(f64, f64)>],
+    width: u32,
+    height: u32,
+    rule: FillRule,
+) -> Vec<u8> {
+    let w = width as usize;
+    let h = height as usize;
+    let mut mask = vec![0u8; w * h];
+    let rings: Vec<&[(f64, f64)]> = rings
+        .iter()
+        .filter(|ring| ring.len() >= 3)
+        .map(Vec::as_slice)
+        .collect();
+    let parts: Vec<&[&[(f64, f64)]]> = match rule {
+        FillRule::EvenOdd => vec![&rings[..]],
+        FillRule::Union => rings.chunks(1).collect(),
+    };
+
+    let mut crossings = Vec::new();
+    for part in parts {
+        for row in 0..h {
+            let y = row as f64 + 0.5;
+            crossings.clear();
+            for ring in part {
+                for (i, &(x0, y0)) in ring.iter().enumerate() {
+                    let (x1, y1) = ring[(i + 1) % ring.len()];
+                    if (y0 <= y) != (y1 <= y) {
+                        crossings.push(x0 + (y - y0) * (x1 - x0) / (y1 - y0));
+                    }
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            // Fill pixels whose centre lies in [enter, exit)
+            for span in crossings.chunks_exact(2) {
+                let start = ((span[0] - 0.5).ceil().max(0.0) as usize).min(w);
+                let end = ((span[1] - 0.5).ceil().max(0.0) as usize).min(w);
+                if start < end {
+                    mask[row * w + start..row * w + end].fill(1);
+                }
+            }
+        }
+    }
+    mask
+}
+
+/// Rasterize a COCO polygon segmentation into COCO RLE.
+///
+/// Rings are filled with [`FillRule::Union`], matching COCO's reading of
+/// multiple polygons as disjoint parts of one object.
+///
+/// # Arguments
+/// * `polygons` - COCO polygon lists in pixel coordinates
+/// * `width` - Image width in pixels
+/// * `height` - Image height in pixels
+pub fn coco_polygon_to_rle(
+    polygons: &[Vec<f64>],
+    width: u32,
+    height: u32,
+) -> Result<CocoRle, Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::CocoError(
+            "cannot rasterize a polygon without the image dimensions".to_string(),
+        ));
+    }
+
+    let rings: Vec<Vec<(f64, f64)>> = polygons
+        .iter()
+        .map(|ring| ring.chunks_exact(2).map(|p| (p[0], p[1])).collect())
+        .collect();
+    let mask = rasterize_polygon(&rings, width, height, FillRule::Union);
+    encode_rle(&mask, width, height)
+}
+
+/// Convert a segmentation to the requested [`MaskFormat`].
+///
+/// Polygons are rasterized with [`coco_polygon_to_rle`] for RLE output, and
+/// RLE masks are traced back to contours for polygon output. Segmentations
+/// already in the requested format are returned unchanged. Returns `None`
+/// when an RLE mask has no contour to emit as a polygon.
+pub fn convert_segmentation(
+    segmentation: CocoSegmentation,
+    format: MaskFormat,
+    image_width: u32,
+    image_height: u32,
+) -> Result<Option<CocoSegmentation>, Error> {
+    let (mask, height, width) = match (format, segmentation) {
+        (MaskFormat::Polygon, seg @ CocoSegmentation::Polygon(_))
+        | (
+            MaskFormat::Rle,
+            seg @ (CocoSegmentation::Rle(_) | CocoSegmentation::CompressedRle(_)),
+        ) => {
+            return Ok(Some(seg));
+        }
+        (MaskFormat::Rle, CocoSegmentation::Polygon(polygons)) => {
+            let rle = coco_polygon_to_rle(&polygons, image_width, image_height)?;
+            return Ok(Some(CocoSegmentation::Rle(rle)));
+        }
+        (MaskFormat::Polygon, CocoSegmentation::Rle(rle)) => decode_rle(&rle)?,
+        (MaskFormat::Polygon, CocoSegmentation::CompressedRle(compressed)) => {
+            decode_compressed_rle(&compressed)?
+        }
+    };
+
+    let polygons: Vec<Vec<f64>> = mask_to_contours(&mask, width, height)
+        .into_iter()
+        .map(|contour| contour.into_iter().flat_map(|(x, y)| [x, y]).collect())
+        .collect();
+    if polygons.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(CocoSegmentation::Polygon(polygons)))
+    }
+}
+
 // =============================================================================
 // Area Calculation
 // =============================================================================
@@ -942,6 +1266,48 @@ mod tests {
         assert_eq!(decoded, mask);
     }
 
+    #[test]
+    fn test_leb128_matches_pycocotools() {
+        // String produced by pycocotools' rleToString for these counts.
+        let counts = vec![100, 50, 25, 40];
+        assert_eq!(encode_leb128(&counts), "T3b1i0F");
+        assert_eq!(decode_leb128("T3b1i0F").unwrap(), counts);
+    }
+
+    #[test]
+    fn test_decode_leb128_counts_relative_to_two_before() {
+        // From the fourth count on, values are stored relative to the count
+        // two before, not to the running total: "O" is -1, so the fourth
+        // count is 3 - 1.
+        assert_eq!(decode_leb128("537O").unwrap(), vec![5, 3, 7, 2]);
+
+        let compressed = CocoCompressedRle {
+            counts: "537O".to_string(),
+            size: [17, 1],
+        };
+        let (mask, height, width) = decode_compressed_rle(&compressed).unwrap();
+        assert_eq!((height, width), (17, 1));
+        assert_eq!(
+            mask,
+            [vec![0; 5], vec![1; 3], vec![0; 7], vec![1; 2]].concat()
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_compressed_rle_roundtrip() {
+        #[rustfmt::skip]
+        let mask = vec![
+            0, 1, 1, 0,
+            1, 1, 0, 0,
+            0, 1, 1, 1,
+        ];
+
+        let compressed = encode_compressed_rle(&mask, 4, 3).unwrap();
+        assert_eq!(compressed.size, [3, 4]);
+        let (decoded, _, _) = decode_compressed_rle(&compressed).unwrap();
+        assert_eq!(decoded, mask);
+    }
+
     #[test]
     fn test_encode_rle_size_mismatch() {
         let mask = vec![0u8; 50];
@@ -950,4 +1316,103 @@ mod tests {
             "Should reject mask length != width * height"
         );
     }
+
+    // =========================================================================
+    // Mask Format Conversion Tests
+    // =========================================================================
+
+    /// Two disjoint 3x3 squares on a 10x10 image: (1,1)-(4,4) and (6,5)-(9,8).
+    fn two_ring_polygon() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 1.0, 4.0, 1.0, 4.0, 4.0, 1.0, 4.0],
+            vec![6.0, 5.0, 9.0, 5.0, 9.0, 8.0, 6.0, 8.0],
+        ]
+    }
+
+    #[test]
+    fn test_coco_polygon_to_rle_fills_every_ring() {
+        let rle = coco_polygon_to_rle(&two_ring_polygon(), 10, 10).unwrap();
+        assert_eq!(rle.size, [10, 10]);
+
+        let (mask, _, _) = decode_rle(&rle).unwrap();
+        assert_eq!(mask.iter().filter(|&&v| v == 1).count(), 18);
+        for (x, y) in [(1, 1), (3, 3), (6, 5), (8, 7)] {
+            assert_eq!(mask[y * 10 + x], 1, "pixel ({x}, {y}) should be set");
+        }
+        for (x, y) in [(0, 0), (4, 4), (5, 5), (9, 8)] {
+            assert_eq!(mask[y * 10 + x], 0, "pixel ({x}, {y}) should be clear");
+        }
+    }
+
+    #[test]
+    fn test_convert_segmentation_multi_ring_to_rle() {
+        let seg = CocoSegmentation::Polygon(two_ring_polygon());
+        let converted = convert_segmentation(seg, MaskFormat::Rle, 10, 10)
+            .unwrap()
+            .unwrap();
+
+        let json = serde_json::to_value(&converted).unwrap();
+        assert_eq!(json["size"], serde_json::json!([10, 10]));
+        let counts: Vec<u32> = serde_json::from_value(json["counts"].clone()).unwrap();
+        assert_eq!(counts.iter().sum::<u32>(), 100);
+        assert!((calculate_coco_area(&converted).unwrap() - 18.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_convert_segmentation_multi_ring_to_polygon() {
+        // Polygons pass through with one list per ring.
+        let seg = CocoSegmentation::Polygon(two_ring_polygon());
+        let converted = convert_segmentation(seg, MaskFormat::Polygon, 10, 10)
+            .unwrap()
+            .unwrap();
+        let json = serde_json::to_value(&converted).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+
+        // An RLE covering both rings traces back to one polygon per region.
+        let rle = coco_polygon_to_rle(&two_ring_polygon(), 10, 10).unwrap();
+        let converted =
+            convert_segmentation(CocoSegmentation::Rle(rle), MaskFormat::Polygon, 10, 10)
+                .unwrap()
+                .unwrap();
+        match converted {
+            CocoSegmentation::Polygon(polygons) => {
+                assert_eq!(polygons.len(), 2);
+                assert!(polygons.iter().all(|p| p.len() >= 6 && p.len() % 2 == 0));
+            }
+            other => panic!("expected polygon, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_rasterize_polygon_fill_rules() {
+        // A 6x6 square with a 2x2 square inside it.
+        let rings = vec![
+            vec![(2.0, 2.0), (8.0, 2.0), (8.0, 8.0), (2.0, 8.0)],
+            vec![(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0)],
+        ];
+
+        let even_odd = rasterize_polygon(&rings, 10, 10, FillRule::EvenOdd);
+        assert_eq!(even_odd.iter().filter(|&&v| v == 1).count(), 32);
+        assert_eq!(even_odd[5 * 10 + 5], 0, "inner ring should cut a hole");
+
+        let union = rasterize_polygon(&rings, 10, 10, FillRule::Union);
+        assert_eq!(union.iter().filter(|&&v| v == 1).count(), 36);
+        assert_eq!(union[5 * 10 + 5], 1, "inner ring should be filled");
+    }
+
+    #[test]
+    fn test_coco_polygon_to_rle_requires_dimensions() {
+        assert!(coco_polygon_to_rle(&two_ring_polygon(), 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_convert_segmentation_empty_rle_to_polygon() {
+        let rle = CocoRle {
+            counts: vec![100],
+            size: [10, 10],
+        };
+        let converted =
+            convert_segmentation(CocoSegmentation::Rle(rle), MaskFormat::Polygon, 10, 10).unwrap();
+        assert!(converted.is_none());
+    }
 }
@@ -21,8 +21,9 @@
 //! - Bounding boxes (box2d)
 //! - Polygon segmentation (mask)
 //! - RLE segmentation (decoded to polygons)
+//! - Keypoints (export only)
 //!
-//! Not yet supported: keypoints, captions, panoptic segmentation.
+//! Not yet supported: captions, panoptic segmentation.
 //!
 //! ## Example
 //!
@@ -53,7 +54,7 @@
 //! # }
 //! ```
 
-mod convert;
+pub(crate) mod convert;
 mod reader;
 mod types;
 pub mod verify;
@@ -68,7 +69,7 @@ pub mod studio;
 // Re-export types
 pub use types::{
     CocoAnnotation, CocoCategory, CocoCompressedRle, CocoDataset, CocoImage, CocoIndex, CocoInfo,
-    CocoLicense, CocoRle, CocoSegmentation,
+    CocoLicense, CocoRle, CocoSegmentation, MaskFormat,
 };
 
 // Re-export readers/writers
@@ -80,10 +81,11 @@ pub use writer::{CocoDatasetBuilder, CocoWriteOptions, CocoWriter};
 
 // Re-export conversion functions
 pub use convert::{
-    box2d_to_coco_bbox, calculate_coco_area, coco_bbox_to_box2d, coco_polygon_to_polygon,
-    coco_rle_to_polygon, coco_segmentation_to_mask_data, coco_segmentation_to_polygon,
-    decode_compressed_rle, decode_rle, encode_rle, mask_to_contours, polygon_to_coco_polygon,
-    rle_to_mask_data, validate_coco_bbox,
+    FillRule, box2d_to_coco_bbox, calculate_coco_area, coco_bbox_to_box2d, coco_polygon_to_polygon,
+    coco_polygon_to_rle, coco_rle_to_polygon, coco_segmentation_to_mask_data,
+    coco_segmentation_to_polygon, convert_segmentation, decode_compressed_rle, decode_rle,
+    encode_compressed_rle, encode_rle, keypoints_to_coco_keypoints, mask_to_contours,
+    polygon_to_coco_polygon, rasterize_polygon, rle_to_mask_data, validate_coco_bbox,
 };
 
 // Re-export Arrow conversions (feature-gated)
@@ -726,6 +726,8 @@ mod tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
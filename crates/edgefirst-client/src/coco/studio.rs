@@ -13,14 +13,17 @@
 use super::{
     convert::{
         box2d_to_coco_bbox, coco_bbox_to_box2d, coco_segmentation_to_polygon,
-        polygon_to_coco_polygon,
+        compute_bbox_from_keypoints, compute_bbox_from_polygon, convert_segmentation,
+        keypoints_to_coco_keypoints, polygon_to_coco_polygon, polygon_to_polygon_string,
     },
     reader::{CocoReadOptions, CocoReader, read_coco_directory},
-    types::{CocoDataset, CocoImage, CocoIndex, CocoInfo, CocoSegmentation},
+    types::{CocoDataset, CocoImage, CocoIndex, CocoInfo, CocoSegmentation, MaskFormat},
     writer::{CocoDatasetBuilder, CocoWriteOptions, CocoWriter},
 };
 use crate::{
     Annotation, AnnotationSetID, Client, DatasetID, Error, FileType, Progress, Sample, SampleFile,
+    SamplesQuery,
+    client::{ANNOTATIONS_PER_BATCH, batch_annotations_by_sample},
 };
 use std::{
     collections::HashSet,
@@ -77,6 +80,9 @@ pub struct CocoExportOptions {
     pub groups: Vec<String>,
     /// Include segmentation masks in output.
     pub include_masks: bool,
+    /// Encoding for segmentation masks. `None` writes Studio polygons as
+    /// COCO polygons.
+    pub mask_format: Option<MaskFormat>,
     /// Include images in output (download and add to ZIP).
     pub include_images: bool,
     /// Output as ZIP archive (if false, output JSON only).
@@ -92,6 +98,7 @@ impl Default for CocoExportOptions {
         Self {
             groups: vec![],
             include_masks: true,
+            mask_format: None,
             include_images: false,
             output_zip: false,
             pretty_json: false,
@@ -272,6 +279,7 @@ async fn send_progress(progress: &Option<Sender<Progress>>, current: usize, tota
                 current,
                 total,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -306,8 +314,10 @@ async fn upload_images_in_batches<'a>(
                 samples,
                 None,
                 Some(ctx.options.concurrency),
+                None,
             )
-            .await?;
+            .await?
+            .into_result()?;
 
         imported += batch.len();
         send_progress(ctx.progress, imported, to_import).await;
@@ -645,6 +655,11 @@ fn convert_coco_image_to_sample(
 /// Export Studio dataset to COCO format.
 ///
 /// Downloads samples and annotations from Studio and converts to COCO format.
+/// Categories are the labels used by the exported annotations, ordered by
+/// [`Label::index`](crate::Label::index) and numbered contiguously from 1.
+/// Datasets storing pixel coordinates are normalized with
+/// [`CoordinateSpace::normalize`](crate::CoordinateSpace::normalize) first,
+/// and samples without image dimensions are skipped with a warning.
 ///
 /// # Arguments
 /// * `client` - Authenticated Studio client
@@ -668,20 +683,46 @@ pub async fn export_studio_to_coco(
 
     // Fetch samples from Studio with annotations
     let groups: Vec<String> = options.groups.clone();
-    let annotation_types = [crate::AnnotationType::Box2d, crate::AnnotationType::Polygon];
+    let annotation_types = [
+        crate::AnnotationType::Box2d,
+        crate::AnnotationType::Polygon,
+        crate::AnnotationType::Keypoints,
+    ];
 
     // Fetch all samples
     let all_samples = client
         .samples(
             dataset_id,
-            Some(annotation_set_id),
-            &annotation_types,
-            &groups,
-            &[],
             progress.clone(),
-            None,
+            SamplesQuery {
+                annotation_set_id: Some(annotation_set_id),
+                annotation_types: annotation_types.to_vec(),
+                groups: groups.to_vec(),
+                ..Default::default()
+            },
         )
         .await?;
+    let space = client.dataset(dataset_id).await?.coordinate_space();
+    let mut skipped = 0;
+    let mut all_samples: Vec<Sample> = retain_groups(all_samples, &groups)
+        .into_iter()
+        .filter(|sample| {
+            let sized =
+                matches!((sample.width, sample.height), (Some(w), Some(h)) if w > 0 && h > 0);
+            skipped += usize::from(!sized);
+            sized
+        })
+        .collect();
+    if skipped > 0 {
+        log::warn!(
+            "COCO export of {} skipped {} samples without image dimensions",
+            annotation_set_id,
+            skipped
+        );
+    }
+    for sample in &mut all_samples {
+        space.normalize(sample)?;
+    }
 
     // Convert to COCO format
     let mut builder = CocoDatasetBuilder::new();
@@ -690,6 +731,20 @@ pub async fn export_studio_to_coco(
         builder = builder.info(info.clone());
     }
 
+    // Register categories up front so their IDs follow the dataset's label
+    // order rather than the order annotations happen to be listed in.
+    let mut labels = client.labels(dataset_id, None).await?;
+    labels.sort_by_key(|label| label.index());
+    let used: HashSet<&str> = all_samples
+        .iter()
+        .flat_map(|sample| &sample.annotations)
+        .filter(|ann| ann.box2d().is_some() || ann.polygon().is_some() || ann.keypoints().is_some())
+        .filter_map(|ann| ann.label().map(|s| s.as_str()))
+        .collect();
+    for label in labels.iter().filter(|label| used.contains(label.name())) {
+        builder.add_category(label.name(), None);
+    }
+
     for sample in &all_samples {
         let image_name = sample.image_name.as_deref().unwrap_or("unknown");
         let width = sample.width.unwrap_or(0);
@@ -705,10 +760,13 @@ pub async fn export_studio_to_coco(
 
         for ann in &sample.annotations {
             // Get bbox from box2d if present, otherwise compute from polygon
+            // or keypoints
             let bbox = if let Some(box2d) = ann.box2d() {
                 Some(box2d_to_coco_bbox(box2d, width, height))
             } else if let Some(polygon) = ann.polygon() {
                 compute_bbox_from_polygon(polygon, width, height)
+            } else if let Some(keypoints) = ann.keypoints() {
+                compute_bbox_from_keypoints(keypoints, width, height)
             } else {
                 None
             };
@@ -717,16 +775,26 @@ pub async fn export_studio_to_coco(
                 let label = ann.label().map(|s| s.as_str()).unwrap_or("unknown");
                 let category_id = builder.add_category(label, None);
 
-                let segmentation = if options.include_masks {
-                    ann.polygon().map(|polygon| {
+                let segmentation = match ann.polygon().filter(|_| options.include_masks) {
+                    Some(polygon) => {
                         let coco_poly = polygon_to_coco_polygon(polygon, width, height);
-                        CocoSegmentation::Polygon(coco_poly)
-                    })
-                } else {
-                    None
+                        let seg = CocoSegmentation::Polygon(coco_poly);
+                        match options.mask_format {
+                            Some(format) => convert_segmentation(seg, format, width, height)?,
+                            None => Some(seg),
+                        }
+                    }
+                    None => None,
                 };
 
-                builder.add_annotation(image_id, category_id, bbox, segmentation);
+                let annotation_id =
+                    builder.add_annotation(image_id, category_id, bbox, segmentation);
+                if let Some(keypoints) = ann.keypoints() {
+                    builder.set_annotation_keypoints(
+                        annotation_id,
+                        keypoints_to_coco_keypoints(keypoints, width, height),
+                    );
+                }
             }
         }
     }
@@ -756,6 +824,28 @@ pub async fn export_studio_to_coco(
     Ok(annotation_count)
 }
 
+/// Keep only the samples, and their annotations, that belong to `groups`.
+///
+/// The group filter is also sent to the server, but it is enforced here so an
+/// export restricted to e.g. `train` never picks up samples or annotations
+/// from other groups. An empty `groups` keeps everything.
+fn retain_groups(samples: Vec<Sample>, groups: &[String]) -> Vec<Sample> {
+    if groups.is_empty() {
+        return samples;
+    }
+
+    samples
+        .into_iter()
+        .filter(|sample| sample.group().is_some_and(|g| groups.contains(g)))
+        .map(|mut sample| {
+            sample
+                .annotations
+                .retain(|ann| ann.group().is_none_or(|g| groups.contains(g)));
+            sample
+        })
+        .collect()
+}
+
 /// Download images for samples from their presigned URLs.
 ///
 /// Returns a vector of (archive_path, image_data) pairs suitable for ZIP
@@ -809,6 +899,7 @@ async fn download_images(
                     current: i + 1,
                     total,
                     status: None,
+                    ..Default::default()
                 })
                 .await;
         }
@@ -853,7 +944,9 @@ pub struct CocoUpdateOptions {
     pub include_masks: bool,
     /// Group name filter (None = match any group).
     pub group: Option<String>,
-    /// Batch size for API calls.
+    /// Number of samples per API call. Each sample's annotations are sent
+    /// in a single request, and a request is closed early once it would
+    /// carry more than 500 annotations.
     pub batch_size: usize,
     /// Maximum concurrent operations.
     pub concurrency: usize,
@@ -1004,7 +1097,7 @@ fn convert_coco_annotation_to_server(
             .segmentation
             .as_ref()
             .and_then(|seg| coco_segmentation_to_polygon(seg, width, height).ok())
-            .map(|p| polygon_to_polygon_string(&p))
+            .and_then(|p| polygon_to_polygon_string(&p))
             .unwrap_or_default()
     } else {
         String::new()
@@ -1021,11 +1114,14 @@ fn convert_coco_annotation_to_server(
         y: box2d.top() as f64,
         w: box2d.width() as f64,
         h: box2d.height() as f64,
+        z: None,
+        l: None,
         score: 1.0,
         polygon,
         image_id,
         annotation_set_id,
         object_reference: None,
+        attributes: Default::default(),
     };
 
     (server_ann, missing_label)
@@ -1173,6 +1269,9 @@ async fn update_sample_groups(
 ///
 /// # Returns
 /// Update result with counts of updated and not-found samples.
+///
+/// # Errors
+/// Returns [`Error::AnnotationSetLocked`] if the annotation set is locked.
 pub async fn update_coco_annotations(
     client: &Client,
     coco_path: impl AsRef<Path>,
@@ -1202,17 +1301,21 @@ pub async fn update_coco_annotations(
         dataset.categories.len()
     );
 
+    // Refuse locked sets before any annotations are deleted
+    client
+        .ensure_annotation_set_writable(annotation_set_id)
+        .await?;
+
     // Query ALL existing samples from Studio
     log::info!("Fetching existing samples from Studio...");
     let existing_samples = client
         .samples(
             dataset_id,
-            Some(annotation_set_id),
-            &[],
-            &[],
-            &[],
             progress.clone(),
-            None,
+            SamplesQuery {
+                annotation_set_id: Some(annotation_set_id),
+                ..Default::default()
+            },
         )
         .await?;
 
@@ -1293,6 +1396,7 @@ pub async fn update_coco_annotations(
                 current: 0,
                 total: to_update,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -1311,7 +1415,7 @@ pub async fn update_coco_annotations(
     // Delete in batches to avoid overwhelming the server
     for batch in sample_ids_to_update.chunks(options.batch_size) {
         client
-            .delete_annotations_bulk(annotation_set_id, &annotation_types, batch)
+            .delete_annotations_bulk_unchecked(annotation_set_id, &annotation_types, batch)
             .await?;
     }
 
@@ -1322,6 +1426,7 @@ pub async fn update_coco_annotations(
                 current: to_update / 2,
                 total: to_update,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -1329,11 +1434,16 @@ pub async fn update_coco_annotations(
     // Step 2: Add new annotations in batches
     log::info!("Adding {} new annotations...", server_annotations.len());
     let mut added = 0;
-    for batch in server_annotations.chunks(options.batch_size) {
+    for batch in batch_annotations_by_sample(
+        server_annotations,
+        options.batch_size,
+        ANNOTATIONS_PER_BATCH,
+    ) {
+        let count = batch.len();
         client
-            .add_annotations_bulk(annotation_set_id, batch.to_vec())
+            .add_annotations_bulk_unchecked(annotation_set_id, batch)
             .await?;
-        added += batch.len();
+        added += count;
         log::debug!("Added {} annotations so far", added);
     }
 
@@ -1344,6 +1454,7 @@ pub async fn update_coco_annotations(
                 current: to_update,
                 total: to_update,
                 status: None,
+                ..Default::default()
             })
             .await;
     }
@@ -1367,83 +1478,6 @@ pub async fn update_coco_annotations(
     })
 }
 
-/// Convert a Polygon to a polygon string for the server API.
-///
-/// The server expects a 3D array format: `[[[x1,y1],[x2,y2],...], ...]`
-/// where each point is an `[x, y]` pair. This matches how the server
-/// parses polygons in `annotations_handler.go`:
-/// ```go
-/// var polygons [][][]float64
-/// json.Unmarshal([]byte(ann.Polygon), &polygons)
-/// ```
-///
-/// **Note:** This function filters out NaN and Infinity values which would
-/// serialize as `null` in JSON and cause parsing failures on the server.
-fn polygon_to_polygon_string(polygon: &crate::Polygon) -> String {
-    // Convert Vec<Vec<(f32, f32)>> to Vec<Vec<[f32; 2]>> for proper JSON
-    // serialization Filter out any NaN or Infinity values which would become
-    // "null" in JSON
-    let rings: Vec<Vec<[f32; 2]>> = polygon
-        .rings
-        .iter()
-        .map(|ring| {
-            ring.iter()
-                .filter(|(x, y)| x.is_finite() && y.is_finite())
-                .map(|&(x, y)| [x, y])
-                .collect()
-        })
-        .filter(|ring: &Vec<[f32; 2]>| ring.len() >= 3) // Need at least 3 points for a valid polygon
-        .collect();
-
-    serde_json::to_string(&rings).unwrap_or_default()
-}
-
-/// Compute COCO bounding box from polygon contours.
-///
-/// When the server doesn't return bounding box coordinates for segmentation
-/// annotations, we compute them from the polygon bounds.
-fn compute_bbox_from_polygon(
-    polygon: &crate::Polygon,
-    width: u32,
-    height: u32,
-) -> Option<[f64; 4]> {
-    if polygon.rings.is_empty() {
-        return None;
-    }
-
-    let mut min_x = f32::MAX;
-    let mut min_y = f32::MAX;
-    let mut max_x = f32::MIN;
-    let mut max_y = f32::MIN;
-
-    for ring in &polygon.rings {
-        for &(x, y) in ring {
-            if x.is_finite() && y.is_finite() {
-                min_x = min_x.min(x);
-                min_y = min_y.min(y);
-                max_x = max_x.max(x);
-                max_y = max_y.max(y);
-            }
-        }
-    }
-
-    if min_x == f32::MAX || min_y == f32::MAX {
-        return None;
-    }
-
-    // Convert normalized coordinates to COCO pixel coordinates [x, y, w, h]
-    let x = (min_x * width as f32) as f64;
-    let y = (min_y * height as f32) as f64;
-    let w = ((max_x - min_x) * width as f32) as f64;
-    let h = ((max_y - min_y) * height as f32) as f64;
-
-    if w > 0.0 && h > 0.0 {
-        Some([x, y, w, h])
-    } else {
-        None
-    }
-}
-
 /// Verify a COCO dataset import against Studio data.
 ///
 /// Compares the local COCO dataset against what's stored in Studio to verify:
@@ -1526,12 +1560,13 @@ pub async fn verify_coco_import(
     let studio_samples = client
         .samples(
             dataset_id,
-            Some(annotation_set_id),
-            &annotation_types,
-            &groups,
-            &[],
             progress.clone(),
-            None,
+            SamplesQuery {
+                annotation_set_id: Some(annotation_set_id),
+                annotation_types: annotation_types.to_vec(),
+                groups: groups.to_vec(),
+                ..Default::default()
+            },
         )
         .await?;
 
@@ -1660,6 +1695,7 @@ mod tests {
         let options = CocoExportOptions::default();
         assert!(options.groups.is_empty());
         assert!(options.include_masks);
+        assert!(options.mask_format.is_none());
         assert!(!options.include_images);
         assert!(!options.output_zip);
         assert!(!options.pretty_json);
@@ -1935,6 +1971,8 @@ mod tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -2015,6 +2053,8 @@ mod tests {
                     10.0, 10.0, 60.0, 10.0, 60.0, 60.0, 10.0, 60.0,
                 ]])),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -2094,7 +2134,7 @@ mod tests {
         // Create a simple triangle mask
         let mask = crate::Polygon::new(vec![vec![(0.1, 0.2), (0.3, 0.4), (0.5, 0.6)]]);
 
-        let result = polygon_to_polygon_string(&mask);
+        let result = polygon_to_polygon_string(&mask).unwrap();
 
         // Server expects 3D array format: [[[x1,y1],[x2,y2],...]]
         // NOT COCO format: [[x1,y1,x2,y2,...]]
@@ -2110,7 +2150,7 @@ mod tests {
             vec![(0.5, 0.5), (0.6, 0.5), (0.55, 0.6)], // Triangle 2
         ]);
 
-        let result = polygon_to_polygon_string(&mask);
+        let result = polygon_to_polygon_string(&mask).unwrap();
 
         // Should produce two separate polygon rings
         assert_eq!(
@@ -2129,7 +2169,7 @@ mod tests {
             (0.5, 0.6),
         ]]);
 
-        let result = polygon_to_polygon_string(&mask);
+        let result = polygon_to_polygon_string(&mask).unwrap();
 
         // NaN values should be filtered out, not serialized as "null"
         assert!(
@@ -2151,7 +2191,7 @@ mod tests {
             (0.5, 0.6),
         ]]);
 
-        let result = polygon_to_polygon_string(&mask);
+        let result = polygon_to_polygon_string(&mask).unwrap();
 
         assert!(
             !result.contains("null"),
@@ -2171,8 +2211,8 @@ mod tests {
 
         let result = polygon_to_polygon_string(&mask);
 
-        // Only 1 point remains, so the ring should be dropped
-        assert_eq!(result, "[]");
+        // Only 1 point remains, so the ring is dropped and nothing is left
+        assert_eq!(result, None);
     }
 
     #[test]
@@ -2184,7 +2224,7 @@ mod tests {
             (0.5, 0.6),
         ]]);
 
-        let result = polygon_to_polygon_string(&mask);
+        let result = polygon_to_polygon_string(&mask).unwrap();
         assert_eq!(result, "[[[0.1,0.2],[0.3,0.4],[0.5,0.6]]]");
     }
 
@@ -2413,4 +2453,60 @@ mod tests {
     fn test_extract_sample_name_multiple_dots() {
         assert_eq!(extract_sample_name("image.v2.final.jpg"), "image.v2.final");
     }
+
+    // =========================================================================
+    // retain_groups tests
+    // =========================================================================
+
+    fn grouped_sample(name: &str, group: &str, annotation_groups: &[Option<&str>]) -> Sample {
+        let annotations = annotation_groups
+            .iter()
+            .map(|g| {
+                let mut ann = Annotation::new();
+                ann.set_group(g.map(String::from));
+                ann
+            })
+            .collect();
+        Sample {
+            image_name: Some(name.to_string()),
+            group: Some(group.to_string()),
+            annotations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_retain_groups_empty_filter_keeps_everything() {
+        let samples = vec![
+            grouped_sample("a", "train", &[Some("train")]),
+            grouped_sample("b", "val", &[Some("val")]),
+        ];
+        assert_eq!(retain_groups(samples, &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_retain_groups_excludes_other_groups() {
+        let samples = vec![
+            grouped_sample("a", "train", &[Some("train"), None, Some("val")]),
+            grouped_sample("b", "val", &[Some("val")]),
+            Sample {
+                image_name: Some("c".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let kept = retain_groups(samples, &["train".to_string()]);
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].image_name.as_deref(), Some("a"));
+        // Annotations without a group follow their sample; stray ones from
+        // another group are dropped.
+        assert_eq!(kept[0].annotations.len(), 2);
+        assert!(
+            kept[0]
+                .annotations
+                .iter()
+                .all(|ann| ann.group().is_none_or(|g| g == "train"))
+        );
+    }
 }
@@ -65,6 +65,8 @@ mod integration_tests {
                         100.0, 50.0, 300.0, 50.0, 300.0, 350.0, 100.0, 350.0,
                     ]])),
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 CocoAnnotation {
                     id: 2,
@@ -75,6 +77,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 // One annotation on second image
                 CocoAnnotation {
@@ -88,6 +92,8 @@ mod integration_tests {
                         50.0, 100.0, 350.0, 100.0, 350.0, 500.0, 50.0, 500.0,
                     ]])),
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             licenses: vec![],
@@ -534,6 +540,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 CocoAnnotation {
                     id: 2,
@@ -544,6 +552,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 CocoAnnotation {
                     id: 3,
@@ -554,6 +564,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             licenses: vec![],
@@ -754,6 +766,8 @@ mod integration_tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -835,6 +849,8 @@ mod integration_tests {
                     100.0, 50.0, 300.0, 50.0, 300.0, 200.0, 100.0, 200.0,
                 ]])),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -913,6 +929,8 @@ mod integration_tests {
                     size: [10, 10],          // [height, width]
                 })),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1013,6 +1031,8 @@ mod integration_tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1073,6 +1093,8 @@ mod integration_tests {
                         10.0, 10.0, 60.0, 10.0, 60.0, 60.0, 10.0, 60.0,
                     ]])),
                     score: Some(0.95),
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 // Annotation without segmentation + score → box2d_score
                 CocoAnnotation {
@@ -1084,6 +1106,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: Some(0.85),
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             ..Default::default()
@@ -1263,6 +1287,8 @@ mod integration_tests {
                     50.0, 60.0, 250.0, 60.0, 250.0, 240.0, 50.0, 240.0,
                 ]])),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1353,6 +1379,8 @@ mod integration_tests {
                     size: [10, 10],
                 })),
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -1454,6 +1482,8 @@ mod integration_tests {
                         10.0, 10.0, 60.0, 10.0, 60.0, 60.0, 10.0, 60.0,
                     ]])),
                     score: Some(0.95),
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 // Annotation with bbox only + score
                 CocoAnnotation {
@@ -1465,6 +1495,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: Some(0.85),
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             ..Default::default()
@@ -1593,6 +1625,8 @@ mod integration_tests {
                         30.0, 40.0, 130.0, 40.0, 130.0, 160.0, 30.0, 160.0,
                     ]])),
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 // Annotation 2: bbox only on image 1 (no segmentation)
                 CocoAnnotation {
@@ -1604,6 +1638,8 @@ mod integration_tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 // Annotation 3: RLE mask on image 2 (crowd annotation)
                 // 10x10 image, RLE: 10 bg, 5 fg, 85 bg = 100 pixels (5 fg)
@@ -1619,6 +1655,8 @@ mod integration_tests {
                         size: [10, 10],
                     })),
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             licenses: vec![],
@@ -3,8 +3,8 @@
 
 //! COCO JSON data structures for serde serialization/deserialization.
 //!
-//! Supports object detection and instance segmentation annotation types.
-//! Keypoints, captions, and panoptic segmentation are NOT supported in this
+//! Supports object detection, instance segmentation and keypoint annotation
+//! types. Captions and panoptic segmentation are NOT supported in this
 //! version.
 
 use serde::{Deserialize, Serialize};
@@ -142,7 +142,7 @@ pub struct CocoCategory {
 ///
 /// Each annotation represents a single object instance in an image.
 ///
-/// Note: Keypoints, captions, and panoptic fields are NOT supported.
+/// Note: Captions and panoptic fields are NOT supported.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CocoAnnotation {
     /// Unique annotation ID.
@@ -165,6 +165,38 @@ pub struct CocoAnnotation {
     /// Detection confidence score (present in COCO detection results).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub score: Option<f64>,
+    /// Keypoints as flat `[x1, y1, v1, x2, y2, v2, ...]` in pixels, where
+    /// `v` is 0 (not labeled), 1 (occluded) or 2 (visible).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypoints: Option<Vec<f64>>,
+    /// Number of labeled keypoints (`v > 0`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_keypoints: Option<u32>,
+}
+
+/// Segmentation encoding written by the COCO exporters.
+///
+/// Consumers differ on what they accept, so exports can convert every mask
+/// to a single encoding. Multi-ring masks become one polygon list per ring,
+/// or a single RLE covering all rings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskFormat {
+    /// Polygon coordinate lists (`[[x1, y1, x2, y2, ...], ...]`).
+    Polygon,
+    /// Uncompressed run-length encoding (`{"counts": [...], "size": [h, w]}`).
+    Rle,
+}
+
+impl TryFrom<&str> for MaskFormat {
+    type Error = crate::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "polygon" => Ok(MaskFormat::Polygon),
+            "rle" => Ok(MaskFormat::Rle),
+            _ => Err(crate::Error::UnsupportedFormat(s.to_string())),
+        }
+    }
 }
 
 /// Segmentation format: polygon array or RLE.
@@ -353,6 +385,8 @@ mod tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
                 CocoAnnotation {
                     id: 101,
@@ -363,6 +397,8 @@ mod tests {
                     iscrowd: 0,
                     segmentation: None,
                     score: None,
+                    keypoints: None,
+                    num_keypoints: None,
                 },
             ],
             ..Default::default()
@@ -432,6 +468,8 @@ mod tests {
                 100.0, 200.0, 150.0, 200.0, 150.0, 280.0, 100.0, 280.0,
             ]])),
             score: None,
+            keypoints: None,
+            num_keypoints: None,
         };
 
         let json = serde_json::to_string(&ann).unwrap();
@@ -542,4 +580,14 @@ mod tests {
         assert_eq!(cat.synonyms, None);
         assert_eq!(cat.def, None);
     }
+
+    #[test]
+    fn test_mask_format_try_from() {
+        assert_eq!(
+            MaskFormat::try_from("polygon").unwrap(),
+            MaskFormat::Polygon
+        );
+        assert_eq!(MaskFormat::try_from("rle").unwrap(), MaskFormat::Rle);
+        assert!(MaskFormat::try_from("png").is_err());
+    }
 }
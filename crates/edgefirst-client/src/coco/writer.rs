@@ -546,6 +546,8 @@ impl CocoDatasetBuilder {
             iscrowd,
             segmentation,
             score: None,
+            keypoints: None,
+            num_keypoints: None,
         });
 
         id
@@ -563,6 +565,27 @@ impl CocoDatasetBuilder {
         }
     }
 
+    /// Set the keypoints on an annotation by ID, counting the labeled ones
+    /// into `num_keypoints`.
+    ///
+    /// `keypoints` is COCO's flat `[x1, y1, v1, ...]` list in pixels.
+    pub fn set_annotation_keypoints(&mut self, annotation_id: u64, keypoints: Vec<f64>) {
+        if let Some(ann) = self
+            .dataset
+            .annotations
+            .iter_mut()
+            .find(|a| a.id == annotation_id)
+        {
+            ann.num_keypoints = Some(
+                keypoints
+                    .chunks(3)
+                    .filter(|p| p.get(2) > Some(&0.0))
+                    .count() as u32,
+            );
+            ann.keypoints = Some(keypoints);
+        }
+    }
+
     /// Set LVIS annotation metadata on an image.
     pub fn set_image_neg_categories(
         &mut self,
@@ -657,6 +680,8 @@ mod tests {
                 iscrowd: 0,
                 segmentation: None,
                 score: None,
+                keypoints: None,
+                num_keypoints: None,
             }],
             ..Default::default()
         };
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! Gzip compression of uploaded text files.
+//!
+//! Enabled with
+//! [`Client::with_upload_compression`](crate::Client::with_upload_compression).
+//! Multipart uploads of text files such as logs, JSON and CSV send the file
+//! part gzip-compressed with a `Content-Encoding: gzip` part header. Other
+//! files are sent as they are, since images and model weights are already
+//! compressed or gain little. The server must accept gzip-encoded parts, so
+//! compression is off unless requested.
+
+use crate::Error;
+use flate2::{Compression, write::GzEncoder};
+use reqwest::{
+    header::{CONTENT_ENCODING, HeaderMap, HeaderValue},
+    multipart::Part,
+};
+use std::{io::Write as _, path::Path};
+use tokio::io::AsyncRead;
+
+/// File extensions of the text formats compressed on upload.
+const COMPRESSIBLE_EXTENSIONS: &[&str] = &[
+    "csv", "htm", "html", "json", "jsonl", "log", "md", "ndjson", "svg", "tsv", "txt", "xml",
+    "yaml", "yml",
+];
+
+/// Whether `path` names a text file worth compressing, judged by its
+/// extension.
+pub(crate) fn is_compressible(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            COMPRESSIBLE_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// Gzip-compresses `data` at `level` (0-9).
+pub(crate) fn gzip(data: &[u8], level: u32) -> Result<Vec<u8>, Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level));
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// The contents of a file part of a multipart upload.
+pub(crate) struct UploadSource {
+    pub(crate) reader: Box<dyn AsyncRead + Send + Sync + Unpin>,
+    /// Bytes that will be sent, after any compression.
+    pub(crate) len: usize,
+    /// Whether `reader` yields gzip-compressed data.
+    pub(crate) gzip: bool,
+}
+
+impl UploadSource {
+    /// Opens `path` for upload. When `level` is set and the file is text it
+    /// is read and compressed in memory; otherwise it is streamed from disk.
+    pub(crate) async fn open(path: &Path, level: Option<u32>) -> Result<Self, Error> {
+        match level {
+            Some(level) if is_compressible(path) => {
+                let data = tokio::fs::read(path).await?;
+                let data = tokio::task::spawn_blocking(move || gzip(&data, level)).await??;
+                Ok(UploadSource {
+                    len: data.len(),
+                    reader: Box::new(std::io::Cursor::new(data)),
+                    gzip: true,
+                })
+            }
+            _ => {
+                let file = tokio::fs::File::open(path).await?;
+                let len = file.metadata().await?.len() as usize;
+                Ok(UploadSource {
+                    reader: Box::new(file),
+                    len,
+                    gzip: false,
+                })
+            }
+        }
+    }
+}
+
+/// Adds the `Content-Encoding: gzip` header to `part` when `gzip` is set.
+pub(crate) fn encoded_part(part: Part, gzip: bool) -> Part {
+    if !gzip {
+        return part;
+    }
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    part.headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read as _;
+    use tokio::io::AsyncReadExt as _;
+
+    #[test]
+    fn is_compressible_matches_text_extensions() {
+        assert!(is_compressible(Path::new("logs/train.log")));
+        assert!(is_compressible(Path::new("metrics.JSON")));
+        assert!(!is_compressible(Path::new("model.tflite")));
+        assert!(!is_compressible(Path::new("frame.jpg")));
+        assert!(!is_compressible(Path::new("README")));
+    }
+
+    #[tokio::test]
+    async fn open_compresses_only_text_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("train.log");
+        let text = "epoch 1 loss 0.5\n".repeat(100);
+        std::fs::write(&log, &text).unwrap();
+
+        let mut source = UploadSource::open(&log, Some(6)).await.unwrap();
+        assert!(source.gzip);
+        assert!(source.len < text.len());
+        let mut sent = Vec::new();
+        source.reader.read_to_end(&mut sent).await.unwrap();
+        assert_eq!(sent.len(), source.len);
+        let mut decoded = String::new();
+        GzDecoder::new(&sent[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+
+        let source = UploadSource::open(&log, None).await.unwrap();
+        assert!(!source.gzip);
+        assert_eq!(source.len, text.len());
+
+        let weights = dir.path().join("model.onnx");
+        std::fs::write(&weights, [0u8; 64]).unwrap();
+        let source = UploadSource::open(&weights, Some(6)).await.unwrap();
+        assert!(!source.gzip);
+        assert_eq!(source.len, 64);
+    }
+}
@@ -5,7 +5,7 @@ use std::{collections::HashMap, fmt::Display};
 
 use crate::{
     Client, Error,
-    api::{AnnotationSetID, DatasetID, ProjectID, SampleID},
+    api::{AnnotationSetID, DatasetID, ProjectID, SampleID, ValidationIssue},
     mask::MaskData,
 };
 use chrono::{DateTime, Utc};
@@ -48,7 +48,7 @@ use polars::prelude::*;
 ///     _ => println!("Processing other sensor data"),
 /// }
 /// ```
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub enum FileType {
     /// Standard image files (JPEG, PNG, etc.)
     Image,
@@ -66,6 +66,12 @@ pub enum FileType {
     All,
 }
 
+/// The files of one sample held in memory, keyed by [`FileType`].
+///
+/// Returned per sample by
+/// [`Client::download_samples_memory`](crate::Client::download_samples_memory).
+pub type SampleFiles = HashMap<FileType, Vec<u8>>;
+
 impl std::fmt::Display for FileType {
     /// Returns the server API type name for this file type.
     /// Used when making API requests to the server.
@@ -234,8 +240,14 @@ impl FileType {
 ///     AnnotationType::Box3d => println!("Processing 3D bounding boxes"),
 ///     AnnotationType::Polygon => println!("Processing polygon contours"),
 ///     AnnotationType::Mask => println!("Processing raster pixel masks"),
+///     AnnotationType::Keypoints => println!("Processing keypoint skeletons"),
+///     AnnotationType::Other(name) => println!("Skipping unknown type {}", name),
 /// }
 /// ```
+///
+/// Names this client does not know, such as a type added to the server
+/// later, convert to [`Other`](Self::Other) through `From<String>` so they
+/// pass through unchanged; `TryFrom<&str>` still rejects them.
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum AnnotationType {
     /// 2D bounding boxes for object detection in images
@@ -246,6 +258,10 @@ pub enum AnnotationType {
     Polygon,
     /// Raster pixel masks for semantic/instance segmentation
     Mask,
+    /// Pose keypoints
+    Keypoints,
+    /// A type this client does not know, holding its name as given
+    Other(String),
 }
 
 impl TryFrom<&str> for AnnotationType {
@@ -259,6 +275,7 @@ impl TryFrom<&str> for AnnotationType {
             "seg" => Ok(AnnotationType::Polygon),
             "mask" => Ok(AnnotationType::Polygon), // backward compat
             "raster" => Ok(AnnotationType::Mask),
+            "keypoints" => Ok(AnnotationType::Keypoints),
             _ => Err(crate::Error::InvalidAnnotationType(s.to_string())),
         }
     }
@@ -266,15 +283,16 @@ impl TryFrom<&str> for AnnotationType {
 
 impl From<String> for AnnotationType {
     fn from(s: String) -> Self {
-        // For backward compatibility, default to Box2d if invalid
-        s.as_str().try_into().unwrap_or(AnnotationType::Box2d)
+        s.as_str().try_into().unwrap_or_else(|_| {
+            log::warn!("Unknown annotation type {:?}, keeping it as Other", s);
+            AnnotationType::Other(s)
+        })
     }
 }
 
 impl From<&String> for AnnotationType {
     fn from(s: &String) -> Self {
-        // For backward compatibility, default to Box2d if invalid
-        s.as_str().try_into().unwrap_or(AnnotationType::Box2d)
+        AnnotationType::from(s.clone())
     }
 }
 
@@ -289,12 +307,16 @@ impl AnnotationType {
     /// - `Box2d` → `"box2d"`
     /// - `Box3d` → `"box3d"`
     /// - `Polygon` / `Mask` → `"mask"`
-    pub fn as_server_type(&self) -> &'static str {
+    /// - `Keypoints` → `"keypoints"`
+    /// - `Other(name)` → `name`
+    pub fn as_server_type(&self) -> &str {
         match self {
             AnnotationType::Box2d => "box2d",
             AnnotationType::Box3d => "box3d",
             AnnotationType::Polygon => "mask",
             AnnotationType::Mask => "mask",
+            AnnotationType::Keypoints => "keypoints",
+            AnnotationType::Other(name) => name,
         }
     }
 }
@@ -306,11 +328,87 @@ impl std::fmt::Display for AnnotationType {
             AnnotationType::Box3d => "box3d",
             AnnotationType::Polygon => "polygon",
             AnnotationType::Mask => "mask",
+            AnnotationType::Keypoints => "keypoints",
+            AnnotationType::Other(name) => name,
         };
         write!(f, "{}", value)
     }
 }
 
+/// Parts of a [`Sample`] fetched by
+/// [`Client::samples`](crate::Client::samples).
+///
+/// Identifiers (`id`, `uuid`, `image_name`) and the other scalar metadata
+/// such as group, sequence and date are always returned; [`IDS`](Self::IDS)
+/// asks for nothing more. Combine the other flags with `|`. The default is
+/// [`ALL`](Self::ALL).
+///
+/// Leaving out [`ANNOTATIONS`](Self::ANNOTATIONS) or [`FILES`](Self::FILES)
+/// drops the annotation set and file types from the `samples.list` request,
+/// so the server does not send them. Dimensions cost little on the wire and
+/// are cleared on the client when [`DIMENSIONS`](Self::DIMENSIONS) is not
+/// asked for.
+///
+/// # Examples
+///
+/// ```rust
+/// use edgefirst_client::SampleFields;
+///
+/// let fields = SampleFields::IDS | SampleFields::DIMENSIONS;
+/// assert!(fields.contains(SampleFields::DIMENSIONS));
+/// assert!(!fields.contains(SampleFields::ANNOTATIONS));
+/// assert_eq!(SampleFields::default(), SampleFields::ALL);
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct SampleFields(u8);
+
+impl SampleFields {
+    /// Identifiers and scalar metadata only.
+    pub const IDS: SampleFields = SampleFields(0);
+    /// Image `width` and `height`.
+    pub const DIMENSIONS: SampleFields = SampleFields(1);
+    /// Annotations from the requested annotation set.
+    pub const ANNOTATIONS: SampleFields = SampleFields(1 << 1);
+    /// The image URL and the sensor files of the requested file types.
+    pub const FILES: SampleFields = SampleFields(1 << 2);
+    /// Every field.
+    pub const ALL: SampleFields = SampleFields(0b111);
+
+    /// Returns `true` if every field in `other` is also in `self`.
+    pub const fn contains(self, other: SampleFields) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Clears the fields of `sample` that are not in `self`.
+    pub(crate) fn project(self, sample: &mut Sample) {
+        if !self.contains(SampleFields::DIMENSIONS) {
+            sample.width = None;
+            sample.height = None;
+        }
+        if !self.contains(SampleFields::ANNOTATIONS) {
+            sample.annotations.clear();
+        }
+        if !self.contains(SampleFields::FILES) {
+            sample.image_url = None;
+            sample.files.clear();
+        }
+    }
+}
+
+impl Default for SampleFields {
+    fn default() -> Self {
+        SampleFields::ALL
+    }
+}
+
+impl std::ops::BitOr for SampleFields {
+    type Output = SampleFields;
+
+    fn bitor(self, rhs: SampleFields) -> SampleFields {
+        SampleFields(self.0 | rhs.0)
+    }
+}
+
 /// A dataset in EdgeFirst Studio containing sensor data and annotations.
 ///
 /// Datasets are collections of multi-modal sensor data (images, LiDAR, radar)
@@ -364,6 +462,84 @@ pub struct Dataset {
     tag: String,
     #[serde(default)]
     tag_description: String,
+    #[serde(default)]
+    coordinate_space: CoordinateSpace,
+}
+
+/// Unit of the 2D box and polygon coordinates stored in a dataset.
+///
+/// Read from the dataset's `coordinate_space` field. A dataset without it
+/// is assumed to be [`CoordinateSpace::Pixels`], the unit images are
+/// annotated in, so nothing is exported in the wrong unit silently: the
+/// exporters convert pixel datasets with [`CoordinateSpace::normalize`]
+/// before writing, which needs each sample's image dimensions. Datasets
+/// the server reports as `normalized` are written as stored.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateSpace {
+    /// Pixels of the sample's image.
+    #[default]
+    Pixels,
+    /// Fractions of the image width and height, from 0.0 to 1.0.
+    Normalized,
+}
+
+impl CoordinateSpace {
+    /// Converts the 2D boxes, polygons and keypoints of `sample` from this space to
+    /// normalized coordinates, dividing by the sample's image dimensions.
+    /// Does nothing for [`CoordinateSpace::Normalized`]. 3D boxes and
+    /// raster masks are left as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameters`] if pixel coordinates need
+    /// converting and the sample has no width or height.
+    pub fn normalize(self, sample: &mut Sample) -> Result<(), Error> {
+        if self == CoordinateSpace::Normalized
+            || sample.annotations.iter().all(|ann| {
+                ann.box2d().is_none() && ann.polygon().is_none() && ann.keypoints().is_none()
+            })
+        {
+            return Ok(());
+        }
+        let (width, height) = match (sample.width, sample.height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => (width as f32, height as f32),
+            _ => {
+                return Err(Error::InvalidParameters(format!(
+                    "sample {} has pixel coordinates but no image dimensions",
+                    sample.image_name().unwrap_or("unknown")
+                )));
+            }
+        };
+        for ann in &mut sample.annotations {
+            if let Some(box2d) = ann.box2d() {
+                let normalized = Box2d::new(
+                    box2d.left() / width,
+                    box2d.top() / height,
+                    box2d.width() / width,
+                    box2d.height() / height,
+                );
+                ann.set_box2d(Some(normalized));
+            }
+            if let Some(polygon) = ann.polygon() {
+                let rings = polygon
+                    .rings
+                    .iter()
+                    .map(|ring| ring.iter().map(|&(x, y)| (x / width, y / height)).collect())
+                    .collect();
+                ann.set_polygon(Some(Polygon::new(rings)));
+            }
+            if let Some(keypoints) = ann.keypoints() {
+                let points = keypoints
+                    .points
+                    .iter()
+                    .map(|&(x, y, v)| (x / width, y / height, v))
+                    .collect();
+                ann.set_keypoints(Some(Keypoints::new(points)));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Display for Dataset {
@@ -415,6 +591,14 @@ impl Dataset {
         &self.tag_description
     }
 
+    /// Returns the unit of this dataset's 2D box and polygon coordinates.
+    ///
+    /// Datasets that do not report one are assumed to be
+    /// [`CoordinateSpace::Pixels`]; see [`CoordinateSpace`].
+    pub fn coordinate_space(&self) -> CoordinateSpace {
+        self.coordinate_space
+    }
+
     pub async fn project(&self, client: &Client) -> Result<crate::api::Project, Error> {
         client.project(self.project_id).await
     }
@@ -464,6 +648,20 @@ impl Dataset {
     ) -> Result<(), Error> {
         client.delete_samples(self.id, sample_ids).await
     }
+
+    pub async fn delete_sample(&self, client: &Client, sample_id: SampleID) -> Result<(), Error> {
+        client.delete_sample(self.id, sample_id).await
+    }
+
+    /// Checks an annotation set of this dataset for structural problems, see
+    /// [`Client::validate_dataset`].
+    pub async fn validate(
+        &self,
+        client: &Client,
+        annotation_set_id: AnnotationSetID,
+    ) -> Result<Vec<ValidationIssue>, Error> {
+        client.validate_dataset(self.id, annotation_set_id).await
+    }
 }
 
 /// The AnnotationSet class represents a collection of annotations in a dataset.
@@ -483,6 +681,8 @@ pub struct AnnotationSet {
     description: String,
     #[serde(rename = "date", default)]
     created: Option<DateTime<Utc>>,
+    #[serde(default, alias = "read_only")]
+    locked: bool,
 }
 
 impl Display for AnnotationSet {
@@ -528,6 +728,13 @@ impl AnnotationSet {
         self.created
     }
 
+    /// Returns `true` if the annotation set is locked (read-only), for
+    /// example after review. Annotations of a locked set cannot be added or
+    /// deleted; writes fail with [`Error::AnnotationSetLocked`].
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
     pub async fn dataset(&self, client: &Client) -> Result<Dataset, Error> {
         client
             .dataset(self.dataset_id.ok_or_else(|| {
@@ -1012,6 +1219,27 @@ impl Sample {
         &self.annotations
     }
 
+    /// Groups this sample's annotations by label name, keeping their
+    /// original order within each label.
+    ///
+    /// Annotations without a label are collected under the empty string.
+    pub fn annotations_by_label(&self) -> HashMap<String, Vec<&Annotation>> {
+        let mut groups: HashMap<String, Vec<&Annotation>> = HashMap::new();
+        for annotation in &self.annotations {
+            let label = annotation.label().cloned().unwrap_or_default();
+            groups.entry(label).or_default().push(annotation);
+        }
+        groups
+    }
+
+    /// URL of this sample's file of `file_type`, if it has one.
+    pub(crate) fn file_url(&self, file_type: &FileType) -> Option<&str> {
+        match file_type {
+            FileType::Image => self.image_url.as_deref(),
+            other => resolve_file(other, &self.files).and_then(|file| file.url()),
+        }
+    }
+
     pub fn with_annotations(mut self, annotations: Vec<Annotation>) -> Self {
         self.annotations = annotations;
         self
@@ -1028,10 +1256,20 @@ impl Sample {
     /// (inline base64-encoded data):
     /// 1. First tries to download from URL if available
     /// 2. Falls back to decoding inline base64 data for legacy datasets
+    ///
+    /// URL downloads go through the client's download cache when one is set
+    /// with [`Client::with_cache_dir`]. With `verify_checksums`, as with
+    /// [`DownloadOptions::verify_checksums`](crate::DownloadOptions::verify_checksums)
+    /// for whole datasets, they are checked against the checksum storage
+    /// reports for the file, if any. Storage is only asked for the checksum
+    /// when the presigned URL was signed for it; otherwise a checksum it
+    /// sends unasked is used. A file that does not match is downloaded once
+    /// more before returning [`Error::ChecksumMismatch`].
     pub async fn download(
         &self,
         client: &Client,
         file_type: FileType,
+        verify_checksums: bool,
     ) -> Result<Option<Vec<u8>>, Error> {
         use base64::{Engine, engine::general_purpose::STANDARD};
 
@@ -1040,7 +1278,7 @@ impl Sample {
             if let Some(url) = self.image_url.as_deref()
                 && is_valid_url(url)
             {
-                return Ok(Some(client.download(url).await?));
+                return Ok(Some(client.download_cached(url, verify_checksums).await?));
             }
             return Ok(None);
         }
@@ -1052,7 +1290,7 @@ impl Sample {
             Some(f) => {
                 // Prefer URL (newer datasets)
                 if let Some(url) = f.url() {
-                    return Ok(Some(client.download(url).await?));
+                    return Ok(Some(client.download_cached(url, verify_checksums).await?));
                 }
 
                 // Fall back to inline data (legacy datasets)
@@ -1109,6 +1347,88 @@ impl Sample {
             None => Ok(None),
         }
     }
+
+    /// Returns `true` if [`download`](Self::download) has something to fetch
+    /// for `file_type`: a URL or, for legacy datasets, inline data.
+    pub(crate) fn has_file(&self, file_type: &FileType) -> bool {
+        if *file_type == FileType::Image {
+            return self.image_url.as_deref().is_some_and(is_valid_url);
+        }
+        resolve_file(file_type, &self.files)
+            .is_some_and(|f| f.url().is_some() || f.data().is_some())
+    }
+}
+
+/// Overlap between the samples of two datasets, computed by
+/// [`Client::dataset_diff`](crate::Client::dataset_diff).
+///
+/// Samples are matched by UUID when both carry one, otherwise by image file
+/// name. A sample with neither is never matched.
+#[derive(Clone, Debug, Default)]
+pub struct DatasetDiff {
+    /// Samples of the first dataset with no match in the second.
+    pub only_a: Vec<Sample>,
+    /// Samples of the second dataset with no match in the first.
+    pub only_b: Vec<Sample>,
+    /// Matched samples, as `(sample in a, sample in b)` pairs.
+    pub common: Vec<(Sample, Sample)>,
+}
+
+impl DatasetDiff {
+    pub(crate) fn new(a: Vec<Sample>, b: Vec<Sample>) -> Self {
+        let mut by_uuid = HashMap::new();
+        let mut by_name = HashMap::new();
+        for (index, sample) in b.iter().enumerate() {
+            if let Some(uuid) = sample.uuid() {
+                by_uuid.entry(uuid.clone()).or_insert(index);
+            }
+            if let Some(name) = sample.image_name() {
+                by_name.entry(name.to_string()).or_insert(index);
+            }
+        }
+
+        let mut matched = vec![false; b.len()];
+        let mut pairs = Vec::new();
+        let mut only_a = Vec::new();
+        for sample in a {
+            let by_id = sample
+                .uuid()
+                .and_then(|uuid| by_uuid.get(uuid))
+                .copied()
+                .filter(|&index| !matched[index]);
+            // Fall back to the file name only when one side has no UUID: two
+            // samples with different UUIDs are different samples.
+            let index = by_id.or_else(|| {
+                sample
+                    .image_name()
+                    .and_then(|name| by_name.get(name))
+                    .copied()
+                    .filter(|&index| {
+                        !matched[index] && (sample.uuid().is_none() || b[index].uuid().is_none())
+                    })
+            });
+            match index {
+                Some(index) => {
+                    matched[index] = true;
+                    pairs.push((sample, index));
+                }
+                None => only_a.push(sample),
+            }
+        }
+
+        let mut b: Vec<Option<Sample>> = b.into_iter().map(Some).collect();
+        let common = pairs
+            .into_iter()
+            .filter_map(|(sample, index)| b[index].take().map(|other| (sample, other)))
+            .collect();
+        let only_b = b.into_iter().flatten().collect();
+
+        DatasetDiff {
+            only_a,
+            only_b,
+            common,
+        }
+    }
 }
 
 /// A file associated with a sample (e.g., LiDAR point cloud, radar data).
@@ -1331,7 +1651,33 @@ impl TypeName for Box3d {
     }
 }
 
+/// Checks box coordinates for [`Box2d::try_new`] and [`Box3d::try_new`]:
+/// every value must be finite and every dimension strictly positive.
+fn validate_box(
+    kind: &str,
+    position: &[(&str, f32)],
+    dimensions: &[(&str, f32)],
+) -> Result<(), Error> {
+    for (name, value) in position.iter().chain(dimensions) {
+        if !value.is_finite() {
+            return Err(Error::InvalidParameters(format!(
+                "{kind} {name} must be finite, got {value}"
+            )));
+        }
+    }
+    for (name, value) in dimensions {
+        if *value <= 0.0 {
+            return Err(Error::InvalidParameters(format!(
+                "{kind} {name} must be positive, got {value}"
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl Box3d {
+    /// Creates a box without validation. Zero, negative and non-finite
+    /// dimensions are stored as given; use [`Box3d::try_new`] to reject them.
     pub fn new(cx: f32, cy: f32, cz: f32, width: f32, height: f32, length: f32) -> Self {
         Self {
             x: cx,
@@ -1343,6 +1689,24 @@ impl Box3d {
         }
     }
 
+    /// Creates a box, rejecting NaN or infinite values and dimensions that
+    /// are zero or negative with [`Error::InvalidParameters`].
+    pub fn try_new(
+        cx: f32,
+        cy: f32,
+        cz: f32,
+        width: f32,
+        height: f32,
+        length: f32,
+    ) -> Result<Self, Error> {
+        validate_box(
+            "Box3d",
+            &[("cx", cx), ("cy", cy), ("cz", cz)],
+            &[("width", width), ("height", height), ("length", length)],
+        )?;
+        Ok(Self::new(cx, cy, cz, width, height, length))
+    }
+
     pub fn width(&self) -> f32 {
         self.w
     }
@@ -1378,6 +1742,11 @@ impl Box3d {
     pub fn front(&self) -> f32 {
         self.z - self.l / 2.0
     }
+
+    /// Volume of the box; zero when any dimension is zero, negative or NaN.
+    pub fn volume(&self) -> f32 {
+        self.w.max(0.0) * self.h.max(0.0) * self.l.max(0.0)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -1395,6 +1764,8 @@ impl TypeName for Box2d {
 }
 
 impl Box2d {
+    /// Creates a box without validation. Zero, negative and non-finite
+    /// dimensions are stored as given; use [`Box2d::try_new`] to reject them.
     pub fn new(left: f32, top: f32, width: f32, height: f32) -> Self {
         Self {
             x: left,
@@ -1404,6 +1775,17 @@ impl Box2d {
         }
     }
 
+    /// Creates a box, rejecting NaN or infinite values and a width or height
+    /// that is zero or negative with [`Error::InvalidParameters`].
+    pub fn try_new(left: f32, top: f32, width: f32, height: f32) -> Result<Self, Error> {
+        validate_box(
+            "Box2d",
+            &[("left", left), ("top", top)],
+            &[("width", width), ("height", height)],
+        )?;
+        Ok(Self::new(left, top, width, height))
+    }
+
     pub fn width(&self) -> f32 {
         self.w
     }
@@ -1427,6 +1809,33 @@ impl Box2d {
     pub fn cy(&self) -> f32 {
         self.y + self.h / 2.0
     }
+
+    /// Area of the box; zero when the width or height is zero, negative or
+    /// NaN.
+    pub fn area(&self) -> f32 {
+        self.w.max(0.0) * self.h.max(0.0)
+    }
+
+    /// The overlap of two boxes, or `None` when they do not overlap. Boxes
+    /// that only share an edge or corner do not overlap.
+    pub fn intersection(&self, other: &Box2d) -> Option<Box2d> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.w).min(other.x + other.w);
+        let bottom = (self.y + self.h).min(other.y + other.h);
+        (right > left && bottom > top).then(|| Box2d::new(left, top, right - left, bottom - top))
+    }
+
+    /// Intersection over union of two boxes, from 0.0 for disjoint boxes to
+    /// 1.0 for identical ones. A box with zero area gives 0.0, never NaN.
+    pub fn iou(&self, other: &Box2d) -> f32 {
+        let Some(intersection) = self.intersection(other) else {
+            return 0.0;
+        };
+        let overlap = intersection.area();
+        let union = self.area() + other.area() - overlap;
+        if union > 0.0 { overlap / union } else { 0.0 }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1444,6 +1853,71 @@ impl Polygon {
     pub fn new(rings: Vec<Vec<(f32, f32)>>) -> Self {
         Self { rings }
     }
+
+    /// Rasterizes the polygon onto a `width` x `height` image and encodes it
+    /// as compressed COCO RLE, the `counts` string pycocotools stores in
+    /// `{"size": [height, width], "counts": ...}`.
+    ///
+    /// The polygon is rasterized with
+    /// [`rasterize_polygon`](crate::coco::rasterize_polygon) and
+    /// [`FillRule::EvenOdd`](crate::coco::FillRule::EvenOdd) over all rings
+    /// together, so a ring inside another cuts a hole and self-intersecting
+    /// rings fill as they would in an SVG `evenodd` path. A polygon without a
+    /// ring of at least three points gives an empty RLE.
+    ///
+    /// COCO export rasterizes the same way but with
+    /// [`FillRule::Union`](crate::coco::FillRule::Union): there each ring is a
+    /// separate part of the object and inner rings are filled.
+    pub fn to_rle(&self, width: u32, height: u32) -> Vec<u8> {
+        let rings: Vec<Vec<(f64, f64)>> = self
+            .rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .filter(|(x, y)| x.is_finite() && y.is_finite())
+                    .map(|&(x, y)| (x as f64 * width as f64, y as f64 * height as f64))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|ring| ring.len() >= 3)
+            .collect();
+        if rings.is_empty() || width == 0 || height == 0 {
+            return Vec::new();
+        }
+
+        let mask =
+            crate::coco::rasterize_polygon(&rings, width, height, crate::coco::FillRule::EvenOdd);
+        crate::coco::encode_compressed_rle(&mask, width, height)
+            .expect("mask is sized width x height")
+            .counts
+            .into_bytes()
+    }
+
+    /// Decodes compressed COCO RLE of a `width` x `height` mask, as written
+    /// by [`to_rle`](Self::to_rle) or pycocotools, into a polygon normalized
+    /// to the image.
+    ///
+    /// Region boundaries are traced with
+    /// [`mask_to_contours`](crate::coco::mask_to_contours), as in COCO import,
+    /// so the rings follow the filled pixels rather than reproducing the
+    /// polygon that was encoded. An empty RLE gives an empty polygon.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CocoError`] if `rle` is not a valid counts string or
+    /// its runs do not cover the image.
+    pub fn from_rle(rle: &[u8], width: u32, height: u32) -> Result<Polygon, Error> {
+        if rle.is_empty() {
+            return Ok(Polygon::new(Vec::new()));
+        }
+        let counts = std::str::from_utf8(rle)
+            .map_err(|_| Error::CocoError("RLE counts are not ASCII".to_string()))?;
+        let segmentation =
+            crate::coco::CocoSegmentation::CompressedRle(crate::coco::CocoCompressedRle {
+                counts: counts.to_string(),
+                size: [height, width],
+            });
+        crate::coco::coco_segmentation_to_polygon(&segmentation, width, height)
+    }
 }
 
 impl serde::Serialize for Polygon {
@@ -1555,6 +2029,129 @@ fn parse_polygon_value(value: &serde_json::Value) -> Vec<Vec<(f32, f32)>> {
     result
 }
 
+/// Visibility flag of a keypoint, stored as COCO's `v` value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    /// Not labeled (`0`); the point's coordinates carry no meaning.
+    NotLabeled,
+    /// Labeled but occluded (`1`).
+    Occluded,
+    /// Labeled and visible (`2`).
+    Visible,
+}
+
+impl From<Visibility> for u8 {
+    fn from(visibility: Visibility) -> Self {
+        match visibility {
+            Visibility::NotLabeled => 0,
+            Visibility::Occluded => 1,
+            Visibility::Visible => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Visibility {
+    type Error = crate::Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(Visibility::NotLabeled),
+            1 => Ok(Visibility::Occluded),
+            2 => Ok(Visibility::Visible),
+            _ => Err(crate::Error::InvalidParameters(format!(
+                "keypoint visibility must be 0, 1 or 2, got {}",
+                v
+            ))),
+        }
+    }
+}
+
+/// Keypoints of a pose annotation, such as the joints of a person.
+///
+/// Coordinates are normalized to the image like [`Polygon`] vertices, and
+/// points keep their order, which a dataset's skeleton refers to by index.
+/// On the wire each point is `[x, y, v]` with the COCO visibility flag `v`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keypoints {
+    pub points: Vec<(f32, f32, Visibility)>,
+}
+
+impl TypeName for Keypoints {
+    fn type_name() -> String {
+        "keypoints".to_owned()
+    }
+}
+
+impl Keypoints {
+    pub fn new(points: Vec<(f32, f32, Visibility)>) -> Self {
+        Self { points }
+    }
+
+    /// Number of labeled points, visible or occluded: COCO's
+    /// `num_keypoints`.
+    pub fn num_labeled(&self) -> usize {
+        self.points
+            .iter()
+            .filter(|(_, _, v)| *v != Visibility::NotLabeled)
+            .count()
+    }
+}
+
+impl serde::Serialize for Keypoints {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.points.len()))?;
+        for &(x, y, v) in &self.points {
+            seq.serialize_element(&(x, y, u8::from(v)))?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Keypoints {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let values = value.as_array().map(Vec::as_slice).unwrap_or_default();
+
+        // Accept `[[x, y, v], ...]` as well as COCO's flat `[x, y, v, ...]`
+        let triples: Vec<&[serde_json::Value]> = if values.first().is_some_and(|v| v.is_array()) {
+            values
+                .iter()
+                .filter_map(|point| point.as_array().map(Vec::as_slice))
+                .collect()
+        } else {
+            values.chunks(3).collect()
+        };
+
+        let mut points = Vec::with_capacity(triples.len());
+        for triple in triples {
+            let [x, y, v] = triple else {
+                return Err(serde::de::Error::custom(
+                    "keypoint must have x, y and visibility",
+                ));
+            };
+            let coordinate = |c: &serde_json::Value| {
+                c.as_f64()
+                    .map(|c| c as f32)
+                    .ok_or_else(|| serde::de::Error::custom("keypoint coordinate is not a number"))
+            };
+            let visibility = v
+                .as_u64()
+                .and_then(|v| u8::try_from(v).ok())
+                .and_then(|v| Visibility::try_from(v).ok())
+                .ok_or_else(|| serde::de::Error::custom("keypoint visibility must be 0, 1 or 2"))?;
+            points.push((coordinate(x)?, coordinate(y)?, visibility));
+        }
+        Ok(Self { points })
+    }
+}
+
 /// Helper struct for deserializing annotations from the server.
 ///
 /// The server sends bounding box coordinates as flat fields (x, y, w, h) at the
@@ -1588,6 +2185,8 @@ struct AnnotationRaw {
     box3d: Option<Box3d>,
     #[serde(default, alias = "mask")]
     polygon: Option<Polygon>,
+    #[serde(default)]
+    keypoints: Option<Keypoints>,
     // Flat box2d fields from server (x, y, w, h at annotation level)
     #[serde(default)]
     x: Option<f64>,
@@ -1597,6 +2196,8 @@ struct AnnotationRaw {
     w: Option<f64>,
     #[serde(default)]
     h: Option<f64>,
+    #[serde(default)]
+    attributes: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -1650,6 +2251,9 @@ pub struct Annotation {
     /// PNG-encoded raster mask (populated from Arrow, not from Studio JSON-RPC).
     #[serde(skip)]
     mask: Option<MaskData>,
+    /// Pose keypoints, sent as `[[x, y, v], ...]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keypoints: Option<Keypoints>,
     /// Detection confidence score for box2d (0..1).
     #[serde(skip_serializing_if = "Option::is_none")]
     box2d_score: Option<f32>,
@@ -1662,6 +2266,9 @@ pub struct Annotation {
     /// Confidence score for mask (0..1).
     #[serde(skip_serializing_if = "Option::is_none")]
     mask_score: Option<f32>,
+    /// Free-form annotation attributes such as `occluded` or `truncated`.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    attributes: HashMap<String, serde_json::Value>,
 }
 
 impl<'de> serde::Deserialize<'de> for Annotation {
@@ -1695,10 +2302,12 @@ impl<'de> serde::Deserialize<'de> for Annotation {
             box3d: raw.box3d,
             polygon: raw.polygon,
             mask: None,
+            keypoints: raw.keypoints,
             box2d_score: None,
             box3d_score: None,
             polygon_score: None,
             mask_score: None,
+            attributes: raw.attributes,
         })
     }
 }
@@ -1726,10 +2335,12 @@ impl Annotation {
             box3d: None,
             polygon: None,
             mask: None,
+            keypoints: None,
             box2d_score: None,
             box3d_score: None,
             polygon_score: None,
             mask_score: None,
+            attributes: HashMap::new(),
         }
     }
 
@@ -1845,6 +2456,14 @@ impl Annotation {
         self.mask = mask;
     }
 
+    pub fn keypoints(&self) -> Option<&Keypoints> {
+        self.keypoints.as_ref()
+    }
+
+    pub fn set_keypoints(&mut self, keypoints: Option<Keypoints>) {
+        self.keypoints = keypoints;
+    }
+
     pub fn box2d_score(&self) -> Option<f32> {
         self.box2d_score
     }
@@ -1876,6 +2495,14 @@ impl Annotation {
     pub fn set_mask_score(&mut self, score: Option<f32>) {
         self.mask_score = score;
     }
+
+    pub fn attributes(&self) -> &HashMap<String, serde_json::Value> {
+        &self.attributes
+    }
+
+    pub fn set_attributes(&mut self, attributes: HashMap<String, serde_json::Value>) {
+        self.attributes = attributes;
+    }
 }
 
 /// A label used to identify annotations in a dataset.
@@ -2071,25 +2698,71 @@ fn convert_polygon_to_nested_series(polygon: &Polygon) -> Series {
 /// - `not_exhaustive_label_indices`: Incomplete label indices (List<UInt32>)
 /// - `timing`: Pipeline timing (Struct{load, preprocess, inference, decode} of Int64)
 ///
+/// With `include_attributes`, each key of the annotations'
+/// [`attributes`](Annotation::attributes) adds an `attr_<key>` column after
+/// these. A key whose values are all booleans, all numbers or all strings
+/// gets a Boolean, Float64 or String column; mixed or nested values are
+/// written as JSON text.
+///
 /// # Example
 ///
 /// ```rust,no_run
-/// use edgefirst_client::{Client, samples_dataframe};
+/// use edgefirst_client::{Client, SamplesQuery, samples_dataframe};
 ///
 /// # async fn example() -> Result<(), edgefirst_client::Error> {
 /// # let client = Client::new()?;
 /// # let dataset_id = 1.into();
 /// # let annotation_set_id = 1.into();
-/// let samples = client
-///     .samples(dataset_id, Some(annotation_set_id), &[], &[], &[], None, None)
-///     .await?;
-/// let df = samples_dataframe(&samples)?;
+/// let query = SamplesQuery {
+///     annotation_set_id: Some(annotation_set_id),
+///     ..Default::default()
+/// };
+/// let samples = client.samples(dataset_id, None, query).await?;
+/// let df = samples_dataframe(&samples, false)?;
 /// println!("DataFrame shape: {:?}", df.shape());
 /// # Ok(())
 /// # }
 /// ```
 #[cfg(feature = "polars")]
-pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
+pub fn samples_dataframe(samples: &[Sample], include_attributes: bool) -> Result<DataFrame, Error> {
+    let all_columns = samples_columns(samples, include_attributes)?;
+    let height = all_columns.first().map(|c| c.len()).unwrap_or(0);
+
+    let non_empty_columns: Vec<Column> = all_columns
+        .into_iter()
+        .filter(|col| col.name() == "name" || !is_all_null_column(col))
+        .collect();
+
+    Ok(DataFrame::new(height, non_empty_columns)?)
+}
+
+/// Create a DataFrame from a slice of samples keeping every schema column.
+///
+/// Unlike [`samples_dataframe`], all-null columns are kept and typed, so
+/// frames built from different slices of a dataset share one schema and can
+/// be written as consecutive Parquet row groups.
+#[cfg(feature = "polars")]
+pub(crate) fn samples_dataframe_full(samples: &[Sample]) -> Result<DataFrame, Error> {
+    let mut columns = samples_columns(samples, false)?;
+    let height = columns.first().map(|c| c.len()).unwrap_or(0);
+
+    // An all-null polygon column is built untyped; give it the list type it
+    // has whenever any polygon is present.
+    for col in &mut columns {
+        if col.name() == "polygon" && col.dtype() == &DataType::Null {
+            *col = col.cast(&DataType::List(Box::new(DataType::List(Box::new(
+                DataType::Float32,
+            )))))?;
+        }
+    }
+
+    Ok(DataFrame::new(height, columns)?)
+}
+
+/// Builds every column of the samples schema, including all-null ones, and
+/// the `attr_<key>` columns when `include_attributes` is set.
+#[cfg(feature = "polars")]
+fn samples_columns(samples: &[Sample], include_attributes: bool) -> Result<Vec<Column>, Error> {
     // Collect per-row vectors directly while iterating samples
     let mut names: Vec<String> = Vec::new();
     let mut frames: Vec<Option<u32>> = Vec::new();
@@ -2117,6 +2790,7 @@ pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
     let mut timing_preprocess: Vec<Option<i64>> = Vec::new();
     let mut timing_inference: Vec<Option<i64>> = Vec::new();
     let mut timing_decode: Vec<Option<i64>> = Vec::new();
+    let mut attributes: Vec<Option<&HashMap<String, serde_json::Value>>> = Vec::new();
 
     for sample in samples {
         // Extract sample metadata once per sample
@@ -2184,6 +2858,7 @@ pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
             mask_scores.push(None);
             iscrowds.push(None);
             category_frequencies.push(None);
+            attributes.push(None);
             push_sample_fields!();
         } else {
             // One row per annotation
@@ -2221,6 +2896,7 @@ pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
                 mask_scores.push(ann.mask_score());
                 iscrowds.push(ann.iscrowd);
                 category_frequencies.push(ann.category_frequency.clone());
+                attributes.push(Some(&ann.attributes));
                 push_sample_fields!();
             }
         }
@@ -2381,8 +3057,8 @@ pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
     .into_series()
     .into();
 
-    // Collect all columns, then drop any where ALL values are null (except "name")
-    let all_columns: Vec<Column> = vec![
+    // Collect all columns; callers decide whether to drop all-null ones
+    let mut all_columns: Vec<Column> = vec![
         names_col,
         frames_col,
         objects_col,
@@ -2408,14 +3084,60 @@ pub fn samples_dataframe(samples: &[Sample]) -> Result<DataFrame, Error> {
         timing_col,
     ];
 
-    let height = all_columns.first().map(|c| c.len()).unwrap_or(0);
+    if include_attributes {
+        all_columns.extend(attribute_columns(&attributes));
+    }
 
-    let non_empty_columns: Vec<Column> = all_columns
-        .into_iter()
-        .filter(|col| col.name() == "name" || !is_all_null_column(col))
+    Ok(all_columns)
+}
+
+/// Flattens per-row annotation attributes into one `attr_<key>` column per
+/// key, in key order. Rows without the key are null.
+#[cfg(feature = "polars")]
+fn attribute_columns(rows: &[Option<&HashMap<String, serde_json::Value>>]) -> Vec<Column> {
+    use serde_json::Value;
+
+    let keys: std::collections::BTreeSet<&String> = rows
+        .iter()
+        .flatten()
+        .flat_map(|attrs| attrs.keys())
         .collect();
 
-    Ok(DataFrame::new(height, non_empty_columns)?)
+    keys.into_iter()
+        .map(|key| {
+            let name = format!("attr_{}", key);
+            let values: Vec<Option<&Value>> = rows
+                .iter()
+                .map(|row| {
+                    row.and_then(|attrs| attrs.get(key))
+                        .filter(|v| !v.is_null())
+                })
+                .collect();
+            let present = || values.iter().flatten();
+
+            let series = if present().all(|v| v.is_boolean()) {
+                let bools: Vec<Option<bool>> =
+                    values.iter().map(|v| v.and_then(Value::as_bool)).collect();
+                Series::new(name.into(), bools)
+            } else if present().all(|v| v.is_number()) {
+                let numbers: Vec<Option<f64>> =
+                    values.iter().map(|v| v.and_then(Value::as_f64)).collect();
+                Series::new(name.into(), numbers)
+            } else {
+                let text: Vec<Option<String>> = values
+                    .iter()
+                    .map(|v| {
+                        v.map(|v| match v {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        })
+                    })
+                    .collect();
+                Series::new(name.into(), text)
+            };
+            series.into()
+        })
+        .collect()
 }
 
 /// Returns `true` when every value in the column is null. For `Struct`
@@ -2881,10 +3603,10 @@ mod tests {
             AnnotationType::Polygon
         );
 
-        // Invalid defaults to Box2d for backward compatibility
+        // Unknown names are kept as Other rather than guessed
         assert_eq!(
             AnnotationType::from("invalid".to_string()),
-            AnnotationType::Box2d
+            AnnotationType::Other("invalid".to_string())
         );
 
         // Test: Invalid input
@@ -2906,6 +3628,92 @@ mod tests {
             AnnotationType::try_from(AnnotationType::Polygon.to_string().as_str()).unwrap(),
             AnnotationType::Polygon
         );
+        assert_eq!(
+            AnnotationType::try_from(AnnotationType::Keypoints.to_string().as_str()).unwrap(),
+            AnnotationType::Keypoints
+        );
+    }
+
+    #[test]
+    fn test_keypoints_serde_round_trip() {
+        let keypoints = Keypoints::new(vec![
+            (0.5, 0.25, Visibility::Visible),
+            (0.0, 0.0, Visibility::NotLabeled),
+            (0.75, 0.5, Visibility::Occluded),
+        ]);
+        let json = serde_json::to_value(&keypoints).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([[0.5, 0.25, 2], [0.0, 0.0, 0], [0.75, 0.5, 1]])
+        );
+        assert_eq!(
+            serde_json::from_value::<Keypoints>(json).unwrap(),
+            keypoints
+        );
+
+        // COCO's flat list reads the same.
+        let flat = serde_json::json!([0.5, 0.25, 2, 0.0, 0.0, 0, 0.75, 0.5, 1]);
+        assert_eq!(
+            serde_json::from_value::<Keypoints>(flat).unwrap(),
+            keypoints
+        );
+        assert_eq!(keypoints.num_labeled(), 2);
+
+        assert!(serde_json::from_value::<Keypoints>(serde_json::json!([[0.5, 0.5, 3]])).is_err());
+        assert!(serde_json::from_value::<Keypoints>(serde_json::json!([0.5, 0.5])).is_err());
+    }
+
+    #[test]
+    fn test_annotation_keypoints_round_trip() {
+        let mut annotation = Annotation::new();
+        annotation.set_label(Some("person".to_string()));
+        annotation.set_keypoints(Some(Keypoints::new(vec![(0.5, 0.25, Visibility::Visible)])));
+
+        let json = serde_json::to_value(&annotation).unwrap();
+        assert_eq!(json["keypoints"], serde_json::json!([[0.5, 0.25, 2]]));
+
+        let parsed: Annotation = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.keypoints(), annotation.keypoints());
+        assert_eq!(
+            serde_json::to_value(Annotation::new())
+                .unwrap()
+                .get("keypoints"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_visibility_coco_flag() {
+        for v in [
+            Visibility::NotLabeled,
+            Visibility::Occluded,
+            Visibility::Visible,
+        ] {
+            assert_eq!(Visibility::try_from(u8::from(v)).unwrap(), v);
+        }
+        assert!(matches!(
+            Visibility::try_from(3),
+            Err(crate::Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_annotation_type_unknown_is_preserved_as_other() {
+        // A type added to the server later must not fail or turn into a box.
+        let polyline = AnnotationType::from("polyline".to_string());
+        assert_eq!(polyline, AnnotationType::Other("polyline".to_string()));
+        assert_eq!(AnnotationType::from(&"polyline".to_string()), polyline);
+
+        // The name round-trips unchanged for display and server filters.
+        assert_eq!(polyline.to_string(), "polyline");
+        assert_eq!(polyline.as_server_type(), "polyline");
+        assert_eq!(AnnotationType::from(polyline.to_string()), polyline);
+
+        // Strict parsing still rejects it.
+        assert!(matches!(
+            AnnotationType::try_from("polyline"),
+            Err(crate::Error::InvalidAnnotationType(name)) if name == "polyline"
+        ));
     }
 
     #[test]
@@ -3393,6 +4201,31 @@ mod tests {
     }
 
     // ==== Box3d Tests ====
+    #[test]
+    fn test_box2d_iou_and_intersection() {
+        let a = Box2d::new(0.0, 0.0, 0.4, 0.4);
+        let b = Box2d::new(0.2, 0.2, 0.4, 0.4);
+        assert!((a.area() - 0.16).abs() < 1e-6);
+        let overlap = a.intersection(&b).unwrap();
+        assert!((overlap.left() - 0.2).abs() < 1e-6);
+        assert!((overlap.width() - 0.2).abs() < 1e-6);
+        // 0.04 / (0.16 + 0.16 - 0.04)
+        assert!((a.iou(&b) - 1.0 / 7.0).abs() < 1e-6);
+        assert!((a.iou(&a) - 1.0).abs() < 1e-6);
+
+        // Disjoint and edge-touching boxes do not intersect.
+        let far = Box2d::new(0.5, 0.5, 0.1, 0.1);
+        assert_eq!(a.intersection(&far), None);
+        assert_eq!(a.intersection(&Box2d::new(0.4, 0.0, 0.1, 0.4)), None);
+        assert_eq!(a.iou(&far), 0.0);
+
+        // Degenerate boxes give 0.0 rather than NaN.
+        let flat = Box2d::new(0.1, 0.1, 0.0, 0.2);
+        assert_eq!(flat.area(), 0.0);
+        assert_eq!(flat.iou(&flat), 0.0);
+        assert_eq!(flat.iou(&a), 0.0);
+    }
+
     #[test]
     fn test_box3d_construction_and_accessors() {
         // Test case 1: Basic 3D construction
@@ -3452,7 +4285,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_box3d_volume() {
+        assert_eq!(Box3d::new(0.0, 0.0, 0.0, 2.0, 3.0, 4.0).volume(), 24.0);
+        assert_eq!(Box3d::new(0.0, 0.0, 0.0, 2.0, -3.0, 4.0).volume(), 0.0);
+    }
+
+    #[test]
+    fn test_box2d_try_new_accepts_positive_dimensions() {
+        let bbox = Box2d::try_new(0.1, 0.2, 0.3, 0.4).unwrap();
+        assert_eq!(bbox, Box2d::new(0.1, 0.2, 0.3, 0.4));
+    }
+
+    #[test]
+    fn test_box2d_try_new_rejects_invalid_dimensions() {
+        for (w, h) in [
+            (-0.1, 0.2),
+            (0.1, -0.2),
+            (0.0, 0.2),
+            (0.1, 0.0),
+            (f32::NAN, 0.2),
+        ] {
+            assert!(
+                matches!(
+                    Box2d::try_new(0.1, 0.1, w, h),
+                    Err(Error::InvalidParameters(_))
+                ),
+                "width={w} height={h} should be rejected"
+            );
+        }
+        assert!(Box2d::try_new(f32::NAN, 0.1, 0.2, 0.2).is_err());
+        assert!(Box2d::try_new(0.1, 0.1, f32::INFINITY, 0.2).is_err());
+    }
+
+    #[test]
+    fn test_box3d_try_new_rejects_invalid_dimensions() {
+        assert!(Box3d::try_new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0).is_ok());
+        for (w, h, l) in [
+            (-4.0, 5.0, 6.0),
+            (4.0, 0.0, 6.0),
+            (4.0, 5.0, -6.0),
+            (4.0, 5.0, f32::NAN),
+        ] {
+            assert!(
+                matches!(
+                    Box3d::try_new(1.0, 2.0, 3.0, w, h, l),
+                    Err(Error::InvalidParameters(_))
+                ),
+                "width={w} height={h} length={l} should be rejected"
+            );
+        }
+        assert!(Box3d::try_new(1.0, f32::NAN, 3.0, 4.0, 5.0, 6.0).is_err());
+    }
+
     // ==== Polygon Tests ====
+    #[test]
+    fn test_polygon_rle_fills_holes_and_self_intersections() {
+        // 8x8 image: a 6x6 square with a 2x2 hole in the middle.
+        let square =
+            |x0: f32, y0: f32, x1: f32, y1: f32| vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+        let holed = Polygon::new(vec![
+            square(0.125, 0.125, 0.875, 0.875),
+            square(0.375, 0.375, 0.625, 0.625),
+        ]);
+        let decoded = |polygon: &Polygon| {
+            let rle = polygon.to_rle(8, 8);
+            let compressed = crate::coco::CocoCompressedRle {
+                counts: String::from_utf8(rle).unwrap(),
+                size: [8, 8],
+            };
+            crate::coco::decode_compressed_rle(&compressed).unwrap().0
+        };
+        let mask = decoded(&holed);
+        assert_eq!(mask.iter().filter(|&&p| p == 1).count(), 36 - 4);
+        assert_eq!(mask[3 * 8 + 3], 0, "hole is empty");
+        assert_eq!(mask[8 + 1], 1);
+
+        // A bow tie crossing itself at the centre fills both triangles.
+        let bow_tie = Polygon::new(vec![vec![(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]]);
+        let mask = decoded(&bow_tie);
+        assert_eq!(mask[4 * 8], 1, "left lobe");
+        assert_eq!(mask[4 * 8 + 7], 1, "right lobe");
+        assert_eq!(mask[4], 0, "above the crossing");
+
+        // Decoding traces the filled pixels, all within the outer square.
+        let polygon = Polygon::from_rle(&holed.to_rle(8, 8), 8, 8).unwrap();
+        assert!(!polygon.rings.is_empty());
+        assert!(
+            polygon.rings[0]
+                .iter()
+                .all(|&(x, y)| { (0.125..0.875).contains(&x) && (0.125..0.875).contains(&y) })
+        );
+    }
+
+    #[test]
+    fn test_polygon_rle_empty() {
+        assert!(Polygon::new(vec![]).to_rle(8, 8).is_empty());
+        assert!(
+            Polygon::new(vec![vec![(0.0, 0.0), (1.0, 1.0)]])
+                .to_rle(8, 8)
+                .is_empty()
+        );
+        assert!(Polygon::from_rle(b"", 8, 8).unwrap().rings.is_empty());
+        assert!(matches!(
+            Polygon::from_rle(b"1", 8, 8),
+            Err(Error::CocoError(_))
+        ));
+    }
+
     #[test]
     fn test_polygon_creation_and_deserialization() {
         // Test case 1: Direct construction
@@ -3807,7 +4747,7 @@ mod tests {
         let samples = vec![sample_with_ann, sample_no_ann];
 
         // Convert to DataFrame
-        let df = samples_dataframe(&samples).expect("Failed to create DataFrame");
+        let df = samples_dataframe(&samples, false).expect("Failed to create DataFrame");
 
         // Verify we have 2 rows (one per sample)
         assert_eq!(df.height(), 2, "Expected 2 rows (one per sample)");
@@ -3871,7 +4811,7 @@ mod tests {
 
         let samples = vec![sample1, sample2, sample3];
 
-        let df = samples_dataframe(&samples).expect("Failed to create DataFrame");
+        let df = samples_dataframe(&samples, false).expect("Failed to create DataFrame");
 
         // We should have exactly 3 rows - one per sample
         assert_eq!(
@@ -3915,7 +4855,7 @@ mod tests {
         sample.group = Some("test_group".to_string());
         sample.annotations = vec![];
 
-        let df = samples_dataframe(&[sample]).expect("Failed to create DataFrame");
+        let df = samples_dataframe(&[sample], false).expect("Failed to create DataFrame");
 
         let groups_col = df.column("group").expect("group column");
 
@@ -3958,7 +4898,7 @@ mod tests {
 
         sample.annotations = vec![ann1, ann2, ann3];
 
-        let df = samples_dataframe(&[sample]).expect("Failed to create DataFrame");
+        let df = samples_dataframe(&[sample], false).expect("Failed to create DataFrame");
 
         // Should have 3 rows (one per annotation)
         assert_eq!(df.height(), 3, "Expected 3 rows (one per annotation)");
@@ -4005,7 +4945,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // Verify LVIS columns are present (they have data)
         assert!(df.column("iscrowd").is_ok(), "iscrowd column missing");
@@ -4033,6 +4973,65 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "polars")]
+    #[test]
+    fn test_samples_dataframe_attribute_columns() {
+        let mut occluded = Annotation::new();
+        occluded.set_name(Some("a".to_string()));
+        occluded.set_attributes(HashMap::from([
+            ("occluded".to_string(), serde_json::json!(true)),
+            ("truncation".to_string(), serde_json::json!(0.25)),
+            ("color".to_string(), serde_json::json!("red")),
+        ]));
+        let mut plain = Annotation::new();
+        plain.set_name(Some("a".to_string()));
+        plain.set_attributes(HashMap::from([(
+            "color".to_string(),
+            serde_json::json!({ "rgb": [255, 0, 0] }),
+        )]));
+
+        let sample = Sample {
+            image_name: Some("a.jpg".to_string()),
+            annotations: vec![occluded, plain],
+            ..Default::default()
+        };
+
+        let df = samples_dataframe(std::slice::from_ref(&sample), false).unwrap();
+        assert!(df.column("attr_occluded").is_err());
+
+        let df = samples_dataframe(&[sample], true).unwrap();
+        let occluded = df.column("attr_occluded").unwrap();
+        assert_eq!(occluded.dtype(), &DataType::Boolean);
+        assert_eq!(occluded.bool().unwrap().get(0), Some(true));
+        assert_eq!(occluded.bool().unwrap().get(1), None);
+
+        let truncation = df.column("attr_truncation").unwrap();
+        assert_eq!(truncation.f64().unwrap().get(0), Some(0.25));
+
+        let color = df.column("attr_color").unwrap();
+        assert_eq!(color.str().unwrap().get(0), Some("red"));
+        assert_eq!(color.str().unwrap().get(1), Some(r#"{"rgb":[255,0,0]}"#));
+    }
+
+    #[test]
+    fn test_annotation_attributes_round_trip() {
+        let ann: Annotation = serde_json::from_value(serde_json::json!({
+            "label_name": "car",
+            "attributes": { "occluded": true },
+        }))
+        .unwrap();
+        assert_eq!(ann.attributes()["occluded"], serde_json::json!(true));
+        assert_eq!(
+            serde_json::to_value(&ann).unwrap()["attributes"],
+            serde_json::json!({ "occluded": true })
+        );
+        assert!(
+            !serde_json::to_string(&Annotation::new())
+                .unwrap()
+                .contains("attributes")
+        );
+    }
+
     #[test]
     fn test_annotation_serialization_skips_lvis_fields() {
         let ann = Annotation::new();
@@ -4061,6 +5060,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sample_degradation_round_trips() {
+        let mut sample = Sample::new();
+        sample.degradation = Some("blur".to_string());
+        let json = serde_json::to_value(&sample).unwrap();
+        assert_eq!(json["degradation"], "blur");
+
+        let sample: Sample = serde_json::from_value(json).unwrap();
+        assert_eq!(sample.degradation.as_deref(), Some("blur"));
+    }
+
     #[test]
     fn test_annotation_score_fields() {
         let mut ann = Annotation::default();
@@ -4100,6 +5110,36 @@ mod tests {
         assert!(sample.timing.is_some());
     }
 
+    #[test]
+    fn test_sample_annotations_by_label() {
+        let labeled = |name: Option<&str>, object_id: &str| {
+            let mut ann = Annotation::new();
+            ann.set_label(name.map(str::to_string));
+            ann.set_object_id(Some(object_id.to_string()));
+            ann
+        };
+        let sample = Sample::new().with_annotations(vec![
+            labeled(Some("car"), "a"),
+            labeled(Some("person"), "b"),
+            labeled(None, "c"),
+            labeled(Some("car"), "d"),
+        ]);
+
+        let groups = sample.annotations_by_label();
+        assert_eq!(groups.len(), 3);
+        let ids = |label: &str| -> Vec<&str> {
+            groups[label]
+                .iter()
+                .map(|ann| ann.object_id().unwrap().as_str())
+                .collect()
+        };
+        assert_eq!(ids("car"), vec!["a", "d"]);
+        assert_eq!(ids("person"), vec!["b"]);
+        assert_eq!(ids(""), vec!["c"]);
+
+        assert!(Sample::new().annotations_by_label().is_empty());
+    }
+
     // =========================================================================
     // samples_dataframe 2026.04 schema tests
     // =========================================================================
@@ -4121,7 +5161,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // 2026.04: polygon column exists with nested List(List(Float32))
         assert!(df.column("polygon").is_ok(), "Should have polygon column");
@@ -4147,7 +5187,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // name is always present
         assert!(df.column("name").is_ok(), "name column must always exist");
@@ -4179,6 +5219,45 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "polars")]
+    #[test]
+    fn test_samples_dataframe_full_schema_does_not_depend_on_samples() {
+        let mut ann = Annotation::new();
+        ann.set_name(Some("test".to_string()));
+        ann.set_label(Some("person".to_string()));
+        ann.set_label_index(Some(1));
+        ann.set_box2d(Some(Box2d::new(0.1, 0.2, 0.3, 0.4)));
+        ann.set_polygon(Some(Polygon::new(vec![vec![
+            (0.1, 0.2),
+            (0.3, 0.4),
+            (0.5, 0.6),
+        ]])));
+        ann.set_iscrowd(Some(false));
+        ann.set_category_frequency(Some("f".to_string()));
+        let annotated = Sample {
+            image_name: Some("a.jpg".to_string()),
+            group: Some("train".to_string()),
+            width: Some(640),
+            height: Some(480),
+            annotations: vec![ann],
+            neg_label_indices: Some(vec![5]),
+            ..Default::default()
+        };
+        let bare = Sample {
+            image_name: Some("b.jpg".to_string()),
+            ..Default::default()
+        };
+
+        let mut first = samples_dataframe_full(&[annotated]).unwrap();
+        let second = samples_dataframe_full(&[bare]).unwrap();
+        let empty = samples_dataframe_full(&[]).unwrap();
+        assert_eq!(first.schema(), second.schema());
+        assert_eq!(first.schema(), empty.schema());
+
+        first.vstack_mut(&second).unwrap();
+        assert_eq!(first.height(), 2);
+    }
+
     #[cfg(feature = "polars")]
     #[test]
     fn test_samples_dataframe_size_column() {
@@ -4196,7 +5275,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample1, sample2]).unwrap();
+        let df = samples_dataframe(&[sample1, sample2], false).unwrap();
 
         // Size column should be present (not dropped by all-null rule)
         let size_col = df
@@ -4231,7 +5310,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample1, sample2]).unwrap();
+        let df = samples_dataframe(&[sample1, sample2], false).unwrap();
 
         // Size column should be present (not all null)
         let size_col = df
@@ -4261,7 +5340,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // Score columns with data should be present
         assert!(
@@ -4308,7 +5387,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // Timing column should exist (has data)
         assert!(df.column("timing").is_ok(), "timing column missing");
@@ -4338,7 +5417,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // mask column should exist with Binary type
         let mask_col = df.column("mask").unwrap();
@@ -4385,7 +5464,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         // Timing column should be present because at least one field is non-null
         assert!(
@@ -4409,7 +5488,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         assert!(
             df.column("timing").is_err(),
@@ -4436,7 +5515,7 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         let scores = df.column("box2d_score").unwrap();
         let val = scores.f32().unwrap().get(0);
@@ -4457,12 +5536,56 @@ mod tests {
             ..Default::default()
         };
 
-        let df = samples_dataframe(&[sample]).unwrap();
+        let df = samples_dataframe(&[sample], false).unwrap();
 
         let scores = df.column("box2d_score").unwrap();
         let val = scores.f32().unwrap().get(0);
         assert_eq!(val, Some(1.0), "score of 1.0 should survive as non-null");
     }
+
+    #[test]
+    fn test_dataset_diff_matches_uuid_before_file_name() {
+        let sample = |uuid: Option<&str>, name: &str| Sample {
+            uuid: uuid.map(str::to_string),
+            image_name: Some(name.to_string()),
+            ..Default::default()
+        };
+
+        let diff = DatasetDiff::new(
+            vec![
+                sample(Some("u1"), "renamed.jpg"),
+                sample(Some("u2"), "same.jpg"),
+                sample(None, "legacy.jpg"),
+            ],
+            vec![
+                sample(Some("u1"), "original.jpg"),
+                sample(Some("u3"), "same.jpg"),
+                sample(Some("u4"), "legacy.jpg"),
+            ],
+        );
+
+        let names = |samples: &[Sample]| -> Vec<String> {
+            samples
+                .iter()
+                .map(|s| s.image_name().unwrap().to_string())
+                .collect()
+        };
+        let common: Vec<_> = diff
+            .common
+            .iter()
+            .map(|(a, b)| (a.image_name().unwrap(), b.image_name().unwrap()))
+            .collect();
+        assert_eq!(
+            common,
+            [
+                ("renamed.jpg", "original.jpg"),
+                ("legacy.jpg", "legacy.jpg")
+            ]
+        );
+        // Same file name but different UUIDs: distinct samples.
+        assert_eq!(names(&diff.only_a), ["same.jpg"]);
+        assert_eq!(names(&diff.only_b), ["same.jpg"]);
+    }
 }
 
 #[cfg(test)]
@@ -4568,5 +5691,7 @@ mod versioning_deser_tests {
         assert_eq!(dataset.tag_id(), None);
         assert_eq!(dataset.tag(), "");
         assert_eq!(dataset.tag_description(), "");
+        // Without a reported space, coordinates are assumed to be pixels.
+        assert_eq!(dataset.coordinate_space(), CoordinateSpace::Pixels);
     }
 }
@@ -24,7 +24,14 @@ pub enum Error {
     /// URL parsing error.
     UrlParseError(url::ParseError),
     /// RPC error with error code and message from the server.
-    RpcError(i32, String),
+    RpcError {
+        /// Category of `code`, see [`RpcErrorKind::from_code`]
+        kind: RpcErrorKind,
+        /// Error code as sent by the server, kept for debugging
+        code: i32,
+        /// Error message from the server
+        message: String,
+    },
     /// Invalid RPC request ID format.
     InvalidRpcId(String),
     /// Environment variable error.
@@ -99,7 +106,116 @@ pub enum Error {
     /// HTTP would leak them in the clear. Loopback URLs (`127.0.0.1`,
     /// `::1`, `localhost`) are permitted because traffic never leaves
     /// the machine — that's how wiremock and local dev servers connect.
+    /// [`Client::with_allow_insecure`](crate::Client::with_allow_insecure)
+    /// lifts the restriction for remote hosts.
     InsecureUrl(String),
+    /// The operation did not finish within the per-call timeout it was
+    /// given, or an API request exceeded the client's request timeout (see
+    /// [`Client::with_timeout`](crate::Client::with_timeout)). Carries the
+    /// timeout that elapsed.
+    Timeout(std::time::Duration),
+    /// One or more samples of a populate call failed to upload their files.
+    /// See [`PopulateReport`](crate::PopulateReport) for per-sample outcomes.
+    PopulateFailed {
+        /// Number of samples that failed
+        failed: usize,
+        /// Number of samples in the call
+        total: usize,
+        /// Description of the first failure
+        first_error: String,
+    },
+    /// Storage kept rejecting the presigned URL of a sample file after the
+    /// client fetched a fresh one. Carries the sample name.
+    UrlExpired(String),
+    /// The annotation set is locked (read-only) and its annotations cannot
+    /// be modified. See [`AnnotationSet::is_locked`](crate::AnnotationSet::is_locked).
+    AnnotationSetLocked(crate::AnnotationSetID),
+    /// The addressed object does not exist, for example a dataset that was
+    /// permanently deleted and can no longer be restored. Carries its ID.
+    NotFound(String),
+    /// The operation was stopped through the cancellation token it was
+    /// given, for example
+    /// [`Client::download_dataset_with_file_events`](crate::Client::download_dataset_with_file_events).
+    Cancelled,
+    /// The server rejected the `continue_token` cursor of a paginated
+    /// listing, for example because it expired. The listing has to be
+    /// restarted from the first page. Carries the server's message.
+    InvalidCursor(String),
+    /// The snapshot cannot be downloaded because it is not available yet,
+    /// for example while its upload is still being processed, or because
+    /// processing failed. Carries the snapshot status reported by the
+    /// server.
+    SnapshotNotReady {
+        /// Raw snapshot status, see [`SnapshotStatus`](crate::SnapshotStatus)
+        status: String,
+    },
+    /// A downloaded file does not match the checksum storage reported for
+    /// it, even after downloading it again. Both digests are prefixed with
+    /// their algorithm, for example `sha256:…`.
+    ChecksumMismatch {
+        /// Digest reported by storage
+        expected: String,
+        /// Digest of the downloaded bytes
+        actual: String,
+    },
+    /// The server does not implement an endpoint the call relies on,
+    /// usually because it predates the feature. Returned when the server
+    /// answers with the JSON-RPC method-not-found code (`-32601`).
+    UnsupportedByServer {
+        /// RPC method the server does not know, for example `version.summary`
+        feature: String,
+    },
+    /// Storage rejected the presigned upload URL of a file and requesting a
+    /// fresh one failed or was rejected as well. Carries the file name.
+    UploadUrlExpired(String),
+}
+
+impl Error {
+    /// Builds an [`Error::RpcError`] for a server error `code`, classifying
+    /// it with [`RpcErrorKind::from_code`].
+    pub fn rpc(code: i32, message: impl Into<String>) -> Self {
+        Error::RpcError {
+            kind: RpcErrorKind::from_code(code),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Category of the code carried by [`Error::RpcError`].
+///
+/// Studio answers with its own codes (`101` not found, `401`, `403`), the
+/// JSON-RPC 2.0 server range (`-32001`, `-32002`, `-32004`) or, for raw
+/// download endpoints, the HTTP status. Matching on the kind spares callers
+/// and the language bindings from knowing every spelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcErrorKind {
+    /// The request carried no valid token (`401`, `-32001`).
+    Unauthenticated,
+    /// The token is valid but lacks access (`403`, `-32002`).
+    Forbidden,
+    /// The addressed object does not exist (`101`, `404`, `-32004`).
+    NotFound,
+    /// Too many requests (`429`).
+    RateLimited,
+    /// The server failed internally (`10`, `500`–`599`, `-32603`).
+    Internal,
+    /// Any other code, such as `3` for a rejected parameter.
+    Unknown(i32),
+}
+
+impl RpcErrorKind {
+    /// Classifies a server error code.
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            401 | -32001 => RpcErrorKind::Unauthenticated,
+            403 | -32002 => RpcErrorKind::Forbidden,
+            101 | 404 | -32004 => RpcErrorKind::NotFound,
+            429 => RpcErrorKind::RateLimited,
+            10 | 500..=599 | -32603 => RpcErrorKind::Internal,
+            _ => RpcErrorKind::Unknown(code),
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -202,7 +318,7 @@ impl std::fmt::Display for Error {
             Error::HttpError(e) => write!(f, "HTTP error: {}", e),
             Error::MaxRetriesExceeded(n) => write!(f, "Maximum retries ({}) exceeded", n),
             Error::UrlParseError(e) => write!(f, "URL parse error: {}", e),
-            Error::RpcError(code, msg) => write!(f, "RPC error {}: {}", code, msg),
+            Error::RpcError { code, message, .. } => write!(f, "RPC error {}: {}", code, message),
             Error::InvalidRpcId(id) => write!(f, "Invalid RPC ID: {}", id),
             Error::EnvError(e) => write!(f, "Environment variable error: {}", e),
             Error::SemaphoreError(e) => write!(f, "Semaphore error: {}", e),
@@ -246,9 +362,48 @@ impl std::fmt::Display for Error {
             Error::InsecureUrl(url) => write!(
                 f,
                 "refusing insecure URL '{}': Studio bearer tokens require HTTPS \
-                 (loopback http is allowed for tests/dev)",
+                 (loopback http is allowed for tests/dev; use with_allow_insecure \
+                 for remote hosts)",
                 url
             ),
+            Error::Timeout(limit) => write!(f, "operation timed out after {:?}", limit),
+            Error::PopulateFailed {
+                failed,
+                total,
+                first_error,
+            } => write!(
+                f,
+                "{} of {} samples failed to upload: {}",
+                failed, total, first_error
+            ),
+            Error::UrlExpired(sample) => {
+                write!(f, "presigned URL expired for sample {}", sample)
+            }
+            Error::AnnotationSetLocked(id) => write!(f, "annotation set {} is locked", id),
+            Error::NotFound(id) => write!(f, "{} not found", id),
+            Error::Cancelled => write!(f, "operation cancelled"),
+            Error::InvalidCursor(message) => {
+                write!(f, "pagination cursor rejected: {}", message)
+            }
+            Error::SnapshotNotReady { status } => write!(
+                f,
+                "snapshot is not ready (status: {}); wait for its task to finish \
+                 and the snapshot to become available",
+                status
+            ),
+            Error::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {}, downloaded file has {}",
+                expected, actual
+            ),
+            Error::UnsupportedByServer { feature } => write!(
+                f,
+                "{} is not supported by this server; it may need to be upgraded",
+                feature
+            ),
+            Error::UploadUrlExpired(file) => {
+                write!(f, "presigned upload URL expired for file {}", file)
+            }
         }
     }
 }
@@ -498,7 +653,7 @@ mod tests {
         // 2. Capture primitives as strings
         let code_str = error_code.to_string();
         // 3. Wrap to custom Error type
-        let wrapped_err = Error::RpcError(error_code, error_msg.to_string());
+        let wrapped_err = Error::rpc(error_code, error_msg);
         // 4. Capture wrapped error string
         let wrapped_str = wrapped_err.to_string();
         // 5. Verify primitive strings are substrings of wrapped string
@@ -517,6 +672,39 @@ mod tests {
         assert!(wrapped_str.starts_with("RPC error"));
     }
 
+    #[test]
+    fn test_rpc_error_kind_from_code() {
+        // Studio and JSON-RPC spellings of the same category agree.
+        for code in [401, -32001] {
+            assert_eq!(RpcErrorKind::from_code(code), RpcErrorKind::Unauthenticated);
+        }
+        for code in [403, -32002] {
+            assert_eq!(RpcErrorKind::from_code(code), RpcErrorKind::Forbidden);
+        }
+        for code in [101, 404, -32004] {
+            assert_eq!(RpcErrorKind::from_code(code), RpcErrorKind::NotFound);
+        }
+        assert_eq!(RpcErrorKind::from_code(429), RpcErrorKind::RateLimited);
+        for code in [10, 500, 503, -32603] {
+            assert_eq!(RpcErrorKind::from_code(code), RpcErrorKind::Internal);
+        }
+        assert_eq!(RpcErrorKind::from_code(3), RpcErrorKind::Unknown(3));
+
+        // The raw code stays available next to the kind.
+        match Error::rpc(-32004, "no such dataset") {
+            Error::RpcError {
+                kind,
+                code,
+                message,
+            } => {
+                assert_eq!(kind, RpcErrorKind::NotFound);
+                assert_eq!(code, -32004);
+                assert_eq!(message, "no such dataset");
+            }
+            other => panic!("expected RpcError, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_invalid_rpc_id() {
         // 1. Create primitive value
@@ -886,4 +1074,80 @@ mod tests {
         assert!(err_str.contains("keychain locked"));
         assert!(err_str.starts_with("Token storage error:"));
     }
+
+    #[test]
+    fn test_timeout_display() {
+        let err = Error::Timeout(std::time::Duration::from_millis(1500));
+        let err_str = err.to_string();
+        assert!(err_str.starts_with("operation timed out"));
+        assert!(err_str.contains("1.5s"), "got: {err_str}");
+    }
+
+    #[test]
+    fn test_url_expired_display() {
+        let err = Error::UrlExpired("frame_001".into());
+        assert_eq!(
+            err.to_string(),
+            "presigned URL expired for sample frame_001"
+        );
+    }
+
+    #[test]
+    fn test_upload_url_expired_display() {
+        let err = Error::UploadUrlExpired("frame_001.jpg".into());
+        assert_eq!(
+            err.to_string(),
+            "presigned upload URL expired for file frame_001.jpg"
+        );
+    }
+
+    #[test]
+    fn test_annotation_set_locked_display() {
+        let err = Error::AnnotationSetLocked(crate::AnnotationSetID::from(42));
+        assert_eq!(err.to_string(), "annotation set as-2a is locked");
+    }
+
+    #[test]
+    fn test_not_found_display() {
+        let err = Error::NotFound(crate::DatasetID::from(42).to_string());
+        assert_eq!(err.to_string(), "ds-2a not found");
+    }
+
+    #[test]
+    fn test_cancelled_display() {
+        assert_eq!(Error::Cancelled.to_string(), "operation cancelled");
+    }
+
+    #[test]
+    fn test_invalid_cursor_display() {
+        let err = Error::InvalidCursor("continue_token expired".to_string());
+        assert_eq!(
+            err.to_string(),
+            "pagination cursor rejected: continue_token expired"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_not_ready_display() {
+        let err = Error::SnapshotNotReady {
+            status: "processing".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "snapshot is not ready (status: processing); wait for its task to \
+             finish and the snapshot to become available"
+        );
+    }
+
+    #[test]
+    fn test_checksum_mismatch_display() {
+        let err = Error::ChecksumMismatch {
+            expected: "sha1:aa".to_string(),
+            actual: "sha1:bb".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "checksum mismatch: expected sha1:aa, downloaded file has sha1:bb"
+        );
+    }
 }
@@ -6,6 +6,11 @@
 //! - Reading and resolving file paths from Arrow annotation files
 //! - Generating Arrow files from folders of images (with null annotations)
 //! - Validating dataset directory structures
+//! - Converting single samples to COCO fragments ([`sample_to_coco`])
+//! - Merging duplicate samples before upload ([`merge_samples`])
+//! - Writing YOLO / Darknet detection labels ([`yolo`])
+//! - Writing TFRecord shards for TensorFlow (`tfrecord`, behind the
+//!   `tfrecord` feature)
 //! - (Future) Converting from other formats (COCO, DarkNet, YOLO, etc.)
 //!
 //! # EdgeFirst Dataset Format
@@ -59,14 +64,25 @@
 //! ```
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
     path::{Path, PathBuf},
 };
 
 use walkdir::WalkDir;
 
-use crate::Error;
+use crate::{
+    Error, Label, Sample,
+    coco::{
+        CocoAnnotation, CocoImage, CocoSegmentation, box2d_to_coco_bbox, calculate_coco_area,
+        convert::{compute_bbox_from_keypoints, compute_bbox_from_polygon},
+        keypoints_to_coco_keypoints, polygon_to_coco_polygon,
+    },
+};
+
+#[cfg(feature = "tfrecord")]
+pub mod tfrecord;
+pub mod yolo;
 
 /// Image file extensions supported by EdgeFirst.
 pub const IMAGE_EXTENSIONS: &[&str] = &[
@@ -674,12 +690,370 @@ pub fn get_arrow_path(dataset_dir: &Path) -> Option<PathBuf> {
     Some(dataset_dir.join(format!("{}.arrow", dataset_name)))
 }
 
+/// Convert one sample to a COCO `image` entry and its `annotations`.
+///
+/// Use this to build COCO output incrementally instead of exporting a whole
+/// dataset. Each annotation's `category_id` is the index of its label in
+/// `labels`, the same mapping COCO import uses in the other direction.
+/// Annotation IDs are numbered from 1 within the fragment, so offset them
+/// when combining fragments into one dataset. Annotations with neither a
+/// 2D box nor a polygon (3D boxes only) are skipped.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidParameters`] if the sample has no width or
+/// height, which are needed for COCO's pixel coordinates, or if an
+/// annotation's label is missing or not in `labels`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use edgefirst_client::format::sample_to_coco;
+/// # fn example(samples: &[edgefirst_client::Sample], labels: &[edgefirst_client::Label]) -> Result<(), edgefirst_client::Error> {
+/// for (id, sample) in samples.iter().enumerate() {
+///     let (image, annotations) = sample_to_coco(sample, labels, id as u64 + 1)?;
+///     println!("{}: {} annotations", image.file_name, annotations.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn sample_to_coco(
+    sample: &Sample,
+    labels: &[Label],
+    image_id: u64,
+) -> Result<(CocoImage, Vec<CocoAnnotation>), Error> {
+    let image_name = sample.image_name.as_deref().unwrap_or("unknown");
+    let (width, height) = match (sample.width, sample.height) {
+        (Some(width), Some(height)) if width > 0 && height > 0 => (width, height),
+        _ => {
+            return Err(Error::InvalidParameters(format!(
+                "sample {} has no image dimensions",
+                image_name
+            )));
+        }
+    };
+    let file_name = if image_name.contains('.') {
+        image_name.to_string()
+    } else {
+        format!("{}.jpg", image_name)
+    };
+    let image = CocoImage {
+        id: image_id,
+        width,
+        height,
+        file_name,
+        ..Default::default()
+    };
+
+    let mut annotations = Vec::new();
+    for ann in &sample.annotations {
+        let bbox = match (ann.box2d(), ann.polygon(), ann.keypoints()) {
+            (Some(box2d), _, _) => Some(box2d_to_coco_bbox(box2d, width, height)),
+            (None, Some(polygon), _) => compute_bbox_from_polygon(polygon, width, height),
+            (None, None, Some(keypoints)) => compute_bbox_from_keypoints(keypoints, width, height),
+            (None, None, None) => None,
+        };
+        let Some(bbox) = bbox else {
+            continue;
+        };
+
+        let name = ann.label().ok_or_else(|| {
+            Error::InvalidParameters(format!("annotation on {} has no label", image_name))
+        })?;
+        let label = labels.iter().find(|l| l.name() == name).ok_or_else(|| {
+            Error::InvalidParameters(format!("label {} is not in the label list", name))
+        })?;
+        let category_id = u32::try_from(label.index()).map_err(|_| {
+            Error::InvalidParameters(format!("label index {} is out of range", label.index()))
+        })?;
+
+        let segmentation = ann
+            .polygon()
+            .map(|polygon| polygon_to_coco_polygon(polygon, width, height))
+            .filter(|rings| !rings.is_empty())
+            .map(CocoSegmentation::Polygon);
+        let area = match &segmentation {
+            Some(seg) => calculate_coco_area(seg)?,
+            None => bbox[2] * bbox[3],
+        };
+
+        annotations.push(CocoAnnotation {
+            id: annotations.len() as u64 + 1,
+            image_id,
+            category_id,
+            bbox,
+            area,
+            iscrowd: 0,
+            segmentation,
+            score: None,
+            keypoints: ann
+                .keypoints()
+                .map(|keypoints| keypoints_to_coco_keypoints(keypoints, width, height)),
+            num_keypoints: ann
+                .keypoints()
+                .map(|keypoints| keypoints.num_labeled() as u32),
+        });
+    }
+
+    Ok((image, annotations))
+}
+
+/// Merges samples that describe the same image, so a dataset assembled from
+/// several sources can be uploaded without duplicates.
+///
+/// Samples are matched by image name, or by UUID when they have no image
+/// name; samples with neither are kept as they are. The merged samples keep
+/// the order in which each image first appears.
+///
+/// When merged samples disagree on a metadata field (group, sequence,
+/// frame, dimensions, date, location, ...), the value from the first sample
+/// that sets it wins, and fields the first sample leaves unset are filled
+/// from later ones. Files are unioned by type, the first file of each type
+/// winning. Annotations are unioned, dropping exact duplicates.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use edgefirst_client::format::merge_samples;
+/// # fn example(boxes: Vec<edgefirst_client::Sample>, masks: Vec<edgefirst_client::Sample>) {
+/// // Boxes and masks exported separately for the same images.
+/// let samples = merge_samples(boxes.into_iter().chain(masks).collect());
+/// println!("{} unique images", samples.len());
+/// # }
+/// ```
+pub fn merge_samples(samples: Vec<Sample>) -> Vec<Sample> {
+    let mut merged: Vec<Sample> = Vec::with_capacity(samples.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for sample in samples {
+        let key = match (&sample.image_name, &sample.uuid) {
+            (Some(image_name), _) => format!("name:{}", image_name),
+            (None, Some(uuid)) => format!("uuid:{}", uuid),
+            (None, None) => {
+                merged.push(sample);
+                continue;
+            }
+        };
+        match positions.get(&key) {
+            Some(&index) => merge_sample(&mut merged[index], sample),
+            None => {
+                positions.insert(key, merged.len());
+                merged.push(sample);
+            }
+        }
+    }
+    merged
+}
+
+/// Merges `other` into `target`, following the rules of [`merge_samples`].
+fn merge_sample(target: &mut Sample, other: Sample) {
+    fn fill<T>(field: &mut Option<T>, other: Option<T>) {
+        if field.is_none() {
+            *field = other;
+        }
+    }
+
+    fill(&mut target.id, other.id);
+    fill(&mut target.group, other.group);
+    fill(&mut target.sequence_name, other.sequence_name);
+    fill(&mut target.sequence_uuid, other.sequence_uuid);
+    fill(&mut target.sequence_description, other.sequence_description);
+    fill(&mut target.frame_number, other.frame_number);
+    fill(&mut target.uuid, other.uuid);
+    fill(&mut target.image_name, other.image_name);
+    fill(&mut target.image_url, other.image_url);
+    fill(&mut target.width, other.width);
+    fill(&mut target.height, other.height);
+    fill(&mut target.date, other.date);
+    fill(&mut target.source, other.source);
+    fill(&mut target.location, other.location);
+    fill(&mut target.degradation, other.degradation);
+    fill(&mut target.neg_label_indices, other.neg_label_indices);
+    fill(
+        &mut target.not_exhaustive_label_indices,
+        other.not_exhaustive_label_indices,
+    );
+    fill(&mut target.timing, other.timing);
+
+    for file in other.files {
+        if !target
+            .files
+            .iter()
+            .any(|f| f.file_type() == file.file_type())
+        {
+            target.files.push(file);
+        }
+    }
+
+    // Annotations have no equality of their own; compare what is uploaded
+    // plus the raster mask, which is not serialized.
+    let identity = |ann: &crate::Annotation| {
+        (
+            serde_json::to_string(ann).unwrap_or_default(),
+            ann.mask().map(|mask| mask.as_bytes().to_vec()),
+        )
+    };
+    let mut seen: HashSet<_> = target.annotations.iter().map(identity).collect();
+    for ann in other.annotations {
+        if seen.insert(identity(&ann)) {
+            target.annotations.push(ann);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::io::Write;
     use tempfile::TempDir;
 
+    #[test]
+    fn merge_samples_unions_files_and_annotations_by_image_name() {
+        use crate::{Annotation, Box2d, SampleFile};
+
+        let mut boxed = Annotation::new();
+        boxed.set_label(Some("deer".to_string()));
+        boxed.set_box2d(Some(Box2d::new(0.1, 0.1, 0.2, 0.2)));
+        let mut other_box = boxed.clone();
+        other_box.set_label(Some("elk".to_string()));
+
+        let mut first = Sample::new();
+        first.image_name = Some("frame.jpg".to_string());
+        first.group = Some("train".to_string());
+        first.files = vec![SampleFile::with_filename(
+            "image".to_string(),
+            "a/frame.jpg".to_string(),
+        )];
+        first.annotations = vec![boxed.clone()];
+
+        let mut second = Sample::new();
+        second.image_name = Some("frame.jpg".to_string());
+        second.group = Some("val".to_string());
+        second.width = Some(640);
+        second.files = vec![
+            SampleFile::with_filename("image".to_string(), "b/frame.jpg".to_string()),
+            SampleFile::with_filename("lidar.pcd".to_string(), "b/frame.pcd".to_string()),
+        ];
+        second.annotations = vec![boxed, other_box];
+
+        let mut unrelated = Sample::new();
+        unrelated.image_name = Some("other.jpg".to_string());
+
+        let merged = merge_samples(vec![first, unrelated, second]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[1].image_name(), Some("other.jpg"));
+
+        let sample = &merged[0];
+        assert_eq!(sample.group.as_deref(), Some("train"));
+        assert_eq!(sample.width, Some(640));
+        assert_eq!(
+            sample
+                .files
+                .iter()
+                .map(|f| (f.file_type(), f.filename()))
+                .collect::<Vec<_>>(),
+            [
+                ("image", Some("a/frame.jpg")),
+                ("lidar.pcd", Some("b/frame.pcd")),
+            ]
+        );
+        assert_eq!(
+            sample
+                .annotations
+                .iter()
+                .map(|a| a.label().map(String::as_str))
+                .collect::<Vec<_>>(),
+            [Some("deer"), Some("elk")]
+        );
+    }
+
+    #[test]
+    fn sample_to_coco_links_annotations_to_image() {
+        use crate::{Annotation, Box2d, Polygon};
+
+        let labels: Vec<Label> = serde_json::from_value(serde_json::json!([
+            { "id": 1, "index": 0, "name": "deer" },
+            { "id": 2, "index": 3, "name": "elk" },
+        ]))
+        .unwrap();
+
+        let mut sample = Sample::new();
+        sample.image_name = Some("frame".to_string());
+        sample.width = Some(200);
+        sample.height = Some(100);
+        let mut boxed = Annotation::new();
+        boxed.set_label(Some("elk".to_string()));
+        boxed.set_box2d(Some(Box2d::new(0.25, 0.5, 0.5, 0.25)));
+        let mut outlined = Annotation::new();
+        outlined.set_label(Some("deer".to_string()));
+        outlined.set_polygon(Some(Polygon::new(vec![vec![
+            (0.0, 0.0),
+            (0.5, 0.0),
+            (0.5, 0.5),
+            (0.0, 0.5),
+        ]])));
+        sample.annotations = vec![boxed, outlined];
+
+        let (image, annotations) = sample_to_coco(&sample, &labels, 42).unwrap();
+        assert_eq!(image.id, 42);
+        assert_eq!((image.width, image.height), (200, 100));
+        assert_eq!(image.file_name, "frame.jpg");
+
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations.iter().map(|a| a.id).collect::<Vec<_>>(), [1, 2]);
+        assert!(annotations.iter().all(|a| a.image_id == image.id));
+
+        assert_eq!(annotations[0].category_id, 3);
+        assert_eq!(annotations[0].bbox, [50.0, 50.0, 100.0, 25.0]);
+        assert!(annotations[0].segmentation.is_none());
+
+        assert_eq!(annotations[1].category_id, 0);
+        assert_eq!(annotations[1].bbox, [0.0, 0.0, 100.0, 50.0]);
+        assert!(annotations[1].segmentation.is_some());
+        assert_eq!(annotations[1].area, 5000.0);
+    }
+
+    #[test]
+    fn sample_to_coco_exports_keypoints() {
+        use crate::{Annotation, Keypoints, Visibility};
+
+        let labels: Vec<Label> =
+            serde_json::from_value(serde_json::json!([{ "id": 1, "index": 1, "name": "person" }]))
+                .unwrap();
+
+        let mut sample = Sample::new();
+        sample.image_name = Some("pose.png".to_string());
+        sample.width = Some(200);
+        sample.height = Some(100);
+        let mut pose = Annotation::new();
+        pose.set_label(Some("person".to_string()));
+        pose.set_keypoints(Some(Keypoints::new(vec![
+            (0.25, 0.5, Visibility::Visible),
+            (0.9, 0.9, Visibility::NotLabeled),
+            (0.75, 0.25, Visibility::Occluded),
+        ])));
+        sample.annotations = vec![pose];
+
+        let (_, annotations) = sample_to_coco(&sample, &labels, 1).unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(
+            annotations[0].keypoints.as_deref(),
+            Some(&[50.0, 50.0, 2.0, 0.0, 0.0, 0.0, 150.0, 25.0, 1.0][..])
+        );
+        assert_eq!(annotations[0].num_keypoints, Some(2));
+        // Without a box or polygon the bbox spans the labeled points only.
+        assert_eq!(annotations[0].bbox, [50.0, 25.0, 100.0, 25.0]);
+    }
+
+    #[test]
+    fn sample_to_coco_requires_dimensions() {
+        let mut sample = Sample::new();
+        sample.image_name = Some("frame.jpg".to_string());
+        assert!(matches!(
+            sample_to_coco(&sample, &[], 1),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
     /// Create a test image file (minimal JPEG).
     fn create_test_image(path: &Path) {
         // Minimal valid JPEG (smallest possible)
@@ -0,0 +1,532 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! TFRecord shards of `tf.train.Example` records for TensorFlow pipelines.
+//!
+//! Each record is framed as TensorFlow writes it:
+//!
+//! ```text
+//! u64 length (little endian)
+//! u32 masked CRC-32C of length
+//! byte data[length]
+//! u32 masked CRC-32C of data
+//! ```
+//!
+//! and holds one sample encoded as a `tf.train.Example` with the feature
+//! keys of the TensorFlow Object Detection API:
+//!
+//! | Key                                   | Type    |
+//! |---------------------------------------|---------|
+//! | `image/encoded`                       | bytes   |
+//! | `image/filename`, `image/source_id`   | bytes   |
+//! | `image/format`                        | bytes   |
+//! | `image/width`, `image/height`         | int64   |
+//! | `image/object/bbox/xmin` … `ymax`     | float   |
+//! | `image/object/class/text`             | bytes   |
+//! | `image/object/class/label`            | int64   |
+//!
+//! Boxes are normalized to the image. Class labels count from 1 in the order
+//! of `label_map.pbtxt`, leaving 0 for the background class. The protobuf
+//! encoding is written by hand, so TensorFlow is not needed to export.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Write as _},
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, Label, Sample};
+
+/// Default shard size limit for [`TfRecordWriter`]: 100 MiB, in the range
+/// TensorFlow recommends for reading shards in parallel.
+pub const DEFAULT_SHARD_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Framing around each record: the length, its CRC and the data CRC.
+const FRAME_BYTES: u64 = 16;
+
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32c(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32C_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// CRC-32C as stored in TFRecord framing, rotated and offset so that a CRC
+/// of data containing CRCs stays well distributed.
+fn masked_crc32c(data: &[u8]) -> u32 {
+    crc32c(data).rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+/// Value list of one `tf.train.Feature`.
+enum Feature {
+    Bytes(Vec<Vec<u8>>),
+    Float(Vec<f32>),
+    Int64(Vec<i64>),
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Writes a length-delimited protobuf field.
+fn put_bytes_field(buf: &mut Vec<u8>, field: u32, data: &[u8]) {
+    put_varint(buf, u64::from(field << 3 | 2));
+    put_varint(buf, data.len() as u64);
+    buf.extend_from_slice(data);
+}
+
+fn encode_feature(feature: &Feature) -> Vec<u8> {
+    // BytesList, FloatList and Int64List each hold their values in field 1;
+    // the numeric lists are packed.
+    let mut list = Vec::new();
+    let kind = match feature {
+        Feature::Bytes(values) => {
+            for value in values {
+                put_bytes_field(&mut list, 1, value);
+            }
+            1
+        }
+        Feature::Float(values) => {
+            let packed: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+            if !packed.is_empty() {
+                put_bytes_field(&mut list, 1, &packed);
+            }
+            2
+        }
+        Feature::Int64(values) => {
+            let mut packed = Vec::new();
+            for value in values {
+                put_varint(&mut packed, *value as u64);
+            }
+            if !packed.is_empty() {
+                put_bytes_field(&mut list, 1, &packed);
+            }
+            3
+        }
+    };
+    let mut out = Vec::new();
+    put_bytes_field(&mut out, kind, &list);
+    out
+}
+
+/// Encodes `Example { Features { map<string, Feature> } }`, with the map
+/// entries in key order so the same sample always gives the same bytes.
+fn encode_example(features: &BTreeMap<&str, Feature>) -> Vec<u8> {
+    let mut map = Vec::new();
+    for (key, feature) in features {
+        let mut entry = Vec::new();
+        put_bytes_field(&mut entry, 1, key.as_bytes());
+        put_bytes_field(&mut entry, 2, &encode_feature(feature));
+        put_bytes_field(&mut map, 1, &entry);
+    }
+    let mut example = Vec::new();
+    put_bytes_field(&mut example, 1, &map);
+    example
+}
+
+/// Encodes `sample` with its `image` file as a `tf.train.Example`.
+///
+/// `classes` lists the dataset's labels in class order; the first has class
+/// label 1. Only 2D boxes are written. The image size is taken from the
+/// sample, or read from the image header when the sample has none.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidParameters`] if the sample has no image name, the
+/// image size cannot be determined, or a boxed annotation has no label or
+/// one that is not in `classes`.
+pub fn sample_to_tf_example(
+    sample: &Sample,
+    image: &[u8],
+    classes: &[&Label],
+) -> Result<Vec<u8>, Error> {
+    let image_name = sample
+        .image_name()
+        .ok_or_else(|| Error::InvalidParameters("sample has no image name".to_string()))?;
+    let (width, height) = match (sample.width(), sample.height()) {
+        (Some(width), Some(height)) => (i64::from(width), i64::from(height)),
+        _ => {
+            let size = imagesize::blob_size(image).map_err(|err| {
+                Error::InvalidParameters(format!("cannot read size of {}: {}", image_name, err))
+            })?;
+            (size.width as i64, size.height as i64)
+        }
+    };
+    let format = match infer::get(image).map(|kind| kind.extension()) {
+        Some("jpg") => "jpeg",
+        Some(extension) => extension,
+        None => "",
+    };
+
+    let (mut xmin, mut xmax, mut ymin, mut ymax) = (vec![], vec![], vec![], vec![]);
+    let (mut text, mut label) = (vec![], vec![]);
+    for ann in sample.annotations() {
+        let Some(box2d) = ann.box2d() else {
+            continue;
+        };
+        let name = ann.label().ok_or_else(|| {
+            Error::InvalidParameters(format!("annotation on {} has no label", image_name))
+        })?;
+        let class = classes
+            .iter()
+            .position(|label| label.name() == name)
+            .ok_or_else(|| {
+                Error::InvalidParameters(format!("label {} is not in the label list", name))
+            })?;
+        xmin.push(box2d.left());
+        xmax.push(box2d.left() + box2d.width());
+        ymin.push(box2d.top());
+        ymax.push(box2d.top() + box2d.height());
+        text.push(name.as_bytes().to_vec());
+        label.push(class as i64 + 1);
+    }
+
+    let source_id = sample.uuid().map(String::as_str).unwrap_or(image_name);
+    let features = BTreeMap::from([
+        ("image/encoded", Feature::Bytes(vec![image.to_vec()])),
+        (
+            "image/filename",
+            Feature::Bytes(vec![image_name.as_bytes().to_vec()]),
+        ),
+        (
+            "image/source_id",
+            Feature::Bytes(vec![source_id.as_bytes().to_vec()]),
+        ),
+        (
+            "image/format",
+            Feature::Bytes(vec![format.as_bytes().to_vec()]),
+        ),
+        ("image/width", Feature::Int64(vec![width])),
+        ("image/height", Feature::Int64(vec![height])),
+        ("image/object/bbox/xmin", Feature::Float(xmin)),
+        ("image/object/bbox/xmax", Feature::Float(xmax)),
+        ("image/object/bbox/ymin", Feature::Float(ymin)),
+        ("image/object/bbox/ymax", Feature::Float(ymax)),
+        ("image/object/class/text", Feature::Bytes(text)),
+        ("image/object/class/label", Feature::Int64(label)),
+    ]);
+    Ok(encode_example(&features))
+}
+
+/// Writes records into TFRecord shards of at most `max_shard_bytes` each.
+///
+/// Shards are named `<prefix>-00000-of-00003.tfrecord`. The total is only
+/// known once every record is written, so shards carry a `.tmp` name until
+/// [`finish`](Self::finish) renames them. A record larger than the limit
+/// gets a shard of its own.
+pub struct TfRecordWriter {
+    dir: PathBuf,
+    prefix: String,
+    max_shard_bytes: u64,
+    shards: Vec<PathBuf>,
+    file: Option<BufWriter<File>>,
+    shard_bytes: u64,
+}
+
+impl TfRecordWriter {
+    /// Creates a writer for shards in the existing directory `dir`.
+    pub fn new(dir: &Path, prefix: &str, max_shard_bytes: u64) -> Self {
+        TfRecordWriter {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            max_shard_bytes,
+            shards: Vec::new(),
+            file: None,
+            shard_bytes: 0,
+        }
+    }
+
+    /// Appends `record`, starting a new shard if it would not fit in the
+    /// current one.
+    pub fn write(&mut self, record: &[u8]) -> Result<(), Error> {
+        let framed = record.len() as u64 + FRAME_BYTES;
+        if self.file.is_none()
+            || (self.shard_bytes > 0 && self.shard_bytes + framed > self.max_shard_bytes)
+        {
+            self.start_shard()?;
+        }
+        let Some(file) = self.file.as_mut() else {
+            unreachable!("start_shard opens a file");
+        };
+        let length = (record.len() as u64).to_le_bytes();
+        file.write_all(&length)?;
+        file.write_all(&masked_crc32c(&length).to_le_bytes())?;
+        file.write_all(record)?;
+        file.write_all(&masked_crc32c(record).to_le_bytes())?;
+        self.shard_bytes += framed;
+        Ok(())
+    }
+
+    fn start_shard(&mut self) -> Result<(), Error> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let path = self.dir.join(format!(
+            "{}-{:05}.tfrecord.tmp",
+            self.prefix,
+            self.shards.len()
+        ));
+        self.file = Some(BufWriter::new(File::create(&path)?));
+        self.shards.push(path);
+        self.shard_bytes = 0;
+        Ok(())
+    }
+
+    /// Flushes the last shard and gives every shard its final name.
+    ///
+    /// Returns the shard paths in order, none if no record was written.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, Error> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let total = self.shards.len();
+        let mut paths = Vec::with_capacity(total);
+        for (index, tmp) in self.shards.iter().enumerate() {
+            let path = self.dir.join(format!(
+                "{}-{:05}-of-{:05}.tfrecord",
+                self.prefix, index, total
+            ));
+            fs::rename(tmp, &path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// Reads the records of a TFRecord file, checking the framing CRCs.
+///
+/// # Errors
+///
+/// Returns [`Error::UnsupportedFormat`] if the file is truncated or a CRC
+/// does not match.
+pub fn read_tfrecord(path: &Path) -> Result<Vec<Vec<u8>>, Error> {
+    let data = fs::read(path)?;
+    let corrupt = |record: usize, what: &str| {
+        Error::UnsupportedFormat(format!("{}: record {} {}", path.display(), record, what))
+    };
+
+    let mut records = Vec::new();
+    let mut rest = data.as_slice();
+    while !rest.is_empty() {
+        let index = records.len();
+        let (Some(length), Some(length_crc)) = (rest.get(..8), rest.get(8..12)) else {
+            return Err(corrupt(index, "is truncated"));
+        };
+        if masked_crc32c(length).to_le_bytes() != length_crc {
+            return Err(corrupt(index, "has a bad length CRC"));
+        }
+        let length = u64::from_le_bytes(length.try_into().unwrap()) as usize;
+        let end = 12usize
+            .checked_add(length)
+            .filter(|end| end + 4 <= rest.len())
+            .ok_or_else(|| corrupt(index, "is truncated"))?;
+        let record = &rest[12..end];
+        if masked_crc32c(record).to_le_bytes() != rest[end..end + 4] {
+            return Err(corrupt(index, "has a bad data CRC"));
+        }
+        records.push(record.to_vec());
+        rest = &rest[end + 4..];
+    }
+    Ok(records)
+}
+
+/// Writes `label_map.pbtxt` into `dir`, giving each class its label from 1
+/// in `classes` order, as read by the TensorFlow Object Detection API.
+pub fn write_label_map(classes: &[&Label], dir: &Path) -> Result<(), Error> {
+    let mut label_map = String::new();
+    for (index, label) in classes.iter().enumerate() {
+        // A JSON string is a valid text-format protobuf string.
+        label_map.push_str(&format!(
+            "item {{\n  id: {}\n  name: {}\n}}\n",
+            index + 1,
+            serde_json::to_string(label.name())?
+        ));
+    }
+    fs::write(dir.join("label_map.pbtxt"), label_map)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Annotation, Box2d};
+
+    fn read_varint(data: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = data[0];
+            *data = &data[1..];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        value
+    }
+
+    /// Splits a protobuf message into its length-delimited fields.
+    fn bytes_fields(mut data: &[u8]) -> Vec<(u64, &[u8])> {
+        let mut fields = Vec::new();
+        while !data.is_empty() {
+            let tag = read_varint(&mut data);
+            assert_eq!(tag & 7, 2, "only length-delimited fields are written");
+            let length = read_varint(&mut data) as usize;
+            fields.push((tag >> 3, &data[..length]));
+            data = &data[length..];
+        }
+        fields
+    }
+
+    /// Feature keys of an encoded `Example`, with the kind of each value
+    /// list (1 bytes, 2 float, 3 int64) and its payload.
+    fn example_features(example: &[u8]) -> BTreeMap<String, (u64, Vec<u8>)> {
+        let [(1, features)] = bytes_fields(example)[..] else {
+            panic!("Example holds one Features message");
+        };
+        bytes_fields(features)
+            .into_iter()
+            .map(|(_, entry)| {
+                let [(1, key), (2, feature)] = bytes_fields(entry)[..] else {
+                    panic!("map entry holds a key and a value");
+                };
+                let [(kind, list)] = bytes_fields(feature)[..] else {
+                    panic!("Feature holds one list");
+                };
+                (
+                    String::from_utf8(key.to_vec()).unwrap(),
+                    (kind, list.to_vec()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crc32c_matches_reference_value() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn shards_read_back_with_object_detection_features() {
+        let labels: Vec<Label> = serde_json::from_value(serde_json::json!([
+            { "id": 2, "index": 1, "name": "elk" },
+            { "id": 1, "index": 0, "name": "deer" },
+        ]))
+        .unwrap();
+        let mut classes: Vec<&Label> = labels.iter().collect();
+        classes.sort_by_key(|label| label.index());
+
+        let mut ann = Annotation::new();
+        ann.set_label(Some("elk".to_string()));
+        ann.set_box2d(Some(Box2d::new(0.25, 0.5, 0.5, 0.25)));
+        let mut sample = Sample::new().with_annotations(vec![ann]);
+        sample.image_name = Some("frame.png".to_string());
+        sample.width = Some(64);
+        sample.height = Some(32);
+        let image = b"\x89PNG\r\n\x1a\nnot really a png";
+        let record = sample_to_tf_example(&sample, image, &classes).unwrap();
+
+        // Each record needs 16 bytes of framing, so a limit below two
+        // records puts every record in its own shard.
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = TfRecordWriter::new(dir.path(), "data", 2 * record.len() as u64);
+        for _ in 0..3 {
+            writer.write(&record).unwrap();
+        }
+        let shards = writer.finish().unwrap();
+        assert_eq!(
+            shards
+                .iter()
+                .map(|p| p.file_name().unwrap().to_str().unwrap())
+                .collect::<Vec<_>>(),
+            [
+                "data-00000-of-00003.tfrecord",
+                "data-00001-of-00003.tfrecord",
+                "data-00002-of-00003.tfrecord",
+            ]
+        );
+
+        let records = read_tfrecord(&shards[0]).unwrap();
+        assert_eq!(records, [record]);
+        let features = example_features(&records[0]);
+        assert_eq!(
+            features.keys().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "image/encoded",
+                "image/filename",
+                "image/format",
+                "image/height",
+                "image/object/bbox/xmax",
+                "image/object/bbox/xmin",
+                "image/object/bbox/ymax",
+                "image/object/bbox/ymin",
+                "image/object/class/label",
+                "image/object/class/text",
+                "image/source_id",
+                "image/width",
+            ]
+        );
+        let bytes_value = |key: &str| {
+            let (kind, list) = &features[key];
+            assert_eq!(*kind, 1, "{key} is a bytes list");
+            bytes_fields(list)[0].1.to_vec()
+        };
+        assert_eq!(bytes_value("image/encoded"), image);
+        assert_eq!(bytes_value("image/format"), b"png");
+        assert_eq!(bytes_value("image/object/class/text"), b"elk");
+        let (kind, packed) = &features["image/object/class/label"];
+        assert_eq!((*kind, bytes_fields(packed)[0].1), (3, &[2u8][..]));
+        let (kind, packed) = &features["image/object/bbox/xmax"];
+        assert_eq!(
+            (*kind, bytes_fields(packed)[0].1),
+            (2, &0.75f32.to_le_bytes()[..])
+        );
+
+        // Flipping a data byte breaks the record's CRC.
+        let mut data = fs::read(&shards[1]).unwrap();
+        data[20] ^= 1;
+        fs::write(&shards[1], data).unwrap();
+        assert!(matches!(
+            read_tfrecord(&shards[1]),
+            Err(Error::UnsupportedFormat(msg)) if msg.contains("data CRC")
+        ));
+    }
+
+    #[test]
+    fn write_label_map_numbers_classes_from_one() {
+        let labels: Vec<Label> = serde_json::from_value(serde_json::json!([
+            { "id": 1, "index": 0, "name": "deer \"mule\"" },
+            { "id": 2, "index": 1, "name": "elk" },
+        ]))
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        write_label_map(&labels.iter().collect::<Vec<_>>(), dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("label_map.pbtxt")).unwrap(),
+            "item {\n  id: 1\n  name: \"deer \\\"mule\\\"\"\n}\nitem {\n  id: 2\n  name: \"elk\"\n}\n"
+        );
+    }
+}
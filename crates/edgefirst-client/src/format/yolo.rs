@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! YOLO / Darknet detection label files.
+//!
+//! A YOLO dataset has one `.txt` file per image with a line per object:
+//!
+//! ```text
+//! <class> <cx> <cy> <w> <h>
+//! ```
+//!
+//! where `class` is a zero-based class number and the box is given by its
+//! center and size, normalized to the image. Class names are listed one per
+//! line in `classes.txt` (Darknet) and in `data.yaml` (Ultralytics), in
+//! class number order.
+//!
+//! Classes are numbered by their position in the dataset's labels sorted by
+//! [`Label::index`], so gaps in the label indices do not leave empty classes.
+//!
+//! Boxes are written normalized to the image.
+//! [`Client::export_yolo`](crate::Client::export_yolo) converts datasets in
+//! [`CoordinateSpace::Pixels`](crate::CoordinateSpace::Pixels), the default
+//! when the server does not report a space, by each sample's width and
+//! height, so those samples must have image dimensions.
+
+use std::{fs, path::Path};
+
+use crate::{Error, Label, Sample};
+
+/// Orders `labels` by [`Label::index`], giving each label's YOLO class
+/// number as its position in the result.
+pub fn yolo_classes(labels: &[Label]) -> Vec<&Label> {
+    let mut classes: Vec<&Label> = labels.iter().collect();
+    classes.sort_by_key(|label| label.index());
+    classes
+}
+
+/// Name of the label file for `sample`: its image name with the extension
+/// replaced by `.txt`.
+///
+/// Frames of a sequence are prefixed with `{sequence}_{frame}_`, unless the
+/// image name already starts with `{sequence}_`, as
+/// [`Client::download_dataset`](crate::Client::download_dataset) names
+/// their images with `flatten`. Frames with the same name in different
+/// sequences so get their own label files.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidParameters`] if the sample has no image name.
+pub fn yolo_label_file_name(sample: &Sample) -> Result<String, Error> {
+    let image_name = sample
+        .image_name()
+        .ok_or_else(|| Error::InvalidParameters("sample has no image name".to_string()))?;
+    let stem = Path::new(image_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(image_name);
+    let sequence = sample
+        .sequence_name()
+        .map(|name| crate::client::sanitize_path_component(name));
+    let stem = crate::Client::build_filename(stem, true, sequence.as_ref(), sample.frame_number());
+    Ok(format!("{}.txt", stem))
+}
+
+/// Formats the 2D boxes of `sample` as YOLO label lines.
+///
+/// `classes` is the class list from [`yolo_classes`]. Annotations without a
+/// 2D box are skipped; a sample without boxes gives an empty string, which
+/// YOLO trainers read as a background image.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidParameters`] if a boxed annotation has no label or
+/// its label is not in `classes`.
+pub fn sample_to_yolo(sample: &Sample, classes: &[&Label]) -> Result<String, Error> {
+    let image_name = sample.image_name().unwrap_or("unknown");
+    let mut lines = String::new();
+    for ann in sample.annotations() {
+        let Some(box2d) = ann.box2d() else {
+            continue;
+        };
+        let name = ann.label().ok_or_else(|| {
+            Error::InvalidParameters(format!("annotation on {} has no label", image_name))
+        })?;
+        let class = classes
+            .iter()
+            .position(|label| label.name() == name)
+            .ok_or_else(|| {
+                Error::InvalidParameters(format!("label {} is not in the label list", name))
+            })?;
+        lines.push_str(&format!(
+            "{} {:.6} {:.6} {:.6} {:.6}\n",
+            class,
+            box2d.cx(),
+            box2d.cy(),
+            box2d.width(),
+            box2d.height()
+        ));
+    }
+    Ok(lines)
+}
+
+/// Writes `classes.txt` and `data.yaml` listing the class names into `dir`.
+pub fn write_yolo_classes(classes: &[&Label], dir: &Path) -> Result<(), Error> {
+    let names: Vec<&str> = classes.iter().map(|label| label.name()).collect();
+
+    let mut classes_txt = names.join("\n");
+    classes_txt.push('\n');
+    fs::write(dir.join("classes.txt"), classes_txt)?;
+
+    // A JSON array is valid YAML and quotes any name that needs it.
+    let data_yaml = format!(
+        "nc: {}\nnames: {}\n",
+        names.len(),
+        serde_json::to_string(&names)?
+    );
+    fs::write(dir.join("data.yaml"), data_yaml)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Annotation, Box2d, Polygon};
+
+    fn labels() -> Vec<Label> {
+        serde_json::from_value(serde_json::json!([
+            { "id": 2, "index": 7, "name": "elk" },
+            { "id": 1, "index": 0, "name": "deer: mule" },
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn sample_to_yolo_writes_normalized_centers() {
+        let labels = labels();
+        let classes = yolo_classes(&labels);
+        assert_eq!(
+            classes.iter().map(|l| l.name()).collect::<Vec<_>>(),
+            ["deer: mule", "elk"]
+        );
+
+        let mut boxed = Annotation::new();
+        boxed.set_label(Some("elk".to_string()));
+        boxed.set_box2d(Some(Box2d::new(0.25, 0.5, 0.5, 0.25)));
+        let mut outlined = Annotation::new();
+        outlined.set_label(Some("deer: mule".to_string()));
+        outlined.set_polygon(Some(Polygon::new(vec![vec![(0.0, 0.0), (0.5, 0.5)]])));
+        let mut sample = Sample::new().with_annotations(vec![boxed, outlined]);
+        sample.image_name = Some("seq/frame_001.camera.jpeg".to_string());
+
+        assert_eq!(
+            yolo_label_file_name(&sample).unwrap(),
+            "frame_001.camera.txt"
+        );
+        // The same frame name in two sequences gives two label files.
+        let mut left = sample.clone();
+        left.sequence_name = Some("left".to_string());
+        left.frame_number = Some(1);
+        let mut right = left.clone();
+        right.sequence_name = Some("right".to_string());
+        assert_eq!(
+            yolo_label_file_name(&left).unwrap(),
+            "left_1_frame_001.camera.txt"
+        );
+        assert_eq!(
+            yolo_label_file_name(&right).unwrap(),
+            "right_1_frame_001.camera.txt"
+        );
+        assert_eq!(
+            sample_to_yolo(&sample, &classes).unwrap(),
+            "1 0.500000 0.625000 0.500000 0.250000\n"
+        );
+    }
+
+    #[test]
+    fn sample_to_yolo_rejects_unknown_label() {
+        let labels = labels();
+        let mut ann = Annotation::new();
+        ann.set_label(Some("moose".to_string()));
+        ann.set_box2d(Some(Box2d::new(0.0, 0.0, 1.0, 1.0)));
+        let sample = Sample::new().with_annotations(vec![ann]);
+
+        let err = sample_to_yolo(&sample, &yolo_classes(&labels)).unwrap_err();
+        assert!(matches!(err, Error::InvalidParameters(msg) if msg.contains("moose")));
+    }
+
+    #[test]
+    fn write_yolo_classes_lists_names_in_class_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let labels = labels();
+        write_yolo_classes(&yolo_classes(&labels), dir.path()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("classes.txt")).unwrap(),
+            "deer: mule\nelk\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("data.yaml")).unwrap(),
+            "nc: 2\nnames: [\"deer: mule\",\"elk\"]\n"
+        );
+    }
+}
@@ -41,7 +41,7 @@
 //!     let client = client.with_login("username", "password").await?;
 //!
 //!     // List available projects
-//!     let projects = client.projects(None).await?;
+//!     let projects = client.projects(None, None, false).await?;
 //!     println!("Found {} projects", projects.len());
 //!
 //!     Ok(())
@@ -52,44 +52,71 @@
 //!
 //! - `polars`: Enables integration with Polars DataFrames for enhanced data
 //!   manipulation
+//! - `keyring`: Adds [`KeyringTokenStorage`], which keeps the token in the OS
+//!   credential manager
+//! - `tfrecord`: Adds [`Client::export_tfrecord`], which writes an annotation
+//!   set as TFRecord shards for TensorFlow
 
 mod api;
+mod cache;
+mod checksum;
 mod client;
 pub mod coco;
+mod compression;
 mod dataset;
 mod error;
 pub mod format;
 #[cfg(feature = "profiling")]
 pub mod instrument;
 mod mask;
+mod observer;
 mod retry;
 mod storage;
 
 pub use crate::{
     api::{
-        AnnotationSetID, AppId, Artifact, ChangelogEntry, ChangelogResponse, DatasetID,
-        DatasetParams, DatasetSummary, Experiment, ExperimentID, ImageId, Job, NewTrainingSession,
-        NewValidationSession, Organization, OrganizationID, Parameter, PresignedUrl, Project,
-        ProjectID, RestoreResult, RestoredCounts, RestoredFrom, SampleDimensionUpdate, SampleID,
-        SamplesCountResult, SamplesPopulateParams, SamplesPopulateResult,
-        SamplesUpdateDimensionsResult, SchemaField, SchemaFieldType, SchemaOption, SequenceId,
-        ServerAnnotation, Snapshot, SnapshotFromDatasetResult, SnapshotID, SnapshotRestoreResult,
+        AnnotationChanges, AnnotationError, AnnotationSetID, AnnotationUploadReport, AppId,
+        Artifact, ChangelogEntry, ChangelogResponse, DatasetID, DatasetParams, DatasetSummary,
+        Experiment, ExperimentID, ExperimentSummary, ImageId, Job, NewTrainingSession,
+        NewValidationSession, Organization, OrganizationID, Page, PagedResult, Parameter,
+        PopulateReport, PresignedUrl, Project, ProjectID, RestoreResult, RestoredCounts,
+        RestoredFrom, SampleDimensionUpdate, SampleError, SampleID, SamplesCountResult,
+        SamplesPopulateParams, SamplesPopulateResult, SamplesUpdateDimensionsResult, SchemaField,
+        SchemaFieldType, SchemaOption, SequenceId, ServerAnnotation, Severity, Snapshot,
+        SnapshotFromDatasetResult, SnapshotID, SnapshotRestoreResult, SnapshotStatus, SortKey,
         Stage, StartTrainingRequest, StartValidationRequest, Tag, Task, TaskDataList, TaskID,
         TaskInfo, TrainerSchemaInfo, TrainingSession, TrainingSessionID, UsageSummary,
-        ValidationSession, ValidationSessionID, ValidatorSchema, VersionCurrentResponse,
-        VersionTag,
+        ValidationIssue, ValidationIssueKind, ValidationSession, ValidationSessionID,
+        ValidatorSchema, VersionCurrentResponse, VersionTag,
+    },
+    client::{
+        Client, DownloadOptions, Entity, FileEvent, LoginResult, Progress, ProgressPhase,
+        SamplesQuery,
     },
-    client::{Client, Progress},
     dataset::{
-        Annotation, AnnotationSet, AnnotationType, Box2d, Box3d, Dataset, FileType, GpsData, Group,
-        ImuData, Label, Location, Polygon, Sample, SampleFile, Timing,
+        Annotation, AnnotationSet, AnnotationType, Box2d, Box3d, CoordinateSpace, Dataset,
+        DatasetDiff, FileType, GpsData, Group, ImuData, Keypoints, Label, Location, Polygon,
+        Sample, SampleFields, SampleFile, SampleFiles, Timing, Visibility,
     },
-    error::Error,
+    error::{Error, RpcErrorKind},
     mask::MaskData,
-    retry::{RetryScope, classify_url},
-    storage::{FileTokenStorage, MemoryTokenStorage, StorageError, TokenStorage},
+    observer::ClientObserver,
+    retry::{RetryCondition, RetryConfig, RetryScope, classify_url},
+    storage::{
+        AsyncTokenStorage, EnvTokenStorage, FileTokenStorage, MemoryTokenStorage, StorageError,
+        TokenStorage,
+    },
 };
 
+/// Attribute for implementing [`AsyncTokenStorage`].
+pub use async_trait::async_trait;
+
+#[cfg(feature = "keyring")]
+pub use crate::storage::KeyringTokenStorage;
+
+/// Token for cancelling a download, see [`Client::download_dataset_with_file_events`].
+pub use tokio_util::sync::CancellationToken;
+
 #[cfg(feature = "profiling")]
 pub use crate::client::upload_stats;
 
@@ -157,13 +184,13 @@ mod tests {
     async fn get_training_session_for_artifacts() -> Result<TrainingSession, Error> {
         let client = get_client().await?;
         let project = client
-            .projects(Some("Unit Testing"))
+            .projects(Some("Unit Testing"), None, false)
             .await?
             .into_iter()
             .next()
             .ok_or_else(|| Error::InvalidParameters("Unit Testing project not found".into()))?;
         let experiment = client
-            .experiments(project.id(), Some("Unit Testing"))
+            .experiments(Some(project.id()), Some("Unit Testing"), None, false)
             .await?
             .into_iter()
             .next()
@@ -183,13 +210,13 @@ mod tests {
     async fn get_training_session_for_checkpoints() -> Result<TrainingSession, Error> {
         let client = get_client().await?;
         let project = client
-            .projects(Some("Unit Testing"))
+            .projects(Some("Unit Testing"), None, false)
             .await?
             .into_iter()
             .next()
             .ok_or_else(|| Error::InvalidParameters("Unit Testing project not found".into()))?;
         let experiment = client
-            .experiments(project.id(), Some("Unit Testing"))
+            .experiments(Some(project.id()), Some("Unit Testing"), None, false)
             .await?
             .into_iter()
             .next()
@@ -208,13 +235,13 @@ mod tests {
     #[tokio::test]
     async fn test_training_session() -> Result<(), Error> {
         let client = get_client().await?;
-        let project = client.projects(Some("Unit Testing")).await?;
+        let project = client.projects(Some("Unit Testing"), None, false).await?;
         assert!(!project.is_empty());
         let project = project
             .first()
             .expect("'Unit Testing' project should exist");
         let experiment = client
-            .experiments(project.id(), Some("Unit Testing"))
+            .experiments(Some(project.id()), Some("Unit Testing"), None, false)
             .await?;
         let experiment = experiment
             .first()
@@ -260,6 +287,7 @@ mod tests {
                     "artifacts/labels.txt".to_string(),
                     labels.path().to_path_buf(),
                 )],
+                None,
             )
             .await?;
 
@@ -272,13 +300,13 @@ mod tests {
     #[tokio::test]
     async fn test_validate() -> Result<(), Error> {
         let client = get_client().await?;
-        let project = client.projects(Some("Unit Testing")).await?;
+        let project = client.projects(Some("Unit Testing"), None, false).await?;
         assert!(!project.is_empty());
         let project = project
             .first()
             .expect("'Unit Testing' project should exist");
 
-        let sessions = client.validation_sessions(project.id()).await?;
+        let sessions = client.validation_sessions(Some(project.id())).await?;
         for session in &sessions {
             let s = client.validation_session(session.id()).await?;
             assert_eq!(s.id(), session.id());
@@ -357,13 +385,13 @@ mod tests {
     #[tokio::test]
     async fn test_artifacts() -> Result<(), Error> {
         let client = get_client().await?;
-        let project = client.projects(Some("Unit Testing")).await?;
+        let project = client.projects(Some("Unit Testing"), None, false).await?;
         assert!(!project.is_empty());
         let project = project
             .first()
             .expect("'Unit Testing' project should exist");
         let experiment = client
-            .experiments(project.id(), Some("Unit Testing"))
+            .experiments(Some(project.id()), Some("Unit Testing"), None, false)
             .await?;
         let experiment = experiment
             .first()
@@ -427,6 +455,7 @@ mod tests {
                     "checkpoints/test_checkpoint.txt".to_string(),
                     checkpoint_path.clone(),
                 )],
+                None,
             )
             .await?;
 
@@ -480,13 +509,13 @@ mod tests {
     #[tokio::test]
     async fn test_checkpoints() -> Result<(), Error> {
         let client = get_client().await?;
-        let project = client.projects(Some("Unit Testing")).await?;
+        let project = client.projects(Some("Unit Testing"), None, false).await?;
         assert!(!project.is_empty());
         let project = project
             .first()
             .expect("'Unit Testing' project should exist");
         let experiment = client
-            .experiments(project.id(), Some("Unit Testing"))
+            .experiments(Some(project.id()), Some("Unit Testing"), None, false)
             .await?;
         let experiment = experiment.first().ok_or_else(|| {
             Error::InvalidParameters(format!(
@@ -517,6 +546,7 @@ mod tests {
                     "checkpoints/checkpoint.txt".to_string(),
                     checkpoint_path.clone(),
                 )],
+                None,
             )
             .await?;
 
@@ -569,7 +599,7 @@ mod tests {
     #[tokio::test]
     async fn test_task_filtering_by_name() -> Result<(), Error> {
         let client = get_client().await?;
-        let project = client.projects(Some("Unit Testing")).await?;
+        let project = client.projects(Some("Unit Testing"), None, false).await?;
         let project = project
             .first()
             .expect("'Unit Testing' project should exist");
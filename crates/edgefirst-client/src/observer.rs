@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright © 2025 Au-Zone Technologies. All Rights Reserved.
+
+//! Hooks for collecting client metrics.
+//!
+//! Install a [`ClientObserver`] with
+//! [`Client::with_observer`](crate::Client::with_observer) to count requests,
+//! transferred bytes, retries and errors without parsing logs.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use edgefirst_client::{Client, ClientObserver, Error};
+//! use std::sync::{
+//!     Arc,
+//!     atomic::{AtomicU64, Ordering},
+//! };
+//!
+//! #[derive(Default)]
+//! struct Counters {
+//!     requests: AtomicU64,
+//!     bytes: AtomicU64,
+//! }
+//!
+//! impl ClientObserver for Counters {
+//!     fn on_request(&self, _name: &str) {
+//!         self.requests.fetch_add(1, Ordering::Relaxed);
+//!     }
+//!
+//!     fn on_bytes(&self, bytes: u64) {
+//!         self.bytes.fetch_add(bytes, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), Error> {
+//! let counters = Arc::new(Counters::default());
+//! let client = Client::new()?.with_observer(counters.clone());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Error;
+
+/// Receives events about the requests a [`Client`](crate::Client) makes.
+///
+/// Every hook has an empty default, so implementations only override the
+/// events they count. Hooks run inline on the task making the request and
+/// should return quickly.
+///
+/// Events cover JSON-RPC calls and sample file downloads such as those made
+/// by [`Client::download_dataset`](crate::Client::download_dataset). `name`
+/// is the JSON-RPC method, for example `dataset.list`, or `download` for a
+/// sample file.
+pub trait ClientObserver: Send + Sync {
+    /// A request is about to be sent. Called again for every retry.
+    fn on_request(&self, _name: &str) {}
+
+    /// A sample file of `bytes` bytes was downloaded.
+    fn on_bytes(&self, _bytes: u64) {}
+
+    /// A failed request will be sent again. `attempt` starts at 1 for the
+    /// first retry.
+    fn on_retry(&self, _name: &str, _attempt: u32) {}
+
+    /// A request failed for good, after any retries.
+    fn on_error(&self, _name: &str, _error: &Error) {}
+
+    /// The server replaced the session token through the `X-Refreshed-Token`
+    /// header of a JSON-RPC response. The client already uses `token` for
+    /// later requests and has written it to its token storage; implement
+    /// this to keep a copy elsewhere.
+    fn on_token_refresh(&self, _token: &str) {}
+}
@@ -101,6 +101,32 @@
 //! export EDGEFIRST_UPLOAD_TIMEOUT=900 # 15-minute per-part timeout for very slow uplinks
 //! ```
 //!
+//! # Retry Conditions
+//!
+//! Which failures are retried can be narrowed per client with a
+//! [`RetryConfig`] passed to [`crate::Client::with_retry_config`]. Each
+//! retryable failure falls under one [`RetryCondition`]:
+//!
+//! - **Network**: transport errors with no response (connection refused,
+//!   reset, DNS failure)
+//! - **Timeout**: 408 Request Timeout and transport timeouts
+//! - **RateLimit**: 429 Too Many Requests, plus 409/423 for File I/O where S3
+//!   reports transient contention
+//! - **ServerError**: 5xx responses
+//!
+//! Anything else, including every other 4xx, is never retried. The default
+//! configuration enables all four conditions, which is the behaviour
+//! described above.
+//!
+//! ```rust
+//! use edgefirst_client::{RetryCondition, RetryConfig};
+//!
+//! // Retry only on 5xx and network errors.
+//! let config = RetryConfig::default().with_backoff_on_5xx_only();
+//! assert!(config.retry_on.contains(&RetryCondition::ServerError));
+//! assert!(!config.retry_on.contains(&RetryCondition::RateLimit));
+//! ```
+//!
 //! # Examples
 //!
 //! ```rust
@@ -127,6 +153,7 @@
 //! );
 //! ```
 
+use std::collections::HashSet;
 use url::Url;
 
 /// Retry scope classification for URL-based retry policies.
@@ -148,6 +175,102 @@ pub enum RetryScope {
     FileIO,
 }
 
+/// A class of failure that a [`RetryConfig`] may retry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetryCondition {
+    /// Transport errors where no response was received, other than
+    /// timeouts.
+    Network,
+    /// HTTP 5xx responses.
+    ServerError,
+    /// HTTP 408 responses and transport timeouts.
+    Timeout,
+    /// HTTP 429 responses. For File I/O requests this also covers 409 and
+    /// 423, which S3 returns for transient contention.
+    RateLimit,
+}
+
+/// Retry settings for a [`crate::Client`].
+///
+/// Applied with [`crate::Client::with_retry_config`]. The default retries up
+/// to `EDGEFIRST_MAX_RETRIES` times (5 if unset) on every
+/// [`RetryCondition`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum retries per request, on top of the first attempt.
+    pub max_retries: u32,
+    /// Failures that are retried. Anything not covered here fails on the
+    /// first attempt.
+    pub retry_on: HashSet<RetryCondition>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        let max_retries = std::env::var("EDGEFIRST_MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5);
+        RetryConfig {
+            max_retries,
+            retry_on: HashSet::from([
+                RetryCondition::Network,
+                RetryCondition::ServerError,
+                RetryCondition::Timeout,
+                RetryCondition::RateLimit,
+            ]),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Sets the maximum number of retries per request.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the failures that are retried.
+    pub fn with_retry_on(mut self, conditions: impl IntoIterator<Item = RetryCondition>) -> Self {
+        self.retry_on = conditions.into_iter().collect();
+        self
+    }
+
+    /// Restricts retries to 5xx responses and network errors. Timeouts and
+    /// rate limiting fail on the first attempt.
+    pub fn with_backoff_on_5xx_only(self) -> Self {
+        self.with_retry_on([RetryCondition::Network, RetryCondition::ServerError])
+    }
+
+    /// Returns the condition a failed attempt falls under, or `None` when it
+    /// must not be retried.
+    ///
+    /// `status` is the HTTP status if a response was received; `timed_out`
+    /// tells transport timeouts apart from other transport errors when it
+    /// was not.
+    pub fn condition(
+        scope: &RetryScope,
+        status: Option<u16>,
+        timed_out: bool,
+    ) -> Option<RetryCondition> {
+        match status {
+            Some(408) => Some(RetryCondition::Timeout),
+            Some(429) => Some(RetryCondition::RateLimit),
+            Some(409 | 423) if *scope == RetryScope::FileIO => Some(RetryCondition::RateLimit),
+            Some(500..=599) => Some(RetryCondition::ServerError),
+            Some(_) => None,
+            None if timed_out => Some(RetryCondition::Timeout),
+            None => Some(RetryCondition::Network),
+        }
+    }
+
+    /// Returns true if a failed attempt should be retried under this
+    /// configuration. See [`RetryConfig::condition`] for the arguments.
+    pub fn should_retry(&self, scope: &RetryScope, status: Option<u16>, timed_out: bool) -> bool {
+        Self::condition(scope, status, timed_out)
+            .is_some_and(|condition| self.retry_on.contains(&condition))
+    }
+}
+
 /// Classifies a URL to determine which retry policy to apply.
 ///
 /// This function performs URL-based classification to differentiate between
@@ -246,7 +369,8 @@ pub fn classify_url(url: &str) -> RetryScope {
 ///
 /// # Retry Configuration
 ///
-/// - **Max retries**: Configurable via `EDGEFIRST_MAX_RETRIES` (default: 5)
+/// - **Max retries**: `config.max_retries`, which defaults to
+///   `EDGEFIRST_MAX_RETRIES` (default: 5)
 /// - **Timeout**: Configurable via `EDGEFIRST_TIMEOUT` (default: 30 seconds)
 ///   for fast API calls; bulk transfers and paginated sample fetches use
 ///   `EDGEFIRST_READ_TIMEOUT` (default: 120 seconds idle) via `bulk_http` /
@@ -276,6 +400,9 @@ pub fn classify_url(url: &str) -> RetryScope {
 /// | 5xx | Retry | Server error - S3 transient issues |
 /// | Connection errors | Retry | Network issues - common in parallel uploads |
 ///
+/// Narrowing `config.retry_on` removes the corresponding rows from both
+/// tables; see [`RetryCondition`].
+///
 /// # Usage Recommendations
 ///
 /// **For dataset downloads/uploads** (many concurrent S3 operations):
@@ -299,44 +426,36 @@ pub fn classify_url(url: &str) -> RetryScope {
 ///
 /// For operations requiring different retry counts, use separate Client
 /// instances with different `EDGEFIRST_MAX_RETRIES` configuration.
-pub fn create_retry_policy() -> reqwest::retry::Builder {
-    let max_retries = std::env::var("EDGEFIRST_MAX_RETRIES")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(5);
+pub fn create_retry_policy(config: &RetryConfig) -> reqwest::retry::Builder {
+    let config = config.clone();
 
     // Use wildcard host scope since we do URL inspection in classify_fn
     reqwest::retry::for_host("*")
-        .max_retries_per_request(max_retries)
-        .classify_fn(|req_rep| {
+        .max_retries_per_request(config.max_retries)
+        .classify_fn(move |req_rep| {
             let url = req_rep.uri().to_string();
+            let scope = classify_url(&url);
 
-            match classify_url(&url) {
-                RetryScope::StudioApi => {
-                    // Studio API: Never retry auth failures, retry server errors
-                    match req_rep.status() {
-                        Some(status) => match status.as_u16() {
-                            401 | 403 => req_rep.success(), // Auth failures - don't retry
-                            429 | 408 | 500..=599 => req_rep.retryable(),
-                            _ => req_rep.success(),
-                        },
-                        // No status code means connection error, timeout, or other transport
-                        // failure These are safe to retry for API calls
-                        None if req_rep.error().is_some() => req_rep.retryable(),
-                        None => req_rep.success(),
-                    }
-                }
-                RetryScope::FileIO => {
-                    // File I/O: Retry all transient errors
-                    match req_rep.status() {
-                        Some(status) => match status.as_u16() {
-                            429 | 408 | 500..=599 | 409 | 423 => req_rep.retryable(),
-                            _ => req_rep.success(),
-                        },
-                        None if req_rep.error().is_some() => req_rep.retryable(),
-                        None => req_rep.success(),
-                    }
+            // Successful responses and requests that produced neither a
+            // status nor an error are never retried. 401/403 are not covered
+            // by any condition, so auth failures fail fast for both scopes.
+            let status = req_rep.status();
+            match status {
+                Some(status) if !status.is_client_error() && !status.is_server_error() => {
+                    return req_rep.success();
                 }
+                None if req_rep.error().is_none() => return req_rep.success(),
+                _ => {}
+            }
+
+            let timed_out = req_rep
+                .error()
+                .and_then(|e| e.downcast_ref::<reqwest::Error>())
+                .is_some_and(|e| e.is_timeout());
+            if config.should_retry(&scope, status.map(|s| s.as_u16()), timed_out) {
+                req_rep.retryable()
+            } else {
+                req_rep.success()
             }
         })
 }
@@ -398,6 +517,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_default_config_retries_transient_failures() {
+        let config = RetryConfig::default();
+        for status in [408, 429, 500, 502, 503] {
+            assert!(config.should_retry(&RetryScope::StudioApi, Some(status), false));
+        }
+        assert!(config.should_retry(&RetryScope::StudioApi, None, false));
+        assert!(config.should_retry(&RetryScope::StudioApi, None, true));
+        assert!(config.should_retry(&RetryScope::FileIO, Some(409), false));
+        assert!(config.should_retry(&RetryScope::FileIO, Some(423), false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, Some(409), false));
+    }
+
+    #[test]
+    fn test_client_errors_never_retried() {
+        let config = RetryConfig::default();
+        for scope in [RetryScope::StudioApi, RetryScope::FileIO] {
+            for status in [400, 401, 403, 404, 422] {
+                assert!(!config.should_retry(&scope, Some(status), false));
+            }
+        }
+    }
+
+    #[test]
+    fn test_backoff_on_5xx_only() {
+        let config = RetryConfig::default().with_backoff_on_5xx_only();
+        assert!(config.should_retry(&RetryScope::StudioApi, Some(503), false));
+        assert!(config.should_retry(&RetryScope::FileIO, None, false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, Some(400), false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, Some(408), false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, Some(429), false));
+        assert!(!config.should_retry(&RetryScope::FileIO, Some(423), false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, None, true));
+    }
+
+    #[test]
+    fn test_empty_retry_on_disables_retries() {
+        let config = RetryConfig::default().with_retry_on([]);
+        assert!(!config.should_retry(&RetryScope::StudioApi, Some(503), false));
+        assert!(!config.should_retry(&RetryScope::StudioApi, None, false));
+    }
+
     #[test]
     fn test_classify_url_file_io() {
         // S3 URLs for file operations
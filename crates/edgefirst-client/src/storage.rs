@@ -10,12 +10,24 @@
 //!
 //! - [`FileTokenStorage`]: Default file-based storage for desktop platforms
 //! - [`MemoryTokenStorage`]: In-memory storage (no persistence)
+//! - [`EnvTokenStorage`]: Reads the token from an environment variable, for
+//!   CI pipelines that inject secrets that way
+//! - `KeyringTokenStorage`: The OS credential manager (macOS Keychain,
+//!   Windows Credential Manager, Secret Service on Linux), with the `keyring`
+//!   feature
+//!
+//! All implement the synchronous [`TokenStorage`] trait and are installed
+//! with [`Client::with_storage`](crate::Client::with_storage).
 //!
 //! # Custom Storage
 //!
 //! Implement the [`TokenStorage`] trait to create custom storage backends,
 //! such as iOS Keychain or Android EncryptedSharedPreferences.
 //!
+//! Backends that have to await, such as cloud secret managers, implement
+//! [`AsyncTokenStorage`] instead and are installed with
+//! [`Client::with_async_storage`](crate::Client::with_async_storage).
+//!
 //! # Examples
 //!
 //! ```rust,no_run
@@ -37,8 +49,15 @@
 //! ```
 
 use directories::ProjectDirs;
-use log::debug;
-use std::{path::PathBuf, sync::RwLock};
+use futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
+use log::{debug, warn};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+};
 
 /// Error type for token storage operations.
 #[derive(Debug)]
@@ -118,6 +137,194 @@ pub trait TokenStorage: Send + Sync {
     fn clear(&self) -> Result<(), StorageError>;
 }
 
+/// Asynchronous counterpart of [`TokenStorage`] for backends that must
+/// await, such as cloud secret managers or remote key stores.
+///
+/// The client awaits these methods from its async operations (`with_login`,
+/// `renew_token`, `logout`, `save_token`). The synchronous builders
+/// [`Client::with_token`](crate::Client::with_token) and
+/// [`Client::with_server`](crate::Client::with_server) cannot await: they
+/// spawn the store or clear on the current Tokio runtime, or run it to
+/// completion on a temporary runtime when called outside one. Stores and
+/// clears always reach the backend in the order they were made, and `load`
+/// waits for the ones still in flight, so a `with_server` followed by
+/// `with_token` leaves the new token stored.
+///
+/// Implement it with the re-exported [`async_trait`](crate::async_trait)
+/// attribute.
+///
+/// # Example Implementation
+///
+/// ```rust,ignore
+/// use edgefirst_client::{AsyncTokenStorage, StorageError, async_trait};
+///
+/// struct SecretManagerStorage {
+///     secret_id: String,
+/// }
+///
+/// #[async_trait]
+/// impl AsyncTokenStorage for SecretManagerStorage {
+///     async fn store(&self, token: &str) -> Result<(), StorageError> {
+///         // Put a new secret version
+///         Ok(())
+///     }
+///
+///     async fn load(&self) -> Result<Option<String>, StorageError> {
+///         // Fetch the latest secret version
+///         Ok(Some("token".to_string()))
+///     }
+///
+///     async fn clear(&self) -> Result<(), StorageError> {
+///         // Delete the secret
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait AsyncTokenStorage: Send + Sync {
+    /// Store the authentication token.
+    async fn store(&self, token: &str) -> Result<(), StorageError>;
+
+    /// Load the stored authentication token.
+    ///
+    /// Returns `Ok(None)` if no token is stored.
+    async fn load(&self) -> Result<Option<String>, StorageError>;
+
+    /// Clear the stored authentication token.
+    async fn clear(&self) -> Result<(), StorageError>;
+}
+
+/// Storage backend held by a [`Client`](crate::Client): either flavour of
+/// token storage behind one async interface.
+#[derive(Clone)]
+pub(crate) enum TokenStore {
+    Sync(Arc<dyn TokenStorage>),
+    Async(AsyncStore),
+}
+
+impl TokenStore {
+    pub(crate) async fn store(&self, token: &str) -> Result<(), StorageError> {
+        match self {
+            TokenStore::Sync(storage) => storage.store(token),
+            TokenStore::Async(storage) => storage.store(token).await,
+        }
+    }
+
+    pub(crate) async fn clear(&self) -> Result<(), StorageError> {
+        match self {
+            TokenStore::Sync(storage) => storage.clear(),
+            TokenStore::Async(storage) => storage.clear().await,
+        }
+    }
+
+    /// Stores `token` from a synchronous caller.
+    ///
+    /// Async backends are queued behind earlier writes and spawned on the
+    /// current Tokio runtime, with failures logged, or run to completion
+    /// when there is no runtime.
+    pub(crate) fn store_blocking(&self, token: &str) -> Result<(), StorageError> {
+        match self {
+            TokenStore::Sync(storage) => storage.store(token),
+            TokenStore::Async(storage) => bridge(storage.store(token)),
+        }
+    }
+
+    /// Clears the token from a synchronous caller, bridging async backends
+    /// as [`store_blocking`][Self::store_blocking] does.
+    pub(crate) fn clear_blocking(&self) -> Result<(), StorageError> {
+        match self {
+            TokenStore::Sync(storage) => storage.clear(),
+            TokenStore::Async(storage) => bridge(storage.clear()),
+        }
+    }
+}
+
+/// An [`AsyncTokenStorage`] whose stores and clears run one at a time, in
+/// the order they were requested.
+///
+/// Each write is chained after the previous one when it is requested rather
+/// than when it first runs, so writes spawned by the synchronous builders
+/// cannot overtake each other or a later awaited write.
+#[derive(Clone)]
+pub(crate) struct AsyncStore {
+    storage: Arc<dyn AsyncTokenStorage>,
+    last: Arc<Mutex<Shared<BoxFuture<'static, ()>>>>,
+}
+
+impl AsyncStore {
+    pub(crate) fn new(storage: Arc<dyn AsyncTokenStorage>) -> Self {
+        AsyncStore {
+            storage,
+            last: Arc::new(Mutex::new(futures::future::ready(()).boxed().shared())),
+        }
+    }
+
+    /// Loads the token once the writes requested so far have finished.
+    pub(crate) async fn load(&self) -> Result<Option<String>, StorageError> {
+        let pending = self.last.lock().expect("token queue lock poisoned").clone();
+        pending.await;
+        self.storage.load().await
+    }
+
+    fn store(&self, token: &str) -> BoxFuture<'static, Result<(), StorageError>> {
+        let storage = self.storage.clone();
+        let token = token.to_string();
+        self.enqueue(async move { storage.store(&token).await }.boxed())
+    }
+
+    fn clear(&self) -> BoxFuture<'static, Result<(), StorageError>> {
+        let storage = self.storage.clone();
+        self.enqueue(async move { storage.clear().await }.boxed())
+    }
+
+    /// Chains `op` after the last queued write and returns a future for its
+    /// result. `op` runs even if that future is dropped, as long as a later
+    /// write or load is awaited.
+    fn enqueue(
+        &self,
+        op: BoxFuture<'static, Result<(), StorageError>>,
+    ) -> BoxFuture<'static, Result<(), StorageError>> {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        let mut last = self.last.lock().expect("token queue lock poisoned");
+        let previous = last.clone();
+        let next = async move {
+            previous.await;
+            let _ = tx.send(op.await);
+        }
+        .boxed()
+        .shared();
+        *last = next.clone();
+
+        async move {
+            next.await;
+            rx.await.unwrap_or(Ok(()))
+        }
+        .boxed()
+    }
+}
+
+/// Runs an async storage operation from synchronous code. Blocking inside a
+/// runtime worker would stall it, so there the operation is spawned instead.
+/// Outside a runtime it runs on a temporary current-thread runtime, so
+/// backends built on Tokio I/O and timers still work.
+fn bridge(op: BoxFuture<'static, Result<(), StorageError>>) -> Result<(), StorageError> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn(async move {
+                if let Err(e) = op.await {
+                    warn!("Async token storage operation failed: {}", e);
+                }
+            });
+            Ok(())
+        }
+        Err(_) => tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| StorageError::NotAvailable(format!("no Tokio runtime: {}", e)))?
+            .block_on(op),
+    }
+}
+
 /// File-based token storage for desktop platforms.
 ///
 /// Stores the authentication token in a file on the local filesystem. By
@@ -188,7 +395,16 @@ impl TokenStorage for FileTokenStorage {
             })?;
         }
 
-        std::fs::write(&self.path, token).map_err(|e| {
+        // Write a temporary file and rename it into place, so a crash while
+        // storing never leaves a truncated token behind.
+        let tmp = self
+            .path
+            .with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        std::fs::write(&tmp, token).map_err(|e| {
+            StorageError::WriteError(format!("Failed to write token to {:?}: {}", tmp, e))
+        })?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
             StorageError::WriteError(format!("Failed to write token to {:?}: {}", self.path, e))
         })?;
 
@@ -287,10 +503,229 @@ impl TokenStorage for MemoryTokenStorage {
     }
 }
 
+/// Token storage that reads the token from an environment variable.
+///
+/// [`load`](TokenStorage::load) returns the variable's value, or `None` when
+/// it is unset or empty, which suits CI pipelines that inject the token as a
+/// secret. A token passed to [`store`](TokenStorage::store), for example
+/// after a login or refresh, overrides the variable for this storage only.
+/// [`clear`](TokenStorage::clear) makes later loads return `None`; the
+/// process environment itself is never modified, as changing it is not
+/// thread-safe.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use edgefirst_client::{Client, EnvTokenStorage};
+/// use std::sync::Arc;
+///
+/// # fn main() -> Result<(), edgefirst_client::Error> {
+/// // Reads EDGEFIRST_TOKEN.
+/// let client = Client::new()?.with_storage(Arc::new(EnvTokenStorage::new()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EnvTokenStorage {
+    var: String,
+    state: RwLock<EnvToken>,
+}
+
+/// Where [`EnvTokenStorage`] currently takes its token from.
+#[derive(Debug)]
+enum EnvToken {
+    /// The environment variable.
+    Env,
+    /// A token passed to `store`.
+    Stored(String),
+    /// Nothing, after `clear`.
+    Cleared,
+}
+
+impl EnvTokenStorage {
+    /// Variable read by [`new`](Self::new).
+    pub const DEFAULT_VAR: &'static str = "EDGEFIRST_TOKEN";
+
+    /// Create an `EnvTokenStorage` reading
+    /// [`DEFAULT_VAR`](Self::DEFAULT_VAR).
+    pub fn new() -> Self {
+        Self::with_var(Self::DEFAULT_VAR)
+    }
+
+    /// Create an `EnvTokenStorage` reading the variable `var`.
+    pub fn with_var(var: &str) -> Self {
+        Self {
+            var: var.to_string(),
+            state: RwLock::new(EnvToken::Env),
+        }
+    }
+
+    /// Returns the name of the variable the token is read from.
+    pub fn var(&self) -> &str {
+        &self.var
+    }
+}
+
+impl Default for EnvTokenStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenStorage for EnvTokenStorage {
+    fn store(&self, token: &str) -> Result<(), StorageError> {
+        let mut guard = self.state.write().map_err(|e| {
+            StorageError::WriteError(format!("Failed to acquire write lock: {}", e))
+        })?;
+        *guard = EnvToken::Stored(token.to_string());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<String>, StorageError> {
+        let guard = self
+            .state
+            .read()
+            .map_err(|e| StorageError::ReadError(format!("Failed to acquire read lock: {}", e)))?;
+        match &*guard {
+            EnvToken::Env => match std::env::var(&self.var) {
+                Ok(token) if !token.is_empty() => {
+                    debug!("Token loaded from environment variable {}", self.var);
+                    Ok(Some(token))
+                }
+                Ok(_) | Err(std::env::VarError::NotPresent) => Ok(None),
+                Err(e) => Err(StorageError::ReadError(format!(
+                    "Failed to read environment variable {}: {}",
+                    self.var, e
+                ))),
+            },
+            EnvToken::Stored(token) => Ok(Some(token.clone())),
+            EnvToken::Cleared => Ok(None),
+        }
+    }
+
+    fn clear(&self) -> Result<(), StorageError> {
+        let mut guard = self.state.write().map_err(|e| {
+            StorageError::ClearError(format!("Failed to acquire write lock: {}", e))
+        })?;
+        *guard = EnvToken::Cleared;
+        Ok(())
+    }
+}
+
+/// Token storage in the operating system's credential manager.
+///
+/// Keeps the token in the macOS Keychain, the Windows Credential Manager or
+/// the freedesktop Secret Service on Linux (GNOME Keyring, KWallet), under
+/// the account `token` of the given service name. Unlike
+/// [`FileTokenStorage`], the token is encrypted at rest and guarded by the
+/// user's login session.
+///
+/// Requires the `keyring` feature.
+///
+/// Backend failures map onto [`StorageError`]: a credential manager that is
+/// missing, locked or refuses access gives [`StorageError::NotAvailable`],
+/// other failures the read, write or clear error of the operation. On Linux
+/// a Secret Service daemon must be running on the session bus.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use edgefirst_client::{Client, KeyringTokenStorage};
+/// use std::sync::Arc;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let storage = KeyringTokenStorage::new()?;
+/// let client = Client::new()?.with_storage(Arc::new(storage));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "keyring")]
+#[derive(Debug)]
+pub struct KeyringTokenStorage {
+    service: String,
+    entry: keyring::Entry,
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringTokenStorage {
+    /// Service name used by [`new`](Self::new).
+    pub const DEFAULT_SERVICE: &'static str = "edgefirst";
+
+    /// Account the token is stored under within the service.
+    const ACCOUNT: &'static str = "token";
+
+    /// Create a `KeyringTokenStorage` under the
+    /// [`DEFAULT_SERVICE`](Self::DEFAULT_SERVICE) name.
+    pub fn new() -> Result<Self, StorageError> {
+        Self::with_service(Self::DEFAULT_SERVICE)
+    }
+
+    /// Create a `KeyringTokenStorage` under a custom service name, for
+    /// example to keep tokens for several Studio servers apart.
+    pub fn with_service(service: &str) -> Result<Self, StorageError> {
+        let entry = keyring::Entry::new(service, Self::ACCOUNT)
+            .map_err(|e| StorageError::NotAvailable(e.to_string()))?;
+        debug!("KeyringTokenStorage using service {:?}", service);
+        Ok(Self {
+            service: service.to_string(),
+            entry,
+        })
+    }
+
+    /// Returns the service name the token is stored under.
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+}
+
+/// Maps a credential manager error to [`StorageError`], using `or` for
+/// failures other than an unreachable or locked store.
+#[cfg(feature = "keyring")]
+fn keyring_error(err: keyring::Error, or: fn(String) -> StorageError) -> StorageError {
+    match err {
+        keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_) => {
+            StorageError::NotAvailable(err.to_string())
+        }
+        err => or(err.to_string()),
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl TokenStorage for KeyringTokenStorage {
+    fn store(&self, token: &str) -> Result<(), StorageError> {
+        self.entry
+            .set_password(token)
+            .map_err(|e| keyring_error(e, StorageError::WriteError))?;
+        debug!("Token stored in keyring service {:?}", self.service);
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<String>, StorageError> {
+        match self.entry.get_password() {
+            Ok(token) if token.is_empty() => Ok(None),
+            Ok(token) => {
+                debug!("Token loaded from keyring service {:?}", self.service);
+                Ok(Some(token))
+            }
+            Err(keyring::Error::NoEntry) => {
+                debug!("No token in keyring service {:?}", self.service);
+                Ok(None)
+            }
+            Err(e) => Err(keyring_error(e, StorageError::ReadError)),
+        }
+    }
+
+    fn clear(&self) -> Result<(), StorageError> {
+        match self.entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error(e, StorageError::ClearError)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Arc;
     use tempfile::TempDir;
 
     #[test]
@@ -333,6 +768,32 @@ mod tests {
         assert_eq!(storage.load().unwrap(), Some("thread-token".to_string()));
     }
 
+    #[test]
+    fn test_env_storage_missing_var_loads_none() {
+        let storage = EnvTokenStorage::with_var("EDGEFIRST_TEST_ENV_STORAGE_UNSET");
+        assert_eq!(storage.var(), "EDGEFIRST_TEST_ENV_STORAGE_UNSET");
+        assert_eq!(storage.load().unwrap(), None);
+    }
+
+    #[test]
+    fn test_env_storage_store_overrides_and_clear_hides_var() {
+        const VAR: &str = "EDGEFIRST_TEST_ENV_STORAGE_TOKEN";
+        // SAFETY: no other test reads or writes this variable.
+        unsafe {
+            std::env::set_var(VAR, "env-token");
+        }
+        let storage = EnvTokenStorage::with_var(VAR);
+        assert_eq!(storage.load().unwrap(), Some("env-token".to_string()));
+
+        storage.store("refreshed-token").unwrap();
+        assert_eq!(storage.load().unwrap(), Some("refreshed-token".to_string()));
+
+        storage.clear().unwrap();
+        assert_eq!(storage.load().unwrap(), None);
+        // The process environment is left alone.
+        assert_eq!(std::env::var(VAR).as_deref(), Ok("env-token"));
+    }
+
     #[test]
     fn test_file_storage_store_load_clear() {
         let temp_dir = TempDir::new().unwrap();
@@ -375,6 +836,9 @@ mod tests {
 
         storage.store("token-2").unwrap();
         assert_eq!(storage.load().unwrap(), Some("token-2".to_string()));
+
+        // The temporary file is renamed over the token, never left behind.
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
     }
 
     #[test]
@@ -409,4 +873,72 @@ mod tests {
         let err = StorageError::ClearError("clear failed".to_string());
         assert!(err.to_string().contains("clear failed"));
     }
+
+    /// A keyring storage backed by the `keyring` crate's in-process mock,
+    /// which keeps one credential per entry and can inject errors.
+    #[cfg(feature = "keyring")]
+    fn mock_keyring_storage() -> KeyringTokenStorage {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        KeyringTokenStorage::with_service("edgefirst-test").unwrap()
+    }
+
+    #[cfg(feature = "keyring")]
+    fn inject_keyring_error(storage: &KeyringTokenStorage, error: keyring::Error) {
+        storage
+            .entry
+            .get_credential()
+            .downcast_ref::<keyring::mock::MockCredential>()
+            .unwrap()
+            .set_error(error);
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn test_keyring_storage_store_load_clear() {
+        let storage = mock_keyring_storage();
+        assert_eq!(storage.service(), "edgefirst-test");
+
+        // Nothing stored yet, and clearing an empty entry is not an error
+        assert_eq!(storage.load().unwrap(), None);
+        storage.clear().unwrap();
+
+        storage.store("keyring-token").unwrap();
+        assert_eq!(storage.load().unwrap(), Some("keyring-token".to_string()));
+
+        storage.clear().unwrap();
+        assert_eq!(storage.load().unwrap(), None);
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn test_keyring_storage_maps_backend_errors() {
+        let storage = mock_keyring_storage();
+
+        inject_keyring_error(
+            &storage,
+            keyring::Error::NoStorageAccess("keychain locked".into()),
+        );
+        assert!(matches!(
+            storage.store("token"),
+            Err(StorageError::NotAvailable(msg)) if msg.contains("keychain locked")
+        ));
+
+        inject_keyring_error(
+            &storage,
+            keyring::Error::TooLong("password".to_string(), 2560),
+        );
+        assert!(matches!(
+            storage.store("token"),
+            Err(StorageError::WriteError(_))
+        ));
+
+        inject_keyring_error(&storage, keyring::Error::BadEncoding(vec![0xff]));
+        assert!(matches!(storage.load(), Err(StorageError::ReadError(_))));
+
+        inject_keyring_error(
+            &storage,
+            keyring::Error::Invalid("service".to_string(), "empty".to_string()),
+        );
+        assert!(matches!(storage.clear(), Err(StorageError::ClearError(_))));
+    }
 }
@@ -33,12 +33,16 @@
 
 use base64::Engine as _;
 use edgefirst_client::{
-    Client, DatasetID, Error, ExperimentID, Parameter, SampleDimensionUpdate, SampleID, TaskID,
-    TrainingSessionID, ValidationSessionID,
+    AnnotationSetID, Client, DatasetID, DownloadOptions, Entity, Error, ExperimentID, FileEvent,
+    Page, Parameter, ProgressPhase, ProjectID, RetryCondition, RetryConfig, RpcErrorKind,
+    SampleDimensionUpdate, SampleFields, SampleFile, SampleID, SamplesQuery, ServerAnnotation,
+    Severity, SortKey, TaskID, TrainingSessionID, ValidationIssueKind, ValidationSessionID,
 };
 use serde_json::json;
 use serial_test::serial;
-use wiremock::matchers::{body_json, body_partial_json, method, path, query_param};
+use wiremock::matchers::{
+    body_bytes, body_json, body_partial_json, header, method, path, path_regex, query_param,
+};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 // ---------------------------------------------------------------------------
@@ -49,13 +53,14 @@ fn b64(bytes: &[u8]) -> String {
     base64::engine::general_purpose::STANDARD_NO_PAD.encode(bytes)
 }
 
-/// Build a minimal JWT carrying `server="test"` and a far-future `exp`,
-/// so the client treats it as valid and never tries to renew during a
-/// test. The signature is opaque — the client never verifies it.
+/// Build a minimal JWT carrying `server="test"`, a `username` and a
+/// far-future `exp`, so the client treats it as valid and never tries to
+/// renew during a test. The signature is opaque — the client never
+/// verifies it.
 fn fake_jwt() -> String {
     let header = b64(b"{\"alg\":\"none\",\"typ\":\"JWT\"}");
     // exp = 2_000_000_000 → 2033-05-18. Well past the test deadline.
-    let payload = b64(b"{\"server\":\"test\",\"exp\":2000000000}");
+    let payload = b64(b"{\"server\":\"test\",\"username\":\"tester\",\"exp\":2000000000}");
     let signature = b64(b"signature");
     format!("{header}.{payload}.{signature}")
 }
@@ -944,7 +949,10 @@ async fn delete_validation_sessions_maps_permission_denied() {
         .await
         .expect_err("expected permission failure");
     assert!(
-        matches!(err, Error::PermissionDenied(_) | Error::RpcError(100, _)),
+        matches!(
+            err,
+            Error::PermissionDenied(_) | Error::RpcError { code: 100, .. }
+        ),
         "expected PermissionDenied or RpcError(100), got {err:?}"
     );
 }
@@ -1031,7 +1039,10 @@ async fn delete_training_sessions_maps_permission_denied() {
         .await
         .expect_err("expected permission failure");
     assert!(
-        matches!(err, Error::PermissionDenied(_) | Error::RpcError(100, _)),
+        matches!(
+            err,
+            Error::PermissionDenied(_) | Error::RpcError { code: 100, .. }
+        ),
         "expected PermissionDenied or RpcError(100), got {err:?}"
     );
 }
@@ -2190,7 +2201,6755 @@ async fn usage_summary_surfaces_jsonrpc_error() {
         .await
         .expect_err("usage_summary should surface the JSON-RPC error");
     assert!(
-        matches!(err, Error::RpcError(-32000, _)),
+        matches!(err, Error::RpcError { code: -32000, .. }),
         "expected RpcError(-32000, _), got {err:?}"
     );
 }
+
+#[tokio::test]
+async fn method_not_found_maps_to_unsupported_by_server() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("accounting.get_usage_summary"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_error(-32601, "Method not found")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client_for(&server.uri())
+        .usage_summary()
+        .await
+        .expect_err("an unknown method must fail");
+    assert!(
+        matches!(&err, Error::UnsupportedByServer { feature } if feature == "accounting.get_usage_summary"),
+        "got {err:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// samples_since
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn samples_since_keeps_only_samples_dated_after_cutoff() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 4 }))))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [
+                { "id": 1, "image_name": "old.png", "date": "2026-01-01T00:00:00Z" },
+                // Exactly at the cutoff: excluded, the bound is exclusive.
+                { "id": 2, "image_name": "cutoff.png", "date": "2026-03-01T00:00:00Z" },
+                { "id": 3, "image_name": "new.png", "date": "2026-03-01T00:00:01Z" },
+                // No date: cannot be placed relative to the cutoff.
+                { "id": 4, "image_name": "undated.png" },
+            ],
+            "continue_token": null
+        }))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let since = "2026-03-01T00:00:00Z"
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .unwrap();
+    let samples = client
+        .samples_since(DatasetID::from(1u64), since, None)
+        .await
+        .expect("samples_since via mock");
+    let ids: Vec<_> = samples.iter().map(|s| s.id()).collect();
+    assert_eq!(ids, vec![Some(SampleID::from(3u64))]);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::samples` labeled filter
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn samples_labeled_filter_partitions_by_annotation_presence() {
+    let server = MockServer::start().await;
+    for (name, result) in [
+        (
+            "label.list",
+            json!([{ "id": 10, "index": 0, "name": "cat" }]),
+        ),
+        ("samples.count", json!({ "total": 3 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    {
+                        "id": 1, "image_name": "a.jpg",
+                        "annotations": [
+                            { "label_name": "cat", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                        ],
+                    },
+                    { "id": 2, "image_name": "b.jpg", "annotations": [] },
+                    { "id": 3, "image_name": "c.jpg" },
+                ],
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let ids = async |labeled| {
+        client
+            .samples(
+                DatasetID::from(1u64),
+                None,
+                SamplesQuery {
+                    annotation_set_id: Some(AnnotationSetID::from(2u64)),
+                    labeled,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .iter()
+            .map(|s| s.id().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(Some(true)).await, [SampleID::from(1u64)]);
+    assert_eq!(
+        ids(Some(false)).await,
+        [SampleID::from(2u64), SampleID::from(3u64)]
+    );
+    assert_eq!(ids(None).await.len(), 3);
+}
+
+#[tokio::test]
+async fn samples_labeled_filter_requires_annotation_set() {
+    let server = MockServer::start().await;
+    let err = client_for(&server.uri())
+        .samples(
+            DatasetID::from(1u64),
+            None,
+            SamplesQuery {
+                labeled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+    assert_eq!(request_count(&server).await, 0);
+}
+
+#[tokio::test]
+async fn samples_date_window_keeps_dated_samples_inside_it() {
+    let server = MockServer::start().await;
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 4 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    { "id": 1, "image_name": "a.jpg", "date": "2026-03-01T00:00:00Z" },
+                    { "id": 2, "image_name": "b.jpg", "date": "2026-03-15T12:00:00Z" },
+                    { "id": 3, "image_name": "c.jpg", "date": "2026-04-01T00:00:00Z" },
+                    { "id": 4, "image_name": "d.jpg" },
+                ],
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let date = |s: &str| s.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+    let ids = async |start_date, end_date| {
+        client
+            .samples(
+                DatasetID::from(1u64),
+                None,
+                SamplesQuery {
+                    start_date,
+                    end_date,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map(|samples| samples.iter().map(|s| s.id().unwrap()).collect::<Vec<_>>())
+    };
+    assert_eq!(
+        ids(
+            Some(date("2026-03-01T00:00:00Z")),
+            Some(date("2026-03-31T00:00:00Z"))
+        )
+        .await
+        .unwrap(),
+        [SampleID::from(1u64), SampleID::from(2u64)],
+        "bounds are inclusive"
+    );
+    assert_eq!(
+        ids(Some(date("2026-03-10T00:00:00Z")), None).await.unwrap(),
+        [SampleID::from(2u64), SampleID::from(3u64)],
+        "open end, undated sample dropped"
+    );
+    assert_eq!(ids(None, None).await.unwrap().len(), 4);
+
+    let requests = request_count(&server).await;
+    let err = ids(
+        Some(date("2026-04-01T00:00:00Z")),
+        Some(date("2026-03-01T00:00:00Z")),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+    assert_eq!(request_count(&server).await, requests);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::samples_stream`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn samples_stream_fetches_next_page_only_when_drained() {
+    use futures::TryStreamExt as _;
+
+    let server = MockServer::start().await;
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 3 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    { "id": 1, "image_name": "a.jpg" },
+                    { "id": 2, "image_name": "b.jpg" },
+                ],
+                "continue_token": "page-2"
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.list",
+            "params": { "continue_token": "page-2" }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{ "id": 3, "image_name": "c.jpg" }],
+            "continue_token": null
+        }))))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    let list_requests = async || {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| String::from_utf8_lossy(&r.body).contains("samples.list"))
+            .count()
+    };
+
+    let client = client_for(&server.uri());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let stream = client.samples_stream(
+        DatasetID::from(1u64),
+        None,
+        &[],
+        &[],
+        &[],
+        Some(tx),
+        None,
+        None,
+    );
+    let mut stream = std::pin::pin!(stream);
+    assert_eq!(
+        list_requests().await,
+        0,
+        "nothing is fetched before polling"
+    );
+
+    let first = stream.try_next().await.unwrap().unwrap();
+    assert_eq!(first.id(), Some(SampleID::from(1u64)));
+    let second = stream.try_next().await.unwrap().unwrap();
+    assert_eq!(second.id(), Some(SampleID::from(2u64)));
+    assert_eq!(list_requests().await, 1);
+
+    let rest: Vec<_> = stream.try_collect().await.unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].id(), Some(SampleID::from(3u64)));
+    assert_eq!(list_requests().await, 2);
+
+    let mut progress = Vec::new();
+    while let Some(p) = rx.recv().await {
+        progress.push((p.current, p.total));
+    }
+    assert_eq!(progress, [(2, 3), (3, 3)]);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::annotations_stream`
+// ---------------------------------------------------------------------------
+
+/// Mounts an annotation set 7 on dataset 1 whose `samples.list` answers the
+/// first page with `first_page` and the `page-2` cursor with `second_page`.
+async fn mount_paged_annotation_set(
+    server: &MockServer,
+    first_page: serde_json::Value,
+    second_page: ResponseTemplate,
+) {
+    for (name, result) in [
+        (
+            "annset.get",
+            json!({ "id": 7, "dataset_id": 1, "name": "Default", "description": "" }),
+        ),
+        (
+            "label.list",
+            json!([
+                { "id": 1, "index": 0, "name": "cat" },
+                { "id": 2, "index": 1, "name": "dog" },
+            ]),
+        ),
+        ("samples.count", json!({ "total": 3 })),
+        ("samples.list", first_page),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.list",
+            "params": { "continue_token": "page-2" }
+        })))
+        .respond_with(second_page)
+        .with_priority(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn annotations_stream_follows_cursor_without_skips_or_duplicates() {
+    use futures::TryStreamExt as _;
+
+    let server = MockServer::start().await;
+    mount_paged_annotation_set(
+        &server,
+        json!({
+            "samples": [
+                {
+                    "id": 1,
+                    "image_name": "a.jpg",
+                    "annotations": [
+                        { "label_name": "cat", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                        { "label_name": "dog", "x": 0.5, "y": 0.5, "w": 0.2, "h": 0.2 },
+                    ],
+                },
+                { "id": 2, "image_name": "b.jpg" },
+            ],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{
+                "id": 3,
+                "image_name": "c.jpg",
+                "annotations": [
+                    { "label_name": "dog", "x": 0.3, "y": 0.3, "w": 0.2, "h": 0.2 },
+                ],
+            }],
+            "continue_token": null
+        }))),
+    )
+    .await;
+
+    let client = client_for(&server.uri());
+    let annotations: Vec<_> = client
+        .annotations_stream(AnnotationSetID::from(7u64), &[], &[], None, None)
+        .try_collect()
+        .await
+        .unwrap();
+
+    let rows: Vec<_> = annotations
+        .iter()
+        .map(|a| (a.sample_id(), a.label().cloned()))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (Some(SampleID::from(1u64)), Some("cat".to_string())),
+            (Some(SampleID::from(1u64)), Some("dog".to_string())),
+            (Some(SampleID::from(2u64)), None),
+            (Some(SampleID::from(3u64)), Some("dog".to_string())),
+        ]
+    );
+
+    let cursors: Vec<_> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter_map(|r| {
+            let body: serde_json::Value = serde_json::from_slice(&r.body).ok()?;
+            (body["method"] == "samples.list").then(|| body["params"]["continue_token"].clone())
+        })
+        .collect();
+    assert_eq!(cursors, [json!(null), json!("page-2")]);
+}
+
+#[tokio::test]
+async fn annotations_stream_matches_annotations() {
+    use futures::TryStreamExt as _;
+
+    let server = MockServer::start().await;
+    mount_paged_annotation_set(
+        &server,
+        json!({
+            "samples": [
+                {
+                    "id": 1,
+                    "image_name": "a.jpg",
+                    "annotations": [
+                        { "label_name": "cat", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                        { "label_name": "dog", "x": 0.5, "y": 0.5, "w": 0.2, "h": 0.2 },
+                    ],
+                },
+                { "id": 2, "image_name": "b.jpg" },
+            ],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{
+                "id": 3,
+                "image_name": "c.jpg",
+                "annotations": [
+                    { "label_name": "dog", "x": 0.3, "y": 0.3, "w": 0.2, "h": 0.2 },
+                ],
+            }],
+            "continue_token": null
+        }))),
+    )
+    .await;
+
+    let client = client_for(&server.uri());
+    let rows = |annotations: &[edgefirst_client::Annotation]| {
+        annotations
+            .iter()
+            .map(|a| {
+                (
+                    a.sample_id(),
+                    a.name().cloned(),
+                    a.label().cloned(),
+                    a.label_index(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let listed = client
+        .annotations(AnnotationSetID::from(7u64), &[], &[], None, None, &[])
+        .await
+        .unwrap();
+    let streamed: Vec<_> = client
+        .annotations_stream(AnnotationSetID::from(7u64), &[], &[], None, None)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(rows(&streamed), rows(&listed));
+    assert_eq!(
+        streamed.iter().map(|a| a.label_index()).collect::<Vec<_>>(),
+        [Some(0), Some(1), None, Some(1)]
+    );
+}
+
+#[tokio::test]
+async fn annotations_stream_reports_rejected_cursor() {
+    use futures::StreamExt as _;
+
+    let server = MockServer::start().await;
+    mount_paged_annotation_set(
+        &server,
+        json!({
+            "samples": [{ "id": 1, "image_name": "a.jpg" }],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_error(3, "continue_token expired")),
+    )
+    .await;
+
+    let client = client_for(&server.uri());
+    let results: Vec<_> = client
+        .annotations_stream(AnnotationSetID::from(7u64), &[], &[], None, None)
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(
+        matches!(&results[1], Err(Error::InvalidCursor(msg)) if msg == "continue_token expired"),
+        "{:?}",
+        results[1]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::dataset_diff`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn dataset_diff_buckets_partially_overlapping_datasets() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(&server)
+        .await;
+
+    let datasets = [
+        (
+            1u64,
+            json!([
+                { "id": 1, "uuid": "u-shared", "image_name": "shared.jpg" },
+                { "id": 2, "uuid": "u-a", "image_name": "a.jpg" },
+                { "id": 3, "image_name": "legacy.jpg" },
+            ]),
+        ),
+        (
+            2u64,
+            json!([
+                { "id": 11, "uuid": "u-shared", "image_name": "shared-copy.jpg" },
+                { "id": 12, "image_name": "legacy.jpg" },
+                { "id": 13, "uuid": "u-b", "image_name": "b.jpg" },
+            ]),
+        ),
+    ];
+    for (dataset_id, samples) in datasets {
+        let total = samples.as_array().unwrap().len();
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({
+                "method": "samples.count",
+                "params": { "dataset_id": dataset_id }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": total }))),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({
+                "method": "samples.list",
+                "params": { "dataset_id": dataset_id }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+                "samples": samples,
+                "continue_token": null
+            }))))
+            .mount(&server)
+            .await;
+    }
+
+    let diff = client_for(&server.uri())
+        .dataset_diff(DatasetID::from(1u64), DatasetID::from(2u64))
+        .await
+        .expect("dataset_diff via mock");
+
+    let ids = |samples: &[edgefirst_client::Sample]| -> Vec<SampleID> {
+        samples.iter().map(|s| s.id().unwrap()).collect()
+    };
+    assert_eq!(ids(&diff.only_a), [SampleID::from(2u64)]);
+    assert_eq!(ids(&diff.only_b), [SampleID::from(13u64)]);
+    let common: Vec<_> = diff
+        .common
+        .iter()
+        .map(|(a, b)| (a.id().unwrap(), b.id().unwrap()))
+        .collect();
+    assert_eq!(
+        common,
+        [
+            (SampleID::from(1u64), SampleID::from(11u64)),
+            (SampleID::from(3u64), SampleID::from(12u64)),
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::deleted_datasets` / `Client::restore_dataset`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn deleted_datasets_lists_and_restores_soft_deleted_dataset() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "dataset.list_deleted",
+            "params": { "project_id": 1 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([{
+            "id": 5, "project_id": 1, "name": "oops", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+        }]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "dataset.restore",
+            "params": { "id": 5 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let deleted = client
+        .deleted_datasets(Some(edgefirst_client::ProjectID::from(1u64)))
+        .await
+        .expect("dataset.list_deleted via mock");
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].id(), DatasetID::from(5u64));
+    assert_eq!(deleted[0].name(), "oops");
+
+    client
+        .restore_dataset(deleted[0].id())
+        .await
+        .expect("dataset.restore via mock");
+}
+
+#[tokio::test]
+async fn restore_hard_deleted_dataset_is_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("dataset.restore"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_error(101, "dataset not found in DB")),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client_for(&server.uri())
+        .restore_dataset(DatasetID::from(6u64))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::NotFound(id) if id == "ds-6"),
+        "{err:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// attach_sample_files (samples.add_files)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn attach_sample_files_links_and_uploads_lidar_to_image_only_sample() {
+    let server = MockServer::start().await;
+    let upload_url = format!("{}/s3/sample-uuid/scan.pcd", server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.add_files",
+            "params": {
+                "sample_id": 7,
+                "presigned_urls": true,
+                "files": { "lidar.pcd": "scan.pcd" }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "uuid": "sample-uuid",
+            "urls": [{
+                "filename": "scan.pcd",
+                "key": "sample-uuid/scan.pcd",
+                "url": upload_url,
+            }]
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/s3/sample-uuid/scan.pcd"))
+        .and(body_bytes(b"# .PCD v0.7".to_vec()))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let scan = dir.path().join("scan.pcd");
+    tokio::fs::write(&scan, b"# .PCD v0.7").await.unwrap();
+
+    let client = client_for(&server.uri());
+    let result = client
+        .attach_sample_files(
+            SampleID::from(7u64),
+            vec![SampleFile::with_filename(
+                "lidar.pcd".to_string(),
+                scan.to_string_lossy().into_owned(),
+            )],
+            None,
+        )
+        .await
+        .expect("attach_sample_files via mock");
+    assert_eq!(result.uuid, "sample-uuid");
+    assert_eq!(result.urls.len(), 1);
+}
+
+#[tokio::test]
+async fn attach_sample_files_rejects_unsupported_types_without_any_rpc() {
+    // No mocks mounted: any request would fail the call with a 404.
+    let server = MockServer::start().await;
+    let client = client_for(&server.uri());
+
+    for file_type in ["image", "all", "thermal.raw"] {
+        let err = client
+            .attach_sample_files(
+                SampleID::from(7u64),
+                vec![SampleFile::with_bytes(
+                    file_type.to_string(),
+                    "blob.bin".to_string(),
+                    vec![0u8; 4],
+                )],
+                None,
+            )
+            .await
+            .expect_err("unsupported file type should be rejected");
+        assert!(
+            matches!(&err, Error::InvalidFileType(t) if t == file_type),
+            "expected InvalidFileType({file_type}), got {err:?}"
+        );
+    }
+
+    let err = client
+        .attach_sample_files(
+            SampleID::from(7u64),
+            vec![SampleFile::with_filename(
+                "radar.pcd".to_string(),
+                "/does/not/exist.pcd".to_string(),
+            )],
+            None,
+        )
+        .await
+        .expect_err("missing local file should be rejected");
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn populate_samples_reports_per_sample_outcomes_when_a_file_is_missing() {
+    let server = MockServer::start().await;
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("gone.jpg").to_string_lossy().into_owned();
+
+    let mut samples = Vec::new();
+    for (uuid, filename) in [("uuid-a", "a.jpg"), ("uuid-b", "b.jpg"), ("uuid-c", "")] {
+        let local = if filename.is_empty() {
+            missing.clone()
+        } else {
+            let path = dir.path().join(filename);
+            tokio::fs::write(&path, uuid.as_bytes()).await.unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let mut sample = edgefirst_client::Sample::new();
+        sample.uuid = Some(uuid.to_string());
+        sample.image_name = Some(format!("{uuid}.jpg"));
+        sample.files = vec![SampleFile::with_filename("image".to_string(), local)];
+        samples.push(sample);
+    }
+
+    let url = |uuid: &str, filename: &str| {
+        json!({
+            "filename": filename,
+            "key": format!("{uuid}/{filename}"),
+            "url": format!("{}/s3/{uuid}", server.uri()),
+        })
+    };
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.populate2",
+            "params": { "dataset_id": 1, "presigned_urls": true }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "uuid": "uuid-a", "urls": [url("uuid-a", "a.jpg")] },
+            { "uuid": "uuid-b", "urls": [url("uuid-b", "b.jpg")] },
+            { "uuid": "uuid-c", "urls": [url("uuid-c", &missing)] },
+        ]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    for uuid in ["uuid-a", "uuid-b"] {
+        Mock::given(method("PUT"))
+            .and(path(format!("/s3/{uuid}")))
+            .and(body_bytes(uuid.as_bytes().to_vec()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let report = client
+        .populate_samples(DatasetID::from(1u64), None, samples, None, None)
+        .await
+        .expect("populate_samples via mock");
+
+    assert!(!report.is_complete());
+    let succeeded: Vec<_> = report.succeeded.iter().map(|r| r.uuid.as_str()).collect();
+    assert_eq!(succeeded, ["uuid-a", "uuid-b"]);
+    assert_eq!(report.failed.len(), 1);
+    let failure = &report.failed[0];
+    assert_eq!(failure.uuid, "uuid-c");
+    assert_eq!(failure.image_name.as_deref(), Some("uuid-c.jpg"));
+    assert!(
+        failure.message.contains("gone.jpg"),
+        "got {}",
+        failure.message
+    );
+
+    let err = report.into_result().expect_err("partial failure");
+    assert!(
+        matches!(
+            err,
+            Error::PopulateFailed {
+                failed: 1,
+                total: 3,
+                ..
+            }
+        ),
+        "got {err:?}"
+    );
+}
+
+/// Mounts a `samples.populate2` answer for one sample whose `a.jpg` upload
+/// URL is `/s3/expired`, and a `samples.presign` answer pointing `a.jpg` at
+/// `/s3/fresh` when asked for the rejected URL's key.
+async fn mount_expiring_upload(server: &MockServer) {
+    let url = |path: &str| {
+        json!({
+            "filename": "a.jpg",
+            "key": "uuid-a/a.jpg",
+            "url": format!("{}/s3/{path}", server.uri()),
+        })
+    };
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.populate2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "uuid": "uuid-a", "urls": [url("expired")] },
+        ]))))
+        .expect(1)
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.presign",
+            "params": {
+                "dataset_id": 1,
+                "files": [{ "filename": "a.jpg", "type": "image", "key": "uuid-a/a.jpg" }]
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([url("fresh")]))))
+        .expect(1)
+        .mount(server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/expired"))
+        .respond_with(ResponseTemplate::new(403).set_body_string("Request has expired"))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+fn expiring_upload_sample() -> edgefirst_client::Sample {
+    let mut sample = edgefirst_client::Sample::new();
+    sample.uuid = Some("uuid-a".to_string());
+    sample.image_name = Some("a.jpg".to_string());
+    sample.files = vec![SampleFile::with_bytes(
+        "image".to_string(),
+        "a.jpg".to_string(),
+        b"jpeg".to_vec(),
+    )];
+    sample
+}
+
+#[tokio::test]
+async fn populate_samples_re_presigns_an_expired_upload_url() {
+    let server = MockServer::start().await;
+    mount_expiring_upload(&server).await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/fresh"))
+        .and(body_bytes(b"jpeg".to_vec()))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = client_for(&server.uri())
+        .populate_samples(
+            DatasetID::from(1u64),
+            None,
+            vec![expiring_upload_sample()],
+            None,
+            None,
+        )
+        .await
+        .expect("populate_samples via mock");
+
+    assert!(report.is_complete(), "failed: {:?}", report.failed);
+    assert_eq!(report.succeeded[0].uuid, "uuid-a");
+}
+
+#[tokio::test]
+async fn populate_samples_reports_upload_url_expired_when_re_presign_is_rejected() {
+    let server = MockServer::start().await;
+    mount_expiring_upload(&server).await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/fresh"))
+        .respond_with(ResponseTemplate::new(403))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let report = client_for(&server.uri())
+        .populate_samples(
+            DatasetID::from(1u64),
+            None,
+            vec![expiring_upload_sample()],
+            None,
+            None,
+        )
+        .await
+        .expect("populate_samples via mock");
+
+    assert_eq!(report.failed.len(), 1);
+    let expected = Error::UploadUrlExpired("a.jpg".to_string()).to_string();
+    assert!(
+        report.failed[0].message.contains(&expected),
+        "got {}",
+        report.failed[0].message
+    );
+}
+
+#[tokio::test]
+async fn populate_samples_rejects_a_re_presigned_url_for_another_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.populate2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([{
+            "uuid": "uuid-a",
+            "urls": [{
+                "filename": "a.jpg",
+                "key": "uuid-a/a.jpg",
+                "url": format!("{}/s3/expired", server.uri()),
+            }],
+        }]))))
+        .mount(&server)
+        .await;
+    // The fresh URL belongs to a different sample's key.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.presign"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([{
+            "filename": "a.jpg",
+            "key": "uuid-b/a.jpg",
+            "url": format!("{}/s3/fresh", server.uri()),
+        }]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/expired"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/fresh"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let report = client_for(&server.uri())
+        .populate_samples(
+            DatasetID::from(1u64),
+            None,
+            vec![expiring_upload_sample()],
+            None,
+            None,
+        )
+        .await
+        .expect("populate_samples via mock");
+
+    assert_eq!(report.failed.len(), 1);
+    let expected = Error::UploadUrlExpired("a.jpg".to_string()).to_string();
+    assert!(
+        report.failed[0].message.contains(&expected),
+        "got {}",
+        report.failed[0].message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Listing order (`sort_by` / `descending`)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn projects_and_experiments_sort_by_name_and_created() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(rpc_method_body("project.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 2, "name": "beta", "description": "" },
+            { "id": 3, "name": "Alpha", "description": "" },
+            { "id": 1, "name": "gamma", "description": "" },
+        ]))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(rpc_method_body("trainer.list2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 20, "project_id": 1, "name": "run-b", "description": "" },
+            { "id": 10, "project_id": 1, "name": "run-c", "description": "" },
+            { "id": 30, "project_id": 1, "name": "run-a", "description": "" },
+        ]))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let names = |projects: Vec<edgefirst_client::Project>| {
+        projects
+            .iter()
+            .map(|p| p.name().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(client.projects(None, None, false).await.unwrap()),
+        ["beta", "Alpha", "gamma"],
+        "no key keeps server order"
+    );
+    assert_eq!(
+        names(
+            client
+                .projects(None, Some(SortKey::Name), false)
+                .await
+                .unwrap()
+        ),
+        ["Alpha", "beta", "gamma"]
+    );
+    assert_eq!(
+        names(
+            client
+                .projects(None, Some(SortKey::Created), true)
+                .await
+                .unwrap()
+        ),
+        ["Alpha", "beta", "gamma"]
+    );
+    assert_eq!(
+        names(
+            client
+                .projects(None, Some(SortKey::Created), false)
+                .await
+                .unwrap()
+        ),
+        ["gamma", "beta", "Alpha"]
+    );
+
+    let experiments = client
+        .experiments(Some(1u64.into()), None, Some(SortKey::Name), true)
+        .await
+        .unwrap();
+    let names: Vec<_> = experiments.iter().map(|e| e.name()).collect();
+    assert_eq!(names, ["run-c", "run-b", "run-a"]);
+    let experiments = client
+        .experiments(Some(1u64.into()), None, Some(SortKey::Created), false)
+        .await
+        .unwrap();
+    let names: Vec<_> = experiments.iter().map(|e| e.name()).collect();
+    assert_eq!(names, ["run-c", "run-b", "run-a"]);
+}
+
+#[tokio::test]
+async fn datasets_sort_by_created_timestamp_and_name() {
+    let server = MockServer::start().await;
+    let dataset = |id: u64, name: &str, created: &str| {
+        json!({
+            "id": id, "project_id": 1, "name": name, "description": "",
+            "cloud_key": "k", "createdAt": created
+        })
+    };
+    Mock::given(method("POST"))
+        .and(rpc_method_body("dataset.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            // IDs deliberately out of creation order: the timestamp wins.
+            dataset(5, "deer", "2026-03-01T00:00:00Z"),
+            dataset(9, "Cats", "2026-01-01T00:00:00Z"),
+            dataset(1, "birds", "2026-02-01T00:00:00Z"),
+        ]))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let list = |sort_by, descending| {
+        let client = client.clone();
+        async move {
+            client
+                .datasets(Some(1u64.into()), None, sort_by, descending)
+                .await
+                .unwrap()
+                .iter()
+                .map(|d| d.name().to_owned())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(
+        list(Some(SortKey::Created), false).await,
+        ["Cats", "birds", "deer"]
+    );
+    assert_eq!(
+        list(Some(SortKey::Created), true).await,
+        ["deer", "birds", "Cats"]
+    );
+    assert_eq!(
+        list(Some(SortKey::Name), false).await,
+        ["birds", "Cats", "deer"]
+    );
+
+    // With a name filter, the key replaces match-quality ordering.
+    let filtered = client
+        .datasets(Some(1u64.into()), Some("e"), Some(SortKey::Name), false)
+        .await
+        .unwrap();
+    let names: Vec<_> = filtered.iter().map(|d| d.name()).collect();
+    assert_eq!(names, ["deer"]);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::with_default_project`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn project_scoped_listings_fall_back_to_default_project() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, params: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({"method": name, "params": params})))
+    };
+    rpc("dataset.list", json!({"project_id": 7}))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([{
+            "id": 1, "project_id": 7, "name": "default", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+        }]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    rpc("dataset.list", json!({"project_id": 3}))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    rpc("validate.session.list", json!({"project_id": 7}))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri()).with_default_project(7u64.into());
+    assert_eq!(client.default_project(), Some(7u64.into()));
+
+    let datasets = client.datasets(None, None, None, false).await.unwrap();
+    assert_eq!(datasets.len(), 1);
+    assert_eq!(datasets[0].name(), "default");
+
+    // An explicit project overrides the default.
+    let datasets = client
+        .datasets(Some(3u64.into()), None, None, false)
+        .await
+        .unwrap();
+    assert!(datasets.is_empty());
+
+    assert!(client.validation_sessions(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn project_scoped_listing_without_default_is_invalid() {
+    let server = MockServer::start().await;
+    let client = client_for(&server.uri());
+    assert_eq!(client.default_project(), None);
+
+    let err = client.datasets(None, None, None, false).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+    assert!(matches!(
+        client.experiments(None, None, None, false).await,
+        Err(Error::InvalidParameters(_))
+    ));
+    let err = client.validation_sessions(None).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+
+    assert_eq!(request_count(&server).await, 0);
+}
+
+// ---------------------------------------------------------------------------
+// Reauthentication with cached credentials
+// ---------------------------------------------------------------------------
+
+fn jwt_with_exp(exp: i64) -> String {
+    let header = b64(b"{\"alg\":\"none\",\"typ\":\"JWT\"}");
+    let payload = b64(format!("{{\"server\":\"test\",\"exp\":{exp}}}").as_bytes());
+    let signature = b64(b"signature");
+    format!("{header}.{payload}.{signature}")
+}
+
+#[tokio::test]
+async fn expired_token_reauthenticates_with_cached_credentials() {
+    let server = MockServer::start().await;
+    let expired = jwt_with_exp(1_000_000_000);
+    let fresh = fake_jwt();
+
+    // The first login hands out a token that has already expired; the second
+    // one, made by the client on its own, returns a valid token.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": expired }))),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "auth.login",
+            "params": { "username": "u", "password": "p" }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": fresh }))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.refresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(401, "expired")))
+        .expect(0)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .and(wiremock::matchers::header(
+            "Authorization",
+            format!("Bearer {fresh}").as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new()
+        .unwrap()
+        .with_memory_storage()
+        .with_credential_cache()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_login("u", "p")
+        .await
+        .expect("initial login");
+    assert!(!client.token_valid_offline().await);
+
+    client
+        .projects(None, None, false)
+        .await
+        .expect("request should log in again and succeed");
+    assert_eq!(client.token().await, fresh);
+}
+
+#[tokio::test]
+async fn concurrent_requests_with_expired_token_log_in_once() {
+    let server = MockServer::start().await;
+    let expired = jwt_with_exp(1_000_000_000);
+    let fresh = fake_jwt();
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": expired }))),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Slow enough that every request finds the token expired before the
+    // first re-login completes.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.login"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(json!({ "token": fresh })))
+                .set_delay(std::time::Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .and(wiremock::matchers::header(
+            "Authorization",
+            format!("Bearer {fresh}").as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(8)
+        .mount(&server)
+        .await;
+
+    let client = Client::new()
+        .unwrap()
+        .with_memory_storage()
+        .with_credential_cache()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_login("u", "p")
+        .await
+        .expect("initial login");
+    assert!(!client.token_valid_offline().await);
+
+    let results =
+        futures::future::join_all((0..8).map(|_| client.projects(None, None, false))).await;
+    for result in results {
+        result.expect("every request should succeed with the new token");
+    }
+    assert_eq!(client.token().await, fresh);
+}
+
+#[tokio::test]
+async fn renew_token_returns_expiry_and_stores_new_token() {
+    use edgefirst_client::{FileTokenStorage, TokenStorage};
+
+    let server = MockServer::start().await;
+    let renewed = format!(
+        "{}.{}.{}",
+        b64(b"{\"alg\":\"none\",\"typ\":\"JWT\"}"),
+        b64(b"{\"server\":\"test\",\"username\":\"tester\",\"exp\":2100000000}"),
+        b64(b"signature")
+    );
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "auth.refresh",
+            "params": { "username": "tester" }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": renewed }))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let storage = std::sync::Arc::new(FileTokenStorage::with_path(dir.path().join("token")));
+    let client = Client::new()
+        .unwrap()
+        .with_storage(storage.clone())
+        .with_token(&fake_jwt())
+        .unwrap()
+        .with_url(&server.uri())
+        .unwrap();
+
+    let expires = client.renew_token().await.expect("renew_token via mock");
+    assert_eq!(expires.timestamp(), 2_100_000_000);
+    assert_eq!(client.token().await, renewed);
+    assert_eq!(storage.load().unwrap(), Some(renewed));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn server_clock_skew_from_date_header_drives_token_renewal() {
+    let server = MockServer::start().await;
+    let jwt = |exp: i64| {
+        format!(
+            "{}.{}.{}",
+            b64(b"{\"alg\":\"none\",\"typ\":\"JWT\"}"),
+            b64(
+                format!("{{\"server\":\"test\",\"username\":\"tester\",\"exp\":{exp}}}").as_bytes()
+            ),
+            b64(b"signature")
+        )
+    };
+    // Locally the token has 90 minutes left, so it is not renewed yet; the
+    // server's clock runs two hours ahead, where it has already expired.
+    let now = chrono::Utc::now();
+    let token = jwt((now + chrono::Duration::minutes(90)).timestamp());
+    let server_date = (now + chrono::Duration::hours(2))
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string();
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Date", server_date.as_str())
+                .set_body_json(rpc_result(json!([]))),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.refresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(
+            json!({ "token": jwt((now + chrono::Duration::hours(4)).timestamp()) }),
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new()
+        .unwrap()
+        .with_memory_storage()
+        .with_token(&token)
+        .unwrap()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_server_clock(true);
+    assert!(client.token_valid_offline().await);
+
+    client.projects(None, None, false).await.unwrap();
+    let skew = client.clock_skew();
+    assert!(
+        (skew - chrono::Duration::hours(2)).num_seconds().abs() <= 2,
+        "measured skew {skew}"
+    );
+    assert!(!client.token_valid_offline().await);
+
+    // The next request sees the token as expired on the server's clock and
+    // renews it first.
+    client.projects(None, None, false).await.unwrap();
+    assert!(client.token_valid_offline().await);
+}
+
+#[tokio::test]
+async fn reauthenticate_requires_the_credential_cache() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": fake_jwt() }))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new()
+        .unwrap()
+        .with_memory_storage()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_login("u", "p")
+        .await
+        .expect("login");
+
+    let err = client
+        .reauthenticate()
+        .await
+        .expect_err("credentials were not cached");
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+}
+
+#[tokio::test]
+async fn logout_and_with_token_drop_cached_credentials() {
+    let server = MockServer::start().await;
+    // Only the initial login may reach the server.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("auth.login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": fake_jwt() }))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = Client::new()
+        .unwrap()
+        .with_memory_storage()
+        .with_credential_cache()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_login("u", "p")
+        .await
+        .expect("login");
+
+    let switched = client.with_token(&fake_jwt()).unwrap();
+    let err = switched
+        .reauthenticate()
+        .await
+        .expect_err("with_token must drop the cached credentials");
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+
+    let clone = client.clone();
+    client.logout().await.unwrap();
+    let err = clone
+        .reauthenticate()
+        .await
+        .expect_err("logout must drop the cached credentials");
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+}
+
+// ---------------------------------------------------------------------------
+// Group-filtered COCO export
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "polars")]
+#[tokio::test]
+async fn export_coco_group_filter_excludes_other_groups() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("dataset.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 1, "project_id": 1, "name": "pets", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "normalized"
+        }))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 1, "index": 0, "name": "cat" },
+            { "id": 2, "index": 1, "name": "dog" },
+        ]))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 2 }))))
+        .mount(&server)
+        .await;
+    // The listing only honours the filter when `group_names` is sent; the val
+    // sample is returned regardless to check the client drops it too.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.list",
+            "params": { "group_names": ["train"] }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [
+                {
+                    "id": 10,
+                    "image_name": "a.jpg",
+                    "group_name": "train",
+                    "width": 100,
+                    "height": 100,
+                    "annotations": [
+                        { "label_name": "cat", "group_name": "train",
+                          "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                    ],
+                },
+                {
+                    "id": 11,
+                    "image_name": "b.jpg",
+                    "group_name": "val",
+                    "width": 100,
+                    "height": 100,
+                    "annotations": [
+                        { "label_name": "dog", "group_name": "val",
+                          "x": 0.3, "y": 0.3, "w": 0.2, "h": 0.2 },
+                    ],
+                },
+            ],
+            "continue_token": null
+        }))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("instances.json");
+    let options = edgefirst_client::coco::CocoExportOptions {
+        groups: vec!["train".to_string()],
+        ..Default::default()
+    };
+    let count = edgefirst_client::coco::export_studio_to_coco(
+        &client,
+        DatasetID::from(1u64),
+        edgefirst_client::AnnotationSetID::from(2u64),
+        &output,
+        &options,
+        None,
+    )
+    .await
+    .expect("export should succeed");
+    assert_eq!(count, 1);
+
+    let coco: serde_json::Value = serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+    let images: Vec<&str> = coco["images"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|image| image["file_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(images, vec!["a.jpg"]);
+    let categories: Vec<&str> = coco["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|category| category["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(categories, vec!["cat"]);
+}
+
+// ---------------------------------------------------------------------------
+// Sample counts
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn quick_count_is_a_single_count_request_matching_samples_count() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.count",
+            "params": { "dataset_id": 1, "group_names": ["train"] }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 42 }))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let count = client
+        .quick_count(DatasetID::from(1u64), Some("train"))
+        .await
+        .expect("quick_count should succeed");
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1, "quick_count must not page or list");
+
+    let full = client
+        .samples_count(
+            DatasetID::from(1u64),
+            None,
+            &[],
+            &["train".to_string()],
+            &[],
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, full.total);
+    assert_eq!(count, 42);
+}
+
+#[tokio::test]
+async fn samples_count_by_group_counts_every_dataset_group() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "groups.list",
+            "params": { "dataset_id": 1 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 1, "name": "train" },
+            { "id": 2, "name": "val" },
+            { "id": 3, "name": "holdout" }
+        ]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    for (group, total) in [("train", 80), ("val", 15), ("holdout", 5)] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({
+                "method": "samples.count",
+                "params": { "dataset_id": 1, "group_names": [group] }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": total }))),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 100 }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let count = client_for(&server.uri())
+        .samples_count_by_group(DatasetID::from(1u64), None, &[], &[], &[], None)
+        .await
+        .expect("samples_count_by_group via mock");
+
+    assert_eq!(count.total, 100);
+    assert_eq!(count.group_count("train"), 80);
+    assert_eq!(count.group_count("val"), 15);
+    assert_eq!(count.group_count("holdout"), 5);
+    assert_eq!(count.group_count("test"), 0);
+    assert_eq!(count.groups.len(), 3);
+}
+
+#[tokio::test]
+async fn samples_count_by_label_counts_samples_not_instances() {
+    let server = MockServer::start().await;
+    let samples = json!([
+        {
+            "id": 1, "image_name": "a.jpg",
+            "annotations": [
+                { "label_name": "deer", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                { "label_name": "deer", "x": 0.5, "y": 0.5, "w": 0.2, "h": 0.2 },
+                { "label_name": "elk", "x": 0.3, "y": 0.3, "w": 0.1, "h": 0.1 },
+            ],
+        },
+        {
+            "id": 2, "image_name": "b.jpg",
+            "annotations": [
+                { "label_name": "deer", "x": 0.2, "y": 0.2, "w": 0.3, "h": 0.3 },
+            ],
+        },
+        { "id": 3, "image_name": "c.jpg", "annotations": [] },
+    ]);
+    for (name, result) in [
+        (
+            "label.list",
+            json!([
+                { "id": 10, "index": 0, "name": "deer" },
+                { "id": 11, "index": 1, "name": "elk" },
+            ]),
+        ),
+        ("samples.count", json!({ "total": 3 })),
+        (
+            "samples.list",
+            json!({ "samples": samples, "continue_token": null }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let counts = client
+        .samples_count_by_label(DatasetID::from(1u64), AnnotationSetID::from(2u64), None)
+        .await
+        .expect("samples_count_by_label via mock");
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts["deer"], 2);
+    assert_eq!(counts["elk"], 1);
+
+    // Instance counts see both boxes on a.jpg.
+    let listed = client
+        .samples(
+            DatasetID::from(1u64),
+            None,
+            SamplesQuery {
+                annotation_set_id: Some(AnnotationSetID::from(2u64)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let deer_instances: usize = listed
+        .iter()
+        .map(|s| s.annotations_by_label().get("deer").map_or(0, Vec::len))
+        .sum();
+    assert_eq!(deer_instances, 3);
+}
+
+#[tokio::test]
+async fn validate_dataset_reports_each_issue_kind() {
+    let server = MockServer::start().await;
+    let samples = json!([
+        {
+            "id": 1, "image_name": "ok.jpg", "group": "train", "width": 640, "height": 480,
+            "annotations": [
+                { "label_name": "deer", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+            ],
+        },
+        {
+            "id": 2, "image_name": "flat.jpg", "group": "train", "width": 640,
+            "annotations": [
+                { "label_name": "deer", "x": 0.2, "y": 0.2, "w": 0.3, "h": 0.3 },
+            ],
+        },
+        {
+            "id": 3, "image_name": "empty.jpg", "group": "val", "width": 640, "height": 480,
+            "annotations": [],
+        },
+    ]);
+    for (name, result) in [
+        (
+            "label.list",
+            json!([
+                { "id": 10, "index": 0, "name": "deer" },
+                { "id": 11, "index": 1, "name": "elk" },
+            ]),
+        ),
+        (
+            "groups.list",
+            json!([
+                { "id": 1, "name": "train" },
+                { "id": 2, "name": "val" },
+                { "id": 3, "name": "test" },
+            ]),
+        ),
+        ("samples.count", json!({ "total": 3 })),
+        (
+            "samples.list",
+            json!({ "samples": samples, "continue_token": null }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let issues = client
+        .validate_dataset(DatasetID::from(1u64), AnnotationSetID::from(2u64))
+        .await
+        .expect("validate_dataset via mock");
+    let found: Vec<_> = issues
+        .iter()
+        .map(|issue| (issue.severity, issue.kind, issue.subject.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                Severity::Error,
+                ValidationIssueKind::MissingDimensions,
+                "flat"
+            ),
+            (
+                Severity::Warning,
+                ValidationIssueKind::UnannotatedSample,
+                "empty"
+            ),
+            (Severity::Warning, ValidationIssueKind::UnusedLabel, "elk"),
+            (Severity::Warning, ValidationIssueKind::EmptyGroup, "test"),
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// download_dataset file naming
+// ---------------------------------------------------------------------------
+
+/// Mounts a single-sample dataset whose image and sensor files are served by
+/// the mock server, one GET route per file type.
+async fn mount_download_sample(server: &MockServer, image_name: &str, image_bytes: Vec<u8>) {
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 1 }))))
+        .mount(server)
+        .await;
+
+    let file = |name: &str| format!("{}/files/{name}", server.uri());
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{
+                "id": 10,
+                "image_name": image_name,
+                "image_url": file("image"),
+                "group_name": "train",
+                "sensors": [
+                    { "lidar.pcd": file("lidar_pcd") },
+                    { "lidar.depth": file("lidar_depth") },
+                    { "lidar.reflect": file("lidar_reflect") },
+                    { "radar.pcd": file("radar_pcd") },
+                    { "radar.png": file("radar_cube") },
+                ],
+            }],
+            "continue_token": null
+        }))))
+        .mount(server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(image_bytes))
+        .mount(server)
+        .await;
+    for name in [
+        "lidar_pcd",
+        "lidar_depth",
+        "lidar_reflect",
+        "radar_pcd",
+        "radar_cube",
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{name}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(name.as_bytes().to_vec()))
+            .mount(server)
+            .await;
+    }
+}
+
+async fn download_all_types(server: &MockServer) -> Vec<String> {
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    client
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &edgefirst_client::FileType::expand_types(&[edgefirst_client::FileType::All]),
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+        .expect("download should succeed");
+
+    let mut names: Vec<String> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn download_dataset_names_each_file_type_with_its_extension() {
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame.camera.png", png_1x1()).await;
+
+    assert_eq!(
+        download_all_types(&server).await,
+        vec![
+            "frame.camera.png",
+            "frame.lidar.jpg",
+            "frame.lidar.pcd",
+            "frame.lidar.png",
+            "frame.radar.pcd",
+            "frame.radar.png",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn download_dataset_reports_phases_in_order() {
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame.camera.png", png_1x1()).await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+    client
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            Some(tx),
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+        .expect("download should succeed");
+
+    let mut events = Vec::new();
+    while let Some(progress) = rx.recv().await {
+        events.push(progress);
+    }
+    let mut phases: Vec<ProgressPhase> = events.iter().map(|p| p.phase).collect();
+    phases.dedup();
+    assert_eq!(
+        phases,
+        [
+            ProgressPhase::Enumerating,
+            ProgressPhase::Downloading,
+            ProgressPhase::Finalizing
+        ]
+    );
+    let last = events.last().unwrap();
+    assert_eq!((last.current, last.total), (1, 1));
+}
+
+#[tokio::test]
+async fn download_dataset_with_file_events_reports_each_file() {
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame.camera.png", png_1x1()).await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+    client
+        .download_dataset_with_file_events(
+            DatasetID::from(1u64),
+            &[],
+            &edgefirst_client::FileType::expand_types(&[edgefirst_client::FileType::All]),
+            dir.path().to_path_buf(),
+            false,
+            None,
+            Some(tx),
+            None,
+            DownloadOptions {
+                verify_checksums: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("download should succeed");
+
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    let mut started = Vec::new();
+    let mut completed = Vec::new();
+    for event in events {
+        match event {
+            FileEvent::Started { name } => started.push(name),
+            FileEvent::Completed { name, bytes } => {
+                assert!(started.contains(&name), "{name} completed before start");
+                completed.push((name, bytes));
+            }
+        }
+    }
+    started.sort();
+    completed.sort();
+    assert_eq!(
+        started,
+        [
+            "frame.camera.png",
+            "frame.lidar.jpg",
+            "frame.lidar.pcd",
+            "frame.lidar.png",
+            "frame.radar.pcd",
+            "frame.radar.png",
+        ]
+    );
+    assert_eq!(
+        completed,
+        [
+            ("frame.camera.png".to_string(), png_1x1().len() as u64),
+            ("frame.lidar.jpg".to_string(), "lidar_reflect".len() as u64),
+            ("frame.lidar.pcd".to_string(), "lidar_pcd".len() as u64),
+            ("frame.lidar.png".to_string(), "lidar_depth".len() as u64),
+            ("frame.radar.pcd".to_string(), "radar_pcd".len() as u64),
+            ("frame.radar.png".to_string(), "radar_cube".len() as u64),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn download_dataset_skips_existing_files_matching_in_size() {
+    let server = MockServer::start().await;
+    let png = png_1x1();
+    mount_download_sample(&server, "frame.camera.png", png.clone()).await;
+    // Storage answering the size probe with a partial response.
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .and(header("range", "bytes=0-0"))
+        .respond_with(
+            ResponseTemplate::new(206)
+                .insert_header("content-range", format!("bytes 0-0/{}", png.len()).as_str())
+                .set_body_bytes(&png[..1]),
+        )
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let placeholder = vec![0u8; png.len()];
+    std::fs::write(dir.path().join("frame.camera.png"), &placeholder).unwrap();
+    std::fs::write(dir.path().join("frame.lidar.pcd"), b"stale").unwrap();
+
+    let client = client_for(&server.uri());
+    client
+        .download_dataset_with_file_events(
+            DatasetID::from(1u64),
+            &[],
+            &[
+                edgefirst_client::FileType::Image,
+                edgefirst_client::FileType::LidarPcd,
+            ],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            None,
+            DownloadOptions {
+                verify_checksums: true,
+                skip_existing_by_size: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("download should succeed");
+
+    // Same size as the stored image: kept without a full download.
+    assert_eq!(
+        std::fs::read(dir.path().join("frame.camera.png")).unwrap(),
+        placeholder
+    );
+    let requests = server.received_requests().await.unwrap();
+    assert!(
+        requests
+            .iter()
+            .filter(|r| r.url.path() == "/files/image")
+            .all(|r| r.headers.contains_key("range"))
+    );
+    // Different size: downloaded again.
+    assert_eq!(
+        std::fs::read(dir.path().join("frame.lidar.pcd")).unwrap(),
+        b"lidar_pcd"
+    );
+}
+
+/// Serves `/files/image` as `bytes` with the ETag `etag`, ahead of mocks
+/// mounted with a lower priority.
+async fn mount_image_version(server: &MockServer, bytes: Vec<u8>, etag: &str, priority: u8) {
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", etag)
+                .set_body_bytes(bytes),
+        )
+        .with_priority(priority)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn download_dataset_serves_repeat_downloads_from_cache() {
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame.camera.png", png_1x1()).await;
+    mount_image_version(&server, png_1x1(), "\"v1\"", 2).await;
+
+    let cache = tempfile::tempdir().unwrap();
+    let client = client_for(&server.uri())
+        .with_cache_dir(cache.path())
+        .unwrap();
+    // Full fetches of the image; the one-byte ETag probes carry a Range.
+    let file_fetches = async || {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| {
+                r.method.as_str() == "GET"
+                    && r.url.path() == "/files/image"
+                    && !r.headers.contains_key("range")
+            })
+            .count()
+    };
+    let download = async || {
+        let dir = tempfile::tempdir().unwrap();
+        client
+            .download_dataset(
+                DatasetID::from(1u64),
+                &[],
+                &[edgefirst_client::FileType::Image],
+                dir.path().to_path_buf(),
+                false,
+                None,
+                None,
+                DownloadOptions::default(),
+            )
+            .await
+            .expect("download should succeed");
+        std::fs::read(dir.path().join("frame.camera.png")).unwrap()
+    };
+
+    for _ in 0..2 {
+        assert_eq!(download().await, png_1x1());
+        assert_eq!(file_fetches().await, 1, "second download must not fetch");
+    }
+    assert_eq!(client.cache_size().await.unwrap(), png_1x1().len() as u64);
+
+    // The file is replaced under the same key: its new ETag misses.
+    let mut replaced = png_1x1();
+    replaced.extend_from_slice(b"trailer");
+    mount_image_version(&server, replaced.clone(), "\"v2\"", 1).await;
+    assert_eq!(download().await, replaced);
+    assert_eq!(file_fetches().await, 2, "a replaced file must be fetched");
+
+    client.clear_cache().await.unwrap();
+    assert_eq!(client.cache_size().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn download_dataset_adds_sniffed_extension_to_bare_image_names() {
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame", png_1x1()).await;
+
+    let names = download_all_types(&server).await;
+    assert!(names.contains(&"frame.png".to_string()), "got {names:?}");
+}
+
+#[tokio::test]
+async fn download_dataset_falls_back_when_image_format_is_unknown() {
+    // Bytes `infer` cannot identify used to panic the download task.
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame", b"not an image".to_vec()).await;
+
+    let names = download_all_types(&server).await;
+    assert!(names.contains(&"frame.jpg".to_string()), "got {names:?}");
+}
+
+#[tokio::test]
+async fn download_dataset_stops_when_cancelled() {
+    // The image transfer stalls so the token fires while it is in flight.
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(png_1x1())
+                .set_delay(std::time::Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+    mount_download_sample(&server, "frame.png", png_1x1()).await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let cancel = edgefirst_client::CancellationToken::new();
+    let trigger = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        trigger.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let err = client
+        .download_dataset_with_file_events(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            None,
+            DownloadOptions {
+                verify_checksums: true,
+                cancel: Some(cancel),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("cancelled download should fail");
+
+    assert!(matches!(err, Error::Cancelled), "got {err:?}");
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+/// Mounts `count` single-image samples whose files answer after decreasing
+/// delays, so later samples finish before earlier ones.
+async fn mount_staggered_samples(server: &MockServer, count: u64) {
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": count })),
+        (
+            "samples.list",
+            json!({
+                "samples": (0..count)
+                    .map(|i| json!({
+                        "id": i + 1,
+                        "image_name": format!("frame_{i}.png"),
+                        "image_url": format!("{}/files/{i}", server.uri()),
+                    }))
+                    .collect::<Vec<_>>(),
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(server)
+            .await;
+    }
+    for i in 0..count {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{i}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(png_1x1())
+                    .set_delay(std::time::Duration::from_millis(20 * (count - i))),
+            )
+            .mount(server)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn download_dataset_concurrency_keeps_progress_monotonic() {
+    let server = MockServer::start().await;
+    mount_staggered_samples(&server, 6).await;
+    let client = client_for(&server.uri());
+
+    // Zero is raised to a single task instead of never starting one.
+    for concurrency in [4, 0] {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+        client
+            .download_dataset(
+                DatasetID::from(1u64),
+                &[],
+                &[edgefirst_client::FileType::Image],
+                dir.path().to_path_buf(),
+                true,
+                Some(tx),
+                None,
+                DownloadOptions {
+                    concurrency: Some(concurrency),
+                    verify_checksums: true,
+                    timeout: Some(std::time::Duration::from_secs(10)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("download should succeed");
+
+        let mut downloaded = Vec::new();
+        while let Some(progress) = rx.recv().await {
+            if progress.phase == ProgressPhase::Downloading {
+                downloaded.push(progress.current);
+            }
+        }
+        assert_eq!(
+            downloaded,
+            [0, 1, 2, 3, 4, 5, 6],
+            "concurrency {concurrency}"
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 6);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Per-call timeout overrides
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn download_dataset_per_call_timeout_overrides_client_default() {
+    // Every RPC stalls far longer than the per-call deadline but well inside
+    // the client's own 30s request timeout.
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(json!([])))
+                .set_delay(std::time::Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let limit = std::time::Duration::from_millis(200);
+    let started = std::time::Instant::now();
+    let err = client
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions {
+                timeout: Some(limit),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect_err("stalled download should time out");
+    assert!(
+        matches!(err, Error::Timeout(d) if d == limit),
+        "got {err:?}"
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+}
+
+#[tokio::test]
+async fn populate_samples_per_call_timeout_overrides_client_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(json!([])))
+                .set_delay(std::time::Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let limit = std::time::Duration::from_millis(200);
+    let started = std::time::Instant::now();
+    let err = client
+        .populate_samples(
+            DatasetID::from(1u64),
+            None,
+            vec![edgefirst_client::Sample::new()],
+            None,
+            Some(limit),
+        )
+        .await
+        .expect_err("stalled populate should time out");
+    assert!(
+        matches!(err, Error::Timeout(d) if d == limit),
+        "got {err:?}"
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+}
+
+// ---------------------------------------------------------------------------
+// `Client::training_session_logs` / `Client::task_logs`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn training_session_logs_reads_logs_of_backing_task() {
+    let server = MockServer::start().await;
+
+    let mut session = training_session_json(0x111, "session", "");
+    session["docker_task"]["id"] = json!(0x222);
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("trainer.session.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(session)))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "task.logs",
+            "params": { "task_id": 0x222, "offset": 0 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "lines": ["epoch 1/2", "epoch 2/2"],
+            "finished": false
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let (logs, finished) = client
+        .training_session_logs(TrainingSessionID::from(0x111u64), false, None)
+        .await
+        .expect("task.logs via mock");
+    assert_eq!(logs, "epoch 1/2\nepoch 2/2\n");
+    assert!(!finished);
+}
+
+#[tokio::test]
+async fn task_logs_follow_polls_until_finished() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "task.logs",
+            "params": { "task_id": 0x222, "offset": 0 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "lines": ["starting"],
+            "finished": false
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "task.logs",
+            "params": { "task_id": 0x222, "offset": 1 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "lines": ["done"],
+            "finished": true
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let (logs, finished) = client
+        .task_logs(TaskID::from(0x222u64), true, None)
+        .await
+        .expect("followed task.logs via mock");
+    assert_eq!(logs, "starting\ndone\n");
+    assert!(finished);
+}
+
+#[tokio::test]
+async fn task_logs_follow_stops_at_the_timeout() {
+    let server = MockServer::start().await;
+
+    // The task logs one line, then never finishes and has no new lines to
+    // report.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "task.logs",
+            "params": { "task_id": 0x222, "offset": 0 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "lines": ["starting"],
+            "finished": false
+        }))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "task.logs",
+            "params": { "task_id": 0x222, "offset": 1 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "lines": [],
+            "finished": false
+        }))))
+        .mount(&server)
+        .await;
+
+    let limit = std::time::Duration::from_millis(300);
+    let started = std::time::Instant::now();
+    let (logs, finished) = client_for(&server.uri())
+        .task_logs(TaskID::from(0x222u64), true, Some(limit))
+        .await
+        .expect("the lines read before the timeout are returned");
+    assert_eq!(logs, "starting\n");
+    assert!(!finished);
+    assert!(started.elapsed() >= limit);
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_metrics_csv`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn export_metrics_csv_writes_metric_history() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("trainer.session.get"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(training_session_json(0x111, "session", ""))),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.session.metrics",
+            "params": { "trainer_session_id": 0x111 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "loss": [0.9, 0.5, 0.25],
+            "map": [
+                { "step": 10, "value": 0.4 },
+                { "step": 20, "value": 0.6 }
+            ],
+            "best_epoch": 2
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("reports").join("metrics.csv");
+    client_for(&server.uri())
+        .export_metrics_csv(TrainingSessionID::from(0x111u64), &path)
+        .await
+        .expect("export_metrics_csv via mock");
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "key,step,value\n\
+         best_epoch,,2\n\
+         loss,0,0.9\n\
+         loss,1,0.5\n\
+         loss,2,0.25\n\
+         map,10,0.4\n\
+         map,20,0.6\n"
+    );
+}
+
+#[tokio::test]
+async fn experiment_summary_picks_the_session_with_the_highest_metric() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.session.list",
+            "params": { "trainer_id": 7 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            training_session_json(1, "baseline", ""),
+            training_session_json(2, "tuned", ""),
+            training_session_json(3, "history", ""),
+            training_session_json(4, "pending", ""),
+        ]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    for (id, metrics) in [
+        (1, json!({ "map50": 0.41 })),
+        (2, json!({ "map50": 0.67, "loss": 0.1 })),
+        // A history contributes its last value, not its best.
+        (3, json!({ "map50": [0.2, 0.9, 0.55] })),
+        (4, json!({})),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({
+                "method": "trainer.session.metrics",
+                "params": { "trainer_session_id": id }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(metrics)))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let summary = client_for(&server.uri())
+        .experiment_summary(ExperimentID::from(7u64), "map50")
+        .await
+        .expect("experiment_summary via mock");
+
+    assert_eq!(summary.session_count, 4);
+    assert_eq!(summary.best_metric, Some(0.67));
+    let best = summary.best_session.expect("best session");
+    assert_eq!(best.id(), TrainingSessionID::from(2u64));
+    assert_eq!(best.name(), "tuned");
+}
+
+#[tokio::test]
+async fn experiment_summary_of_an_empty_experiment_has_no_best_session() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("trainer.session.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let summary = client_for(&server.uri())
+        .experiment_summary(ExperimentID::from(7u64), "map50")
+        .await
+        .expect("experiment_summary via mock");
+
+    assert_eq!(summary.session_count, 0);
+    assert!(summary.best_session.is_none());
+    assert_eq!(summary.best_metric, None);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::resolve`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn resolve_dispatches_on_id_prefix() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, params: serde_json::Value, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(
+                json!({ "method": name, "params": params }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .expect(1)
+    };
+
+    rpc(
+        "project.get",
+        json!({ "project_id": 1 }),
+        json!({ "id": 1, "name": "Wildlife", "description": "" }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "dataset.get",
+        json!({ "dataset_id": 0x123 }),
+        json!({
+            "id": 0x123, "project_id": 1, "name": "deer", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+        }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "annset.get",
+        json!({ "annotation_set_id": 0x456 }),
+        json!({ "id": 0x456, "dataset_id": 0x123, "name": "Default", "description": "" }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "trainer.session.get",
+        json!({ "trainer_session_id": 0x111 }),
+        training_session_json(0x111, "session", ""),
+    )
+    .mount(&server)
+    .await;
+
+    let client = client_for(&server.uri());
+    assert!(matches!(
+        client.resolve("p-1").await.expect("project"),
+        Entity::Project(p) if p.name() == "Wildlife"
+    ));
+    assert!(matches!(
+        client.resolve("ds-123").await.expect("dataset"),
+        Entity::Dataset(d) if d.id() == DatasetID::from(0x123u64)
+    ));
+    assert!(matches!(
+        client.resolve(" as-456 ").await.expect("annotation set"),
+        Entity::AnnotationSet(a) if a.id() == AnnotationSetID::from(0x456u64)
+    ));
+    assert!(matches!(
+        client.resolve("t-111").await.expect("training session"),
+        Entity::TrainingSession(t) if t.name() == "session"
+    ));
+}
+
+#[tokio::test]
+async fn resolve_rejects_unknown_and_malformed_ids() {
+    let server = MockServer::start().await;
+    let client = client_for(&server.uri());
+
+    for id in ["app-1", "im-1", "123", ""] {
+        assert!(
+            matches!(client.resolve(id).await, Err(Error::InvalidParameters(_))),
+            "{id:?} should be rejected"
+        );
+    }
+    assert!(matches!(
+        client.resolve("ds-zz").await,
+        Err(Error::ParseIntError(_))
+    ));
+    assert_eq!(request_count(&server).await, 0);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::with_retry_config`
+// ---------------------------------------------------------------------------
+
+/// Mounts an `/api` responder that always fails with `status` and returns
+/// a client using `config`.
+async fn retry_client(server: &MockServer, status: u16, config: RetryConfig) -> Client {
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .respond_with(ResponseTemplate::new(status))
+        .mount(server)
+        .await;
+    client_for(&server.uri())
+        .with_retry_config(config)
+        .expect("with_retry_config")
+}
+
+async fn request_count(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap_or_default().len()
+}
+
+#[tokio::test]
+async fn retry_config_never_retries_400() {
+    let server = MockServer::start().await;
+    let client = retry_client(&server, 400, RetryConfig::default().with_max_retries(2)).await;
+    client.projects(None, None, false).await.expect_err("400");
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn retry_config_retries_503_when_server_errors_enabled() {
+    let server = MockServer::start().await;
+    let config = RetryConfig::default()
+        .with_max_retries(1)
+        .with_backoff_on_5xx_only();
+    let client = retry_client(&server, 503, config).await;
+    client.projects(None, None, false).await.expect_err("503");
+    assert!(request_count(&server).await > 1);
+}
+
+#[tokio::test]
+async fn retry_config_skips_503_without_server_errors() {
+    let server = MockServer::start().await;
+    let config = RetryConfig::default()
+        .with_max_retries(2)
+        .with_retry_on([RetryCondition::Network, RetryCondition::RateLimit]);
+    let client = retry_client(&server, 503, config).await;
+    client.projects(None, None, false).await.expect_err("503");
+    assert_eq!(request_count(&server).await, 1);
+}
+
+#[tokio::test]
+async fn retry_config_5xx_only_does_not_retry_rate_limit() {
+    let server = MockServer::start().await;
+    let config = RetryConfig::default()
+        .with_max_retries(2)
+        .with_backoff_on_5xx_only();
+    let client = retry_client(&server, 429, config).await;
+    client.projects(None, None, false).await.expect_err("429");
+    assert_eq!(request_count(&server).await, 1);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::with_observer`
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct CountingObserver {
+    requests: std::sync::atomic::AtomicU64,
+    bytes: std::sync::atomic::AtomicU64,
+    retries: std::sync::atomic::AtomicU64,
+    errors: std::sync::Mutex<Vec<String>>,
+}
+
+impl edgefirst_client::ClientObserver for CountingObserver {
+    fn on_request(&self, _name: &str) {
+        self.requests
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn on_bytes(&self, bytes: u64) {
+        self.bytes
+            .fetch_add(bytes, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn on_retry(&self, _name: &str, _attempt: u32) {
+        self.retries
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn on_error(&self, name: &str, error: &Error) {
+        self.errors.lock().unwrap().push(format!("{name}: {error}"));
+    }
+}
+
+#[tokio::test]
+async fn observer_counts_download_and_failed_request() {
+    use std::sync::atomic::Ordering;
+
+    let server = MockServer::start().await;
+    mount_download_sample(&server, "frame.png", png_1x1()).await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("dataset.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(101, "no such dataset")))
+        .mount(&server)
+        .await;
+
+    let observer = std::sync::Arc::new(CountingObserver::default());
+    let client = client_for(&server.uri()).with_observer(observer.clone());
+    let dir = tempfile::tempdir().unwrap();
+    client
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+        .expect("download should succeed");
+    assert_eq!(
+        observer.bytes.load(Ordering::SeqCst),
+        png_1x1().len() as u64
+    );
+    assert!(observer.errors.lock().unwrap().is_empty());
+
+    client
+        .dataset(DatasetID::from(2u64))
+        .await
+        .expect_err("dataset.get should fail");
+
+    // Every request the server saw, RPC or file, was reported once.
+    assert_eq!(
+        observer.requests.load(Ordering::SeqCst),
+        request_count(&server).await as u64
+    );
+    assert_eq!(observer.retries.load(Ordering::SeqCst), 0);
+    let errors = observer.errors.lock().unwrap();
+    assert_eq!(errors.len(), 1, "got {errors:?}");
+    assert!(errors[0].starts_with("dataset.get: "), "got {errors:?}");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_project`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn export_project_writes_child_entities() {
+    let server = MockServer::start().await;
+    let responses = [
+        (
+            "project.get",
+            json!({ "id": 1, "name": "Wildlife", "description": "backup me" }),
+        ),
+        (
+            "dataset.list",
+            json!([{
+                "id": 2, "project_id": 1, "name": "deer", "description": "",
+                "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+            }]),
+        ),
+        (
+            "annset.list",
+            json!([{ "id": 4, "name": "Default", "description": "" }]),
+        ),
+        (
+            "label.list",
+            json!([{ "id": 9, "index": 0, "name": "deer" }]),
+        ),
+        (
+            "trainer.list2",
+            json!([{ "id": 7, "project_id": 1, "name": "baseline", "description": "" }]),
+        ),
+        (
+            "trainer.session.list",
+            json!([training_session_json(0x111, "run-1", "")]),
+        ),
+    ];
+    for (rpc, result) in responses {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(rpc))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("backup").join("project.json");
+    let client = client_for(&server.uri());
+    client
+        .export_project(1u64.into(), &out)
+        .await
+        .expect("export_project via mock");
+
+    let doc: serde_json::Value = serde_json::from_slice(&std::fs::read(&out).unwrap()).unwrap();
+    assert_eq!(doc["project"]["name"], "Wildlife");
+    let dataset = &doc["datasets"][0];
+    assert_eq!(dataset["name"], "deer");
+    assert_eq!(dataset["annotation_sets"][0]["name"], "Default");
+    assert_eq!(dataset["labels"][0]["name"], "deer");
+    let experiment = &doc["experiments"][0];
+    assert_eq!(experiment["name"], "baseline");
+    let session = &experiment["training_sessions"][0];
+    assert_eq!(session["name"], "run-1");
+    assert_eq!(session["model_params"]["epochs"], 5);
+    assert_eq!(session["dataset_params"]["train_group"], "train");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::clone_structure`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn clone_structure_copies_labels_and_annotation_sets_without_samples() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, params: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(
+                json!({ "method": name, "params": params }),
+            ))
+    };
+
+    rpc("dataset.get", json!({ "dataset_id": 1 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 1, "project_id": 1, "name": "source", "description": "deer cams",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+        }))))
+        .mount(&server)
+        .await;
+    let source_labels = json!([
+        { "id": 10, "index": 0, "name": "deer" },
+        { "id": 11, "index": 1, "name": "elk" },
+    ]);
+    rpc("label.list", json!({ "dataset_id": 1 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(source_labels)))
+        .mount(&server)
+        .await;
+    rpc("annset.list", json!({ "dataset_id": 1 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 20, "name": "Default", "description": "" },
+            { "id": 21, "name": "reviewed", "description": "QA pass" },
+        ]))))
+        .mount(&server)
+        .await;
+
+    rpc(
+        "dataset.create",
+        json!({ "project_id": "p-2", "name": "copy", "description": "deer cams" }),
+    )
+    .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "id": 2 }))))
+    .expect(1)
+    .mount(&server)
+    .await;
+    // The new dataset starts without labels, then reports the copied ones.
+    rpc("label.list", json!({ "dataset_id": 2 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    rpc("label.list", json!({ "dataset_id": 2 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 30, "index": 0, "name": "deer" },
+            { "id": 31, "index": 1, "name": "elk" },
+        ]))))
+        .mount(&server)
+        .await;
+    rpc(
+        "label.add2",
+        json!({ "dataset_id": 2, "labels": [{ "name": "deer" }, { "name": "elk" }] }),
+    )
+    .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+    .expect(1)
+    .mount(&server)
+    .await;
+    // The server already created a "Default" set for the new dataset.
+    rpc("annset.list", json!({ "dataset_id": 2 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 40, "name": "Default", "description": "" },
+        ]))))
+        .mount(&server)
+        .await;
+    rpc(
+        "annset.add",
+        json!({ "dataset_id": 2, "name": "reviewed", "description": "QA pass" }),
+    )
+    .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "id": 41 }))))
+    .expect(1)
+    .mount(&server)
+    .await;
+
+    let client = client_for(&server.uri());
+    let id = client
+        .clone_structure(DatasetID::from(1u64), 2u64.into(), "copy")
+        .await
+        .expect("clone_structure via mock");
+    assert_eq!(id, DatasetID::from(2u64));
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let methods: Vec<String> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+        .filter_map(|body| body["method"].as_str().map(str::to_owned))
+        .collect();
+    assert_eq!(methods.iter().filter(|m| *m == "annset.add").count(), 1);
+    assert!(
+        !methods.iter().any(|m| m.starts_with("samples.")),
+        "no samples should be copied: {methods:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::annotations_with_total`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn annotations_with_total_fetches_one_page_and_reports_full_count() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("annset.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 7, "dataset_id": 1, "name": "Default", "description": ""
+        }))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 5 }))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(
+            json!({ "method": "samples.list", "params": { "limit": 2 } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [
+                { "id": 10, "image_name": "a.jpg", "group_name": "train" },
+                { "id": 11, "image_name": "b.jpg", "group_name": "train" },
+            ],
+            "continue_token": "next"
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let (annotations, total) = client
+        .annotations_with_total(AnnotationSetID::from(7u64), &[], &[], Some(2), None, None)
+        .await
+        .expect("annotations_with_total via mock");
+
+    assert_eq!(total, 5);
+    // One annotation row per unannotated sample; the `samples.list` mock's
+    // `expect(1)` checks the continue token was not followed.
+    assert_eq!(annotations.len(), 2);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::create_annotation_set_with_annotations`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn create_annotation_set_with_annotations_creates_missing_labels_and_uploads() {
+    use edgefirst_client::{Annotation, Box2d, Polygon};
+    use std::collections::HashMap;
+
+    let server = MockServer::start().await;
+    let rpc = |name: &str, params: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(
+                json!({ "method": name, "params": params }),
+            ))
+    };
+
+    // The first listing predates the new label; later ones include it.
+    rpc("label.list", json!({ "dataset_id": 1 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 10, "index": 0, "name": "deer" },
+        ]))))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    rpc("label.list", json!({ "dataset_id": 1 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 10, "index": 0, "name": "deer" },
+            { "id": 11, "index": 1, "name": "elk" },
+        ]))))
+        .mount(&server)
+        .await;
+    rpc(
+        "label.add2",
+        json!({ "dataset_id": 1, "labels": [{ "name": "elk" }] }),
+    )
+    .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+    .expect(1)
+    .mount(&server)
+    .await;
+    rpc(
+        "annset.add",
+        json!({ "dataset_id": 1, "name": "predictions" }),
+    )
+    .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "id": 42 }))))
+    .expect(1)
+    .mount(&server)
+    .await;
+    rpc("annotation.add_bulk", json!({ "annotation_set_id": 42 }))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(&server)
+        .await;
+
+    let mut deer = Annotation::new();
+    deer.set_label(Some("deer".to_string()));
+    deer.set_box2d(Some(Box2d::new(0.1, 0.2, 0.3, 0.4)));
+    deer.set_box2d_score(Some(0.9));
+    let mut elk = Annotation::new();
+    elk.set_label(Some("elk".to_string()));
+    elk.set_polygon(Some(Polygon::new(vec![vec![
+        (0.5, 0.5),
+        (0.7, 0.5),
+        (0.7, 0.9),
+    ]])));
+    let mut empty = Annotation::new();
+    empty.set_label(Some("deer".to_string()));
+
+    let predictions = HashMap::from([
+        (SampleID::from(100u64), vec![deer, empty]),
+        (SampleID::from(101u64), vec![elk]),
+    ]);
+
+    let client = client_for(&server.uri());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let id = client
+        .create_annotation_set_with_annotations(
+            DatasetID::from(1u64),
+            "predictions",
+            &predictions,
+            Some(tx),
+        )
+        .await
+        .expect("create_annotation_set_with_annotations via mock");
+    assert_eq!(id, AnnotationSetID::from(42u64));
+
+    let progress = rx.recv().await.expect("progress event");
+    assert_eq!((progress.current, progress.total), (2, 2));
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let sent: Vec<serde_json::Value> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+        .filter(|body| body["method"] == "annotation.add_bulk")
+        .flat_map(|body| body["params"]["annotations"].as_array().cloned().unwrap())
+        .collect();
+    // The geometry-less annotation is skipped.
+    assert_eq!(sent.len(), 2);
+    let by_label = |id: u64| {
+        sent.iter()
+            .find(|a| a["label_id"] == id)
+            .expect("annotation for label")
+    };
+    let deer = by_label(10);
+    assert_eq!(deer["type"], "box");
+    assert_eq!(deer["image_id"], 100);
+    assert!((deer["score"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+    let elk = by_label(11);
+    assert_eq!(elk["type"], "seg");
+    assert_eq!(elk["image_id"], 101);
+    assert_eq!(elk["score"], 1.0);
+    assert!((elk["h"].as_f64().unwrap() - 0.4).abs() < 1e-6);
+}
+
+#[tokio::test]
+async fn create_annotation_set_with_annotations_batches_requests_by_sample() {
+    use edgefirst_client::{Annotation, Box2d};
+    use std::collections::HashMap;
+
+    let server = MockServer::start().await;
+    for (name, result) in [
+        (
+            "label.list",
+            json!([{ "id": 10, "index": 0, "name": "deer" }]),
+        ),
+        ("label.add2", json!("ok")),
+        ("annset.add", json!({ "id": 42 })),
+        ("annotation.add_bulk", json!([])),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    // 150 samples with 10 annotations each: 1500 annotations in total.
+    let mut deer = Annotation::new();
+    deer.set_label(Some("deer".to_string()));
+    deer.set_box2d(Some(Box2d::new(0.1, 0.2, 0.3, 0.4)));
+    let predictions: HashMap<SampleID, Vec<Annotation>> = (0..150u64)
+        .map(|id| (SampleID::from(1000 + id), vec![deer.clone(); 10]))
+        .collect();
+
+    let client = client_for(&server.uri());
+    client
+        .create_annotation_set_with_annotations(
+            DatasetID::from(1u64),
+            "predictions",
+            &predictions,
+            None,
+        )
+        .await
+        .expect("create_annotation_set_with_annotations via mock");
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let batches: Vec<Vec<u64>> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+        .filter(|body| body["method"] == "annotation.add_bulk")
+        .map(|body| {
+            body["params"]["annotations"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|a| a["image_id"].as_u64().unwrap())
+                .collect()
+        })
+        .collect();
+    // The 500-annotation cap closes each request after 50 whole samples,
+    // before the 100-sample limit is reached.
+    assert_eq!(batches.len(), 3);
+    assert!(
+        batches.iter().all(|b| b.len() == 500),
+        "{:?}",
+        batches.iter().map(Vec::len).collect::<Vec<_>>()
+    );
+    assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 1500);
+    for id in 1000..1150u64 {
+        let holding = batches.iter().filter(|b| b.contains(&id)).count();
+        assert_eq!(holding, 1, "annotations of sample {id} were split");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_coco`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn export_coco_writes_contiguous_categories_and_skips_3d_boxes() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+    };
+    rpc(
+        "annset.get",
+        json!({ "id": 7, "dataset_id": 1, "name": "Default", "description": "" }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "dataset.get",
+        json!({
+            "id": 1, "project_id": 1, "name": "source", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "normalized"
+        }),
+    )
+    .mount(&server)
+    .await;
+    // Label indices have a gap; COCO category IDs must not.
+    rpc(
+        "label.list",
+        json!([
+            { "id": 21, "index": 5, "name": "car" },
+            { "id": 20, "index": 2, "name": "person" },
+        ]),
+    )
+    .mount(&server)
+    .await;
+    rpc("samples.count", json!({ "total": 2 }))
+        .mount(&server)
+        .await;
+    rpc(
+        "samples.list",
+        json!({
+            "samples": [
+                {
+                    "id": 10, "image_name": "a.jpg", "group_name": "train",
+                    "width": 100, "height": 50,
+                    "annotations": [
+                        {
+                            "label_name": "car",
+                            "box2d": { "x": 0.1, "y": 0.2, "w": 0.5, "h": 0.4 }
+                        },
+                        {
+                            "label_name": "person",
+                            "polygon": [[[0.0, 0.0], [0.5, 0.0], [0.5, 1.0]]]
+                        },
+                        {
+                            "label_name": "car",
+                            "box3d": { "x": 1.0, "y": 2.0, "z": 3.0, "w": 1.0, "h": 1.0, "l": 4.0 }
+                        }
+                    ]
+                },
+                { "id": 11, "image_name": "b.jpg", "group_name": "train" }
+            ],
+            "continue_token": null
+        }),
+    )
+    .mount(&server)
+    .await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let path = client
+        .export_coco(AnnotationSetID::from(7u64), &[], dir.path())
+        .await
+        .expect("export_coco via mock");
+    assert_eq!(path, dir.path().join("annotations.json"));
+
+    let coco: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    let categories: Vec<(u64, &str)> = coco["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| (c["id"].as_u64().unwrap(), c["name"].as_str().unwrap()))
+        .collect();
+    assert_eq!(categories, vec![(1, "person"), (2, "car")]);
+
+    // The sample without dimensions and the 3D box are left out.
+    let images = coco["images"].as_array().unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0]["file_name"], "a.jpg");
+    let annotations = coco["annotations"].as_array().unwrap();
+    assert_eq!(annotations.len(), 2);
+
+    let car = annotations.iter().find(|a| a["category_id"] == 2).unwrap();
+    let bbox: Vec<f64> = car["bbox"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+    for (got, want) in bbox.iter().zip([10.0, 10.0, 50.0, 20.0]) {
+        assert!((got - want).abs() < 1e-3, "bbox {bbox:?}");
+    }
+    assert!((car["area"].as_f64().unwrap() - 1000.0).abs() < 1e-3);
+
+    let person = annotations.iter().find(|a| a["category_id"] == 1).unwrap();
+    assert_eq!(person["segmentation"][0].as_array().unwrap().len(), 6);
+}
+
+#[tokio::test]
+async fn keypoints_round_trip_through_populate_and_export_coco() {
+    use edgefirst_client::{Annotation, Keypoints, Visibility};
+
+    let server = MockServer::start().await;
+    let rpc = |name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+    };
+
+    // Populate a sample carrying a keypoint annotation.
+    let dir = tempfile::tempdir().unwrap();
+    let image = dir.path().join("pose.jpg");
+    tokio::fs::write(&image, b"jpeg").await.unwrap();
+    let mut annotation = Annotation::new();
+    annotation.set_label(Some("person".to_string()));
+    annotation.set_keypoints(Some(Keypoints::new(vec![
+        (0.25, 0.5, Visibility::Visible),
+        (0.0, 0.0, Visibility::NotLabeled),
+        (0.75, 0.25, Visibility::Occluded),
+    ])));
+    let mut sample = edgefirst_client::Sample::new();
+    sample.uuid = Some("uuid-pose".to_string());
+    sample.image_name = Some("pose.jpg".to_string());
+    sample.files = vec![SampleFile::with_filename(
+        "image".to_string(),
+        image.to_string_lossy().into_owned(),
+    )];
+    sample.annotations = vec![annotation];
+
+    rpc(
+        "samples.populate2",
+        json!([{
+            "uuid": "uuid-pose",
+            "urls": [{
+                "filename": "pose.jpg",
+                "key": "uuid-pose/pose.jpg",
+                "url": format!("{}/s3/pose", server.uri()),
+            }],
+        }]),
+    )
+    .expect(1)
+    .mount(&server)
+    .await;
+    Mock::given(method("PUT"))
+        .and(path("/s3/pose"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    client
+        .populate_samples(DatasetID::from(1u64), None, vec![sample], None, None)
+        .await
+        .expect("populate_samples via mock")
+        .into_result()
+        .expect("populate succeeds");
+
+    let requests = server.received_requests().await.unwrap();
+    let populate: serde_json::Value = requests
+        .iter()
+        .filter(|r| r.url.path() == "/api")
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .find(|body: &serde_json::Value| body["method"] == "samples.populate2")
+        .unwrap();
+    let uploaded = populate["params"]["samples"][0]["annotations"].clone();
+    assert_eq!(
+        uploaded[0]["keypoints"],
+        json!([[0.25, 0.5, 2], [0.0, 0.0, 0], [0.75, 0.25, 1]])
+    );
+
+    // List the annotations back as the server stored them and export them.
+    rpc(
+        "annset.get",
+        json!({ "id": 7, "dataset_id": 1, "name": "Default", "description": "" }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "dataset.get",
+        json!({
+            "id": 1, "project_id": 1, "name": "poses", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "normalized"
+        }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "label.list",
+        json!([{ "id": 20, "index": 0, "name": "person" }]),
+    )
+    .mount(&server)
+    .await;
+    rpc("samples.count", json!({ "total": 1 }))
+        .mount(&server)
+        .await;
+    rpc(
+        "samples.list",
+        json!({
+            "samples": [{
+                "id": 10, "image_name": "pose.jpg", "width": 200, "height": 100,
+                "annotations": uploaded,
+            }],
+            "continue_token": null
+        }),
+    )
+    .mount(&server)
+    .await;
+
+    let path = client
+        .export_coco(AnnotationSetID::from(7u64), &[], dir.path())
+        .await
+        .expect("export_coco via mock");
+    let coco: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    let annotations = coco["annotations"].as_array().unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(
+        annotations[0]["keypoints"],
+        json!([50.0, 50.0, 2.0, 0.0, 0.0, 0.0, 150.0, 25.0, 1.0])
+    );
+    assert_eq!(annotations[0]["num_keypoints"], 2);
+    assert_eq!(annotations[0]["bbox"], json!([50.0, 25.0, 100.0, 25.0]));
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_yolo`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn export_yolo_writes_label_files_and_class_list() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+    };
+    rpc(
+        "dataset.get",
+        json!({
+            "id": 1, "project_id": 1, "name": "source", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "normalized"
+        }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "label.list",
+        json!([
+            { "id": 21, "index": 5, "name": "car" },
+            { "id": 20, "index": 2, "name": "person" },
+        ]),
+    )
+    .mount(&server)
+    .await;
+    rpc("samples.count", json!({ "total": 2 }))
+        .mount(&server)
+        .await;
+    rpc(
+        "samples.list",
+        json!({
+            "samples": [
+                {
+                    "id": 10, "image_name": "a.jpg", "group_name": "train",
+                    "annotations": [{
+                        "label_name": "car",
+                        "box2d": { "x": 0.1, "y": 0.2, "w": 0.5, "h": 0.4 }
+                    }]
+                },
+                { "id": 11, "image_name": "b.png", "group_name": "train" }
+            ],
+            "continue_token": null
+        }),
+    )
+    .mount(&server)
+    .await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("yolo");
+    client
+        .export_yolo(
+            DatasetID::from(1u64),
+            AnnotationSetID::from(7u64),
+            &output,
+            &[],
+        )
+        .await
+        .expect("export_yolo via mock");
+
+    let read = |name: &str| std::fs::read_to_string(output.join(name)).unwrap();
+    assert_eq!(read("a.txt"), "1 0.350000 0.400000 0.500000 0.400000\n");
+    assert_eq!(read("b.txt"), "");
+    assert_eq!(read("classes.txt"), "person\ncar\n");
+    assert_eq!(read("data.yaml"), "nc: 2\nnames: [\"person\",\"car\"]\n");
+}
+
+#[tokio::test]
+async fn export_yolo_normalizes_boxes_of_a_pixel_space_dataset() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+    };
+    rpc(
+        "dataset.get",
+        json!({
+            "id": 1, "project_id": 1, "name": "pixels", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "pixels"
+        }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "label.list",
+        json!([{ "id": 21, "index": 0, "name": "car" }]),
+    )
+    .mount(&server)
+    .await;
+    rpc("samples.count", json!({ "total": 2 }))
+        .mount(&server)
+        .await;
+    rpc(
+        "samples.list",
+        json!({
+            "samples": [
+                {
+                    "id": 10, "image_name": "a.jpg", "width": 200, "height": 100,
+                    "group_name": "train",
+                    "annotations": [{
+                        "label_name": "car",
+                        "box2d": { "x": 20.0, "y": 20.0, "w": 100.0, "h": 40.0 }
+                    }]
+                },
+                {
+                    "id": 11, "image_name": "b.jpg", "group_name": "val",
+                    "annotations": [{
+                        "label_name": "car",
+                        "box2d": { "x": 20.0, "y": 20.0, "w": 100.0, "h": 40.0 }
+                    }]
+                }
+            ],
+            "continue_token": null
+        }),
+    )
+    .mount(&server)
+    .await;
+
+    let client = client_for(&server.uri());
+    assert_eq!(
+        client
+            .dataset(DatasetID::from(1u64))
+            .await
+            .unwrap()
+            .coordinate_space(),
+        edgefirst_client::CoordinateSpace::Pixels
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("yolo");
+    // Pixel boxes cannot be normalized without the image size: the error
+    // names the sample and nothing is written.
+    let err = client
+        .export_yolo(
+            DatasetID::from(1u64),
+            AnnotationSetID::from(7u64),
+            &output,
+            &[],
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::InvalidParameters(msg) if msg.contains("b.jpg") && !msg.contains("a.jpg")),
+        "{err:?}"
+    );
+    assert!(!output.exists());
+
+    client
+        .export_yolo(
+            DatasetID::from(1u64),
+            AnnotationSetID::from(7u64),
+            &output,
+            &["train".to_string()],
+        )
+        .await
+        .expect("export_yolo of the sized group");
+    assert_eq!(
+        std::fs::read_to_string(output.join("a.txt")).unwrap(),
+        "0 0.350000 0.400000 0.500000 0.400000\n"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_tfrecord`
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "tfrecord")]
+#[tokio::test]
+async fn export_tfrecord_embeds_images_and_skips_samples_without_one() {
+    use edgefirst_client::format::tfrecord::read_tfrecord;
+
+    let server = MockServer::start().await;
+    let rpc = |name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+    };
+    rpc(
+        "dataset.get",
+        json!({
+            "id": 1, "project_id": 1, "name": "source", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z",
+            "coordinate_space": "normalized"
+        }),
+    )
+    .mount(&server)
+    .await;
+    rpc(
+        "label.list",
+        json!([{ "id": 21, "index": 0, "name": "car" }]),
+    )
+    .mount(&server)
+    .await;
+    rpc("samples.count", json!({ "total": 2 }))
+        .mount(&server)
+        .await;
+    rpc(
+        "samples.list",
+        json!({
+            "samples": [
+                {
+                    "id": 10, "image_name": "a.png", "width": 4, "height": 2,
+                    "image_url": format!("{}/images/a.png", server.uri()),
+                    "annotations": [{
+                        "label_name": "car",
+                        "box2d": { "x": 0.1, "y": 0.2, "w": 0.5, "h": 0.4 }
+                    }]
+                },
+                { "id": 11, "image_name": "b.png" }
+            ],
+            "continue_token": null
+        }),
+    )
+    .mount(&server)
+    .await;
+    let image = b"\x89PNG\r\n\x1a\nimage bytes".to_vec();
+    Mock::given(method("GET"))
+        .and(path("/images/a.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(image.clone()))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("tfrecord");
+    let shards = client
+        .export_tfrecord(
+            DatasetID::from(1u64),
+            AnnotationSetID::from(7u64),
+            &output,
+            &[],
+            None,
+        )
+        .await
+        .expect("export_tfrecord via mock");
+
+    assert_eq!(shards, [output.join("data-00000-of-00001.tfrecord")]);
+    let records = read_tfrecord(&shards[0]).unwrap();
+    assert_eq!(records.len(), 1);
+    let contains = |needle: &[u8]| records[0].windows(needle.len()).any(|w| w == needle);
+    assert!(contains(&image));
+    assert!(contains(b"image/object/bbox/xmin"));
+    assert_eq!(
+        std::fs::read_to_string(output.join("label_map.pbtxt")).unwrap(),
+        "item {\n  id: 1\n  name: \"car\"\n}\n"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Locked annotation sets
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn writes_to_locked_annotation_set_fail_with_annotation_set_locked() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("annset.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 42, "dataset_id": 1, "name": "reviewed", "description": "",
+            "locked": true
+        }))))
+        .mount(&server)
+        .await;
+    for name in ["annotation.add_bulk", "annotation.bulk.del"] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+            .expect(0)
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let set_id = AnnotationSetID::from(42u64);
+    assert!(client.annotation_set(set_id).await.unwrap().is_locked());
+
+    let annotation = ServerAnnotation {
+        label_id: Some(1),
+        label_index: None,
+        label_name: None,
+        annotation_type: "box".to_string(),
+        x: 0.5,
+        y: 0.5,
+        w: 0.1,
+        h: 0.1,
+        z: None,
+        l: None,
+        score: 1.0,
+        polygon: String::new(),
+        image_id: 100,
+        annotation_set_id: 42,
+        object_reference: None,
+        attributes: Default::default(),
+    };
+    let err = client
+        .add_annotations_bulk(set_id, vec![annotation])
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, Error::AnnotationSetLocked(id) if id == set_id),
+        "{err:?}"
+    );
+
+    let err = client
+        .delete_annotations_bulk(set_id, &["box".to_string()], &[SampleID::from(100u64)])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::AnnotationSetLocked(_)), "{err:?}");
+}
+
+// ---------------------------------------------------------------------------
+// Presigned URL expiry during `download_dataset`
+// ---------------------------------------------------------------------------
+
+/// Lists a one-sample dataset whose image URL is `/files/{file}`.
+async fn mount_sample_listing(server: &MockServer, file: &str, times: Option<u64>) {
+    let mock = Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{
+                "id": 10,
+                "image_name": "frame.png",
+                "image_url": format!("{}/files/{file}", server.uri()),
+            }],
+            "continue_token": null
+        }))));
+    match times {
+        Some(n) => mock.up_to_n_times(n).expect(n).mount(server).await,
+        None => mock.mount(server).await,
+    }
+}
+
+/// Mounts a one-sample dataset whose first listing gives the image URL
+/// `/files/stale` and answers it with `403`, as S3 does once a presigned
+/// URL expires. Tests mount the listing that re-presigning sees.
+async fn mount_stale_sample(server: &MockServer) {
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 1 })),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(server)
+            .await;
+    }
+    mount_sample_listing(server, "stale", Some(1)).await;
+    Mock::given(method("GET"))
+        .and(path("/files/stale"))
+        .respond_with(ResponseTemplate::new(403).set_body_string("Request has expired"))
+        .mount(server)
+        .await;
+}
+
+async fn download_image(server: &MockServer, output: &std::path::Path) -> Result<(), Error> {
+    client_for(&server.uri())
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            output.to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn download_dataset_re_presigns_expired_urls() {
+    let server = MockServer::start().await;
+    mount_stale_sample(&server).await;
+    mount_sample_listing(&server, "fresh", Some(1)).await;
+    Mock::given(method("GET"))
+        .and(path("/files/fresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(png_1x1()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    download_image(&server, dir.path())
+        .await
+        .expect("download should recover from the expired URL");
+    assert_eq!(
+        std::fs::read(dir.path().join("frame.png")).unwrap(),
+        png_1x1()
+    );
+}
+
+#[tokio::test]
+async fn download_dataset_reports_url_expired_when_re_presign_is_rejected() {
+    let server = MockServer::start().await;
+    mount_stale_sample(&server).await;
+    // The server hands back the same stale URL.
+    mount_sample_listing(&server, "stale", None).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let err = download_image(&server, dir.path())
+        .await
+        .expect_err("a URL rejected after re-presigning must fail");
+    assert!(
+        matches!(&err, Error::UrlExpired(name) if name == "frame"),
+        "got {err:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::download_samples_memory`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn download_samples_memory_returns_bytes_per_file_type() {
+    use edgefirst_client::FileType;
+
+    let server = MockServer::start().await;
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 3 })),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+    // The requested samples are listed among others, on the first page.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.list",
+            "params": { "dataset_id": 1, "types": ["image", "lidar.pcd"] }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [
+                {
+                    "id": 11,
+                    "image_name": "11.jpg",
+                    "image_url": format!("{}/files/11.jpg", server.uri()),
+                },
+                {
+                    "id": 12,
+                    "image_name": "12.jpg",
+                    "image_url": format!("{}/files/12.jpg", server.uri()),
+                },
+                {
+                    "id": 10,
+                    "image_name": "10.jpg",
+                    "image_url": format!("{}/files/10.jpg", server.uri()),
+                    "sensors": { "lidar.pcd": format!("{}/files/10.pcd", server.uri()) },
+                },
+            ],
+            "continue_token": "more"
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    for (name, body) in [
+        ("10.jpg", b"image ten".as_slice()),
+        ("10.pcd", b"points ten".as_slice()),
+        ("11.jpg", b"image eleven".as_slice()),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{name}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let samples = client_for(&server.uri())
+        .download_samples_memory(
+            DatasetID::from(1u64),
+            &[SampleID::from(10u64), SampleID::from(11u64)],
+            &[FileType::Image, FileType::LidarPcd],
+            Some(2),
+        )
+        .await
+        .expect("download_samples_memory");
+
+    assert_eq!(samples.len(), 2);
+    let (id, files) = &samples[0];
+    assert_eq!(*id, SampleID::from(10u64));
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[&FileType::Image], b"image ten");
+    assert_eq!(files[&FileType::LidarPcd], b"points ten");
+    let (id, files) = &samples[1];
+    assert_eq!(*id, SampleID::from(11u64));
+    assert_eq!(files.len(), 1, "sample 11 has no LiDAR file");
+    assert_eq!(files[&FileType::Image], b"image eleven");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::samples_dataframe_to_parquet`, `samples_dataframe_chunked` and
+// `export_parquet`
+// ---------------------------------------------------------------------------
+
+/// Mounts a six-sample dataset listed as three pages of two unannotated
+/// samples, chained by continue tokens.
+#[cfg(feature = "polars")]
+async fn mount_three_sample_pages(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 6 }))))
+        .mount(server)
+        .await;
+    let page = |first: u64, next: Option<&str>| {
+        let samples: Vec<_> = (first..first + 2)
+            .map(
+                |id| json!({ "id": id, "image_name": format!("s{id}.jpg"), "group_name": "train" }),
+            )
+            .collect();
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": samples,
+            "continue_token": next,
+        })))
+    };
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.list"))
+        .respond_with(page(0, Some("p2")))
+        .mount(server)
+        .await;
+    for (token, first, next) in [("p2", 2, Some("p3")), ("p3", 4, None)] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(json!({
+                "method": "samples.list",
+                "params": { "continue_token": token }
+            })))
+            .respond_with(page(first, next))
+            .with_priority(1)
+            .mount(server)
+            .await;
+    }
+}
+
+#[cfg(feature = "polars")]
+#[tokio::test]
+async fn samples_dataframe_to_parquet_writes_one_row_group_per_chunk() {
+    use polars::prelude::{ParquetReader, SerReader as _};
+
+    let server = MockServer::start().await;
+    mount_three_sample_pages(&server).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("samples.parquet");
+    let client = client_for(&server.uri());
+    let rows = client
+        .samples_dataframe_to_parquet(
+            DatasetID::from(1u64),
+            None,
+            &[],
+            &[],
+            &output,
+            2,
+            None,
+            None,
+        )
+        .await
+        .expect("samples_dataframe_to_parquet via mock");
+    assert_eq!(rows, 6);
+
+    let mut reader = ParquetReader::new(std::fs::File::open(&output).unwrap());
+    // Each page was flushed as its own row group rather than buffered.
+    assert_eq!(reader.get_metadata().unwrap().row_groups.len(), 3);
+    let df = reader.finish().unwrap();
+    assert_eq!(df.height(), 6);
+}
+
+#[cfg(feature = "polars")]
+#[tokio::test]
+async fn samples_dataframe_chunked_yields_frames_with_one_schema() {
+    use futures::TryStreamExt as _;
+
+    let server = MockServer::start().await;
+    mount_three_sample_pages(&server).await;
+
+    let client = client_for(&server.uri());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let frames: Vec<_> = client
+        .samples_dataframe_chunked(DatasetID::from(1u64), None, &[], &[], 4, Some(tx), None)
+        .try_collect()
+        .await
+        .expect("samples_dataframe_chunked via mock");
+
+    let heights: Vec<_> = frames.iter().map(|df| df.height()).collect();
+    assert_eq!(heights, [4, 2]);
+    assert_eq!(frames[0].schema(), frames[1].schema());
+
+    let mut last = None;
+    while let Some(update) = rx.recv().await {
+        last = Some(update);
+    }
+    let last = last.expect("progress updates");
+    assert_eq!((last.current, last.total), (6, 6));
+
+    let err = client
+        .samples_dataframe_chunked(DatasetID::from(1u64), None, &[], &[], 0, None, None)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+}
+
+#[cfg(feature = "polars")]
+#[tokio::test]
+async fn export_parquet_writes_one_directory_per_partition() {
+    use polars::prelude::{ParquetReader, SerReader as _};
+
+    let server = MockServer::start().await;
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 4 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    { "id": 1, "image_name": "a.jpg", "group_name": "train" },
+                    { "id": 2, "image_name": "b.jpg", "group_name": "val" },
+                    { "id": 3, "image_name": "c.jpg", "group_name": "train" },
+                    { "id": 4, "image_name": "d.jpg" },
+                ],
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let client = client_for(&server.uri());
+    let rows = client
+        .export_parquet(
+            DatasetID::from(1u64),
+            None,
+            dir.path(),
+            &["group".to_string()],
+        )
+        .await
+        .expect("export_parquet via mock");
+    assert_eq!(rows, 4);
+
+    let read = |partition: &str| {
+        let file = dir.path().join(partition).join("part-00000.parquet");
+        ParquetReader::new(std::fs::File::open(&file).unwrap())
+            .finish()
+            .unwrap()
+    };
+    let train = read("group=train");
+    assert_eq!(train.height(), 2);
+    // The partition value lives in the path, not the file.
+    assert!(train.column("group").is_err());
+    assert!(train.column("name").is_ok());
+    assert_eq!(read("group=val").height(), 1);
+    assert_eq!(read("group=__HIVE_DEFAULT_PARTITION__").height(), 1);
+
+    let err = client
+        .export_parquet(
+            DatasetID::from(1u64),
+            None,
+            dir.path(),
+            &["source".to_string()],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "got {err:?}");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::diagnose_servers`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn diagnose_servers_reports_unreachable_servers_without_failing() {
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("version"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "version": "3.1.0" }))),
+        )
+        .mount(&healthy)
+        .await;
+    let failing = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&failing)
+        .await;
+    // Bind and drop a listener so the port is known to refuse connections.
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let closed_url = format!("http://{}", closed.local_addr().unwrap());
+    drop(closed);
+
+    let client = client_for(&healthy.uri())
+        .with_retry_config(RetryConfig::default().with_max_retries(3))
+        .unwrap();
+    let servers = [healthy.uri(), failing.uri(), closed_url.clone()];
+    let servers: Vec<&str> = servers.iter().map(String::as_str).collect();
+    let results = client.diagnose_servers(&servers).await;
+
+    let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, servers);
+    assert!(results[0].1.is_ok(), "{:?}", results[0].1);
+    assert!(results[1].1.is_err());
+    assert!(results[2].1.is_err());
+    // A diagnostic ping makes a single attempt regardless of the retry policy.
+    assert_eq!(request_count(&failing).await, 1);
+    // The client keeps its own server.
+    assert_eq!(client.url(), healthy.uri());
+    assert!(
+        client.diagnose_servers(&["ftp://invalid"]).await[0]
+            .1
+            .is_err()
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::annotations` object filter
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn annotations_filters_to_requested_object_ids_across_frames() {
+    let server = MockServer::start().await;
+    let frame = |id: u64, objects: &[(&str, &str)]| {
+        json!({
+            "id": id,
+            "image_name": format!("track_{id:03}.jpg"),
+            "sequence_name": "track",
+            "frame_number": id,
+            "annotations": objects
+                .iter()
+                .map(|(label, object)| json!({
+                    "label_name": label,
+                    "object_reference": object,
+                    "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    };
+    mount_paged_annotation_set(
+        &server,
+        json!({
+            "samples": [
+                frame(1, &[("cat", "cat-1"), ("dog", "dog-1")]),
+                frame(2, &[("dog", "dog-1")]),
+            ],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [
+                frame(3, &[("cat", "cat-1"), ("dog", "dog-2")]),
+                frame(4, &[]),
+            ],
+            "continue_token": null
+        }))),
+    )
+    .await;
+
+    let client = client_for(&server.uri());
+    let set = AnnotationSetID::from(7u64);
+    let rows = |annotations: Vec<edgefirst_client::Annotation>| {
+        annotations
+            .iter()
+            .map(|a| (a.frame_number(), a.object_id().cloned()))
+            .collect::<Vec<_>>()
+    };
+
+    let tracked = client
+        .annotations(set, &[], &[], None, None, &["cat-1", "dog-2"])
+        .await
+        .unwrap();
+    assert_eq!(
+        rows(tracked),
+        [
+            (Some(1), Some("cat-1".to_string())),
+            (Some(3), Some("cat-1".to_string())),
+            (Some(3), Some("dog-2".to_string())),
+        ]
+    );
+
+    // No filter keeps every annotation and the empty frame.
+    let all = client
+        .annotations(set, &[], &[], None, None, &[])
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 6);
+}
+
+// ----------------------------------------------------------------------------
+// Client::with_http2 / with_keepalive
+// ----------------------------------------------------------------------------
+
+/// Starts a bare hyper server that speaks HTTP/1.1 and HTTP/2 and answers
+/// every JSON-RPC call with the protocol version the request arrived on, as
+/// the `version` field read by [`Client::version`].
+async fn spawn_protocol_reporter() -> String {
+    use hyper::{Response, service::service_fn};
+    use hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::conn::auto,
+    };
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let service = service_fn(|request: hyper::Request<hyper::body::Incoming>| {
+                    let body = rpc_result(json!({ "version": format!("{:?}", request.version()) }));
+                    async move {
+                        Ok::<_, std::convert::Infallible>(
+                            Response::builder()
+                                .header("content-type", "application/json")
+                                .body(body.to_string())
+                                .unwrap(),
+                        )
+                    }
+                });
+                let _ = auto::Builder::new(TokioExecutor::new())
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn with_http2_selects_negotiated_protocol() {
+    let url = spawn_protocol_reporter().await;
+    let client = client_for(&url)
+        .with_keepalive(std::time::Duration::from_secs(20))
+        .unwrap();
+
+    assert_eq!(client.version().await.unwrap(), "HTTP/1.1");
+
+    let http2 = client.with_http2(true).unwrap();
+    assert_eq!(http2.version().await.unwrap(), "HTTP/2.0");
+
+    // Later rebuilds of the connection pools keep the protocol.
+    let retried = http2
+        .with_retry_config(RetryConfig::default().with_max_retries(0))
+        .unwrap();
+    assert_eq!(retried.version().await.unwrap(), "HTTP/2.0");
+
+    let http1 = retried.with_http2(false).unwrap();
+    assert_eq!(http1.version().await.unwrap(), "HTTP/1.1");
+}
+
+#[tokio::test]
+async fn with_header_reaches_api_requests_and_user_agent_reaches_storage() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .and(header("X-Trace-Id", "7f3c9a"))
+        .and(header("User-Agent", "acme-labeler/1.4"))
+        .and(header(
+            "Authorization",
+            format!("Bearer {}", fake_jwt()).as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri())
+        .with_header("x-trace-id", "old")
+        .unwrap()
+        .with_header("X-Trace-Id", "7f3c9a")
+        .unwrap()
+        .with_user_agent("acme-labeler/1.4")
+        .unwrap();
+    // Later rebuilds of the connection pools keep the headers.
+    let client = client.with_http2(false).unwrap();
+    assert!(client.projects(None, None, false).await.unwrap().is_empty());
+
+    // Presigned storage URLs only get the User-Agent, never custom headers
+    // such as gateway credentials.
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"abc".to_vec()))
+        .mount(&server)
+        .await;
+    let bytes = client
+        .download(&format!("{}/files/image", server.uri()))
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"abc");
+    let requests = server.received_requests().await.unwrap();
+    let storage = requests
+        .iter()
+        .find(|req| req.url.path() == "/files/image")
+        .unwrap();
+    assert!(!storage.headers.contains_key("x-trace-id"));
+    assert_eq!(storage.headers["user-agent"], "acme-labeler/1.4");
+}
+
+#[tokio::test]
+async fn with_proxy_routes_requests_through_the_proxy() {
+    // The mock plays the proxy: plain-HTTP requests reach it in absolute
+    // form with the Studio host in `Host`, which is never resolved.
+    let proxy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .and(header("Host", "studio.example"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let client = client_for(&proxy.uri())
+        .with_allow_insecure(true)
+        .with_url("http://studio.example")
+        .unwrap()
+        .with_proxy(&proxy.uri())
+        .unwrap();
+    assert!(client.projects(None, None, false).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn with_timeout_reports_a_hanging_request_as_timeout() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("project.list"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(json!([])))
+                .set_delay(std::time::Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let limit = std::time::Duration::from_millis(200);
+    let client = client_for(&server.uri())
+        .with_retry_config(RetryConfig::default().with_max_retries(0))
+        .unwrap()
+        .with_timeout(limit)
+        .unwrap();
+    assert_eq!(client.timeout(), limit);
+
+    let started = std::time::Instant::now();
+    let err = client.projects(None, None, false).await.unwrap_err();
+    assert!(
+        matches!(err, Error::Timeout(elapsed) if elapsed == limit),
+        "{err:?}"
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+    assert!(matches!(
+        client.with_timeout(std::time::Duration::ZERO),
+        Err(Error::InvalidParameters(_))
+    ));
+}
+
+// ----------------------------------------------------------------------------
+// Client::login
+// ----------------------------------------------------------------------------
+
+#[tokio::test]
+async fn login_returns_issued_token_without_storing_it() {
+    use edgefirst_client::{MemoryTokenStorage, TokenStorage};
+
+    let server = MockServer::start().await;
+    let issued = fake_jwt();
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "auth.login",
+            "params": { "username": "u", "password": "p" }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "token": issued }))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let storage = std::sync::Arc::new(MemoryTokenStorage::new());
+    let login = Client::new()
+        .unwrap()
+        .with_storage(storage.clone())
+        .with_url(&server.uri())
+        .unwrap()
+        .login("u", "p")
+        .await
+        .expect("login");
+
+    assert_eq!(login.token, issued);
+    assert_eq!(login.client.token().await, issued);
+    assert_eq!(
+        login.expires_at.map(|at| at.timestamp()),
+        Some(2_000_000_000)
+    );
+    assert_eq!(storage.load().unwrap(), None);
+}
+
+// ----------------------------------------------------------------------------
+// `Client::samples` field projection
+// ----------------------------------------------------------------------------
+
+#[tokio::test]
+async fn samples_ids_projection_omits_annotation_payloads() {
+    let server = MockServer::start().await;
+    for (name, result) in [
+        (
+            "label.list",
+            json!([{ "id": 10, "index": 0, "name": "cat" }]),
+        ),
+        ("samples.count", json!({ "total": 1 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [{
+                    "id": 1, "uuid": "u-1", "image_name": "a.jpg",
+                    "width": 640, "height": 480,
+                    "image_url": "https://bucket.example.com/a.jpg",
+                    "annotations": [
+                        { "label_name": "cat", "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 },
+                    ],
+                }],
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let fetch = async |fields| {
+        client
+            .samples(
+                DatasetID::from(1u64),
+                None,
+                SamplesQuery {
+                    annotation_set_id: Some(AnnotationSetID::from(2u64)),
+                    types: vec![edgefirst_client::FileType::Image],
+                    fields,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+    };
+    let list_params = async || {
+        let requests = server.received_requests().await.unwrap();
+        let list = requests
+            .iter()
+            .rev()
+            .find(|r| r.body_json::<serde_json::Value>().unwrap()["method"] == "samples.list")
+            .unwrap();
+        list.body_json::<serde_json::Value>().unwrap()["params"].clone()
+    };
+
+    let ids = fetch(SampleFields::IDS).await;
+    let params = list_params().await;
+    assert!(params.get("annotation_set_id").is_none(), "{params}");
+    assert!(params.get("types").is_none(), "{params}");
+    assert_eq!(ids[0].uuid().map(String::as_str), Some("u-1"));
+    assert_eq!(ids[0].image_name(), Some("a.jpg"));
+    assert!(ids[0].annotations().is_empty());
+    assert_eq!(ids[0].image_url(), None);
+    assert_eq!(ids[0].width(), None);
+
+    let dims = fetch(SampleFields::IDS | SampleFields::DIMENSIONS).await;
+    assert_eq!((dims[0].width(), dims[0].height()), (Some(640), Some(480)));
+    assert!(dims[0].annotations().is_empty());
+
+    let all = fetch(SampleFields::default()).await;
+    let params = list_params().await;
+    assert_eq!(params["annotation_set_id"], json!(2));
+    assert_eq!(params["types"], json!(["image"]));
+    assert_eq!(all[0].annotations().len(), 1);
+    assert!(all[0].image_url().is_some());
+}
+
+// ---------------------------------------------------------------------------
+// `Client::upload_annotations`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn upload_annotations_sends_each_geometry_and_reports_unknown_samples() {
+    use edgefirst_client::{Annotation, Box2d, Box3d, Polygon};
+
+    let server = MockServer::start().await;
+    for (name, result) in [
+        (
+            "annset.get",
+            json!({ "id": 7, "dataset_id": 1, "name": "Default", "description": "" }),
+        ),
+        (
+            "label.list",
+            json!([
+                { "id": 10, "index": 0, "name": "car" },
+                { "id": 11, "index": 1, "name": "road" },
+            ]),
+        ),
+        ("samples.count", json!({ "total": 2 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    { "id": 100, "uuid": "u-100", "image_name": "a.jpg" },
+                    { "id": 101, "uuid": "u-101", "image_name": "b.jpg" },
+                ],
+                "continue_token": null
+            }),
+        ),
+        ("annotation.add_bulk", json!([])),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let mut car = Annotation::new();
+    car.set_sample_id(Some(SampleID::from(100u64)));
+    car.set_label(Some("car".to_string()));
+    car.set_box2d(Some(Box2d::new(0.1, 0.2, 0.3, 0.4)));
+    car.set_box3d(Some(Box3d::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0)));
+    car.set_box3d_score(Some(0.5));
+    let mut road = Annotation::new();
+    road.set_name(Some("b.jpg".to_string()));
+    road.set_label(Some("road".to_string()));
+    road.set_polygon(Some(Polygon::new(vec![vec![
+        (0.0, 0.5),
+        (1.0, 0.5),
+        (1.0, 1.0),
+    ]])));
+    let mut unknown_id = car.clone();
+    unknown_id.set_sample_id(Some(SampleID::from(999u64)));
+    let mut unknown_name = road.clone();
+    unknown_name.set_name(Some("missing.jpg".to_string()));
+
+    let client = client_for(&server.uri());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let report = client
+        .upload_annotations(
+            AnnotationSetID::from(7u64),
+            vec![unknown_id, car, unknown_name, road],
+            Some(tx),
+        )
+        .await
+        .expect("upload_annotations via mock");
+
+    assert_eq!(report.uploaded, 2);
+    assert!(!report.is_complete());
+    let failed: Vec<_> = report.failed.iter().map(|e| e.index).collect();
+    assert_eq!(failed, [0, 2]);
+    assert!(report.failed[1].message.contains("missing.jpg"));
+
+    let progress = rx.recv().await.expect("progress event");
+    assert_eq!((progress.current, progress.total), (3, 3));
+
+    let requests = server.received_requests().await.unwrap();
+    let sent: Vec<serde_json::Value> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+        .filter(|body| body["method"] == "annotation.add_bulk")
+        .flat_map(|body| body["params"]["annotations"].as_array().cloned().unwrap())
+        .collect();
+    let by_type = |kind: &str| {
+        sent.iter()
+            .find(|a| a["type"] == kind)
+            .unwrap_or_else(|| panic!("{kind} record in {sent:?}"))
+    };
+    let box2d = by_type("box");
+    assert_eq!(
+        (box2d["image_id"].clone(), box2d["label_id"].clone()),
+        (json!(100), json!(10))
+    );
+    assert!(box2d.get("z").is_none());
+    let box3d = by_type("3dbox");
+    assert_eq!(box3d["image_id"], 100);
+    for (field, value) in [
+        ("x", 1.0),
+        ("y", 2.0),
+        ("z", 3.0),
+        ("w", 4.0),
+        ("h", 5.0),
+        ("l", 6.0),
+    ] {
+        assert_eq!(box3d[field], value, "{field}");
+    }
+    assert_eq!(box3d["score"], 0.5);
+    let seg = by_type("seg");
+    assert_eq!(
+        (seg["image_id"].clone(), seg["label_id"].clone()),
+        (json!(101), json!(11))
+    );
+    assert!(!seg["polygon"].as_str().unwrap().is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// `Client::delete_sample`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn delete_sample_maps_missing_sample_to_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "image.delete_from_dataset",
+            "params": { "dataset_id": 1, "image_ids": [10] }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "image.delete_from_dataset",
+            "params": { "image_ids": [11] }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(101, "image not found")))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    client
+        .delete_sample(DatasetID::from(1u64), SampleID::from(10u64))
+        .await
+        .expect("delete_sample via mock");
+
+    let err = client
+        .delete_sample(DatasetID::from(1u64), SampleID::from(11u64))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::NotFound(id) if *id == SampleID::from(11u64).to_string()),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn delete_sample_maps_every_not_found_kind() {
+    for code in [101, 404, -32004] {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body("image.delete_from_dataset"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(rpc_error(code, "no such sample")),
+            )
+            .mount(&server)
+            .await;
+
+        let err = client_for(&server.uri())
+            .delete_sample(DatasetID::from(1u64), SampleID::from(12u64))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::NotFound(id) if *id == SampleID::from(12u64).to_string()),
+            "code {code}: {err:?}"
+        );
+    }
+
+    // Other failures keep their RPC error.
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("image.delete_from_dataset"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(403, "forbidden")))
+        .mount(&server)
+        .await;
+    let err = client_for(&server.uri())
+        .delete_sample(DatasetID::from(1u64), SampleID::from(12u64))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::RpcError {
+                kind: RpcErrorKind::Forbidden,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::label_histogram`
+// ---------------------------------------------------------------------------
+
+/// Three frames with three `dog` boxes, one unlabeled box and no `cat`.
+async fn mount_label_histogram_set(server: &MockServer) {
+    let boxed = |label: Option<&str>| json!({ "label_name": label, "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 });
+    mount_paged_annotation_set(
+        server,
+        json!({
+            "samples": [
+                { "id": 1, "image_name": "a.jpg", "annotations": [boxed(Some("dog")), boxed(None)] },
+                { "id": 2, "image_name": "b.jpg", "annotations": [boxed(Some("dog")), boxed(Some("dog"))] },
+            ],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{ "id": 3, "image_name": "c.jpg", "annotations": [] }],
+            "continue_token": null
+        }))),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn label_histogram_counts_each_label_including_unused_ones() {
+    let server = MockServer::start().await;
+    mount_label_histogram_set(&server).await;
+
+    let histogram = client_for(&server.uri())
+        .label_histogram(DatasetID::from(1u64), AnnotationSetID::from(7u64))
+        .await
+        .unwrap();
+    assert_eq!(histogram, [("dog".to_string(), 3), ("cat".to_string(), 0)]);
+}
+
+#[cfg(feature = "polars")]
+#[tokio::test]
+async fn label_histogram_dataframe_matches_label_histogram() {
+    let server = MockServer::start().await;
+    mount_label_histogram_set(&server).await;
+
+    let client = client_for(&server.uri());
+    let (dataset, set) = (DatasetID::from(1u64), AnnotationSetID::from(7u64));
+    let df = client
+        .label_histogram_dataframe(dataset, set)
+        .await
+        .unwrap();
+    assert_eq!(df.get_column_names(), ["label", "count"]);
+
+    let labels = df.column("label").unwrap().str().unwrap();
+    let counts = df.column("count").unwrap().u64().unwrap();
+    let rows: Vec<(String, u64)> = (0..df.height())
+        .map(|i| (labels.get(i).unwrap().to_string(), counts.get(i).unwrap()))
+        .collect();
+    assert_eq!(rows, client.label_histogram(dataset, set).await.unwrap());
+}
+
+// ---------------------------------------------------------------------------
+// `Client::update_sample`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn update_sample_sends_only_the_fields_that_are_set() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.update"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.update",
+            "params": { "sample_id": 11 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(101, "image not found")))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let mut sample = edgefirst_client::Sample::new();
+    sample.group = Some("val".to_string());
+    sample.frame_number = Some(3);
+    let err = client.update_sample(&sample).await.unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    sample.id = Some(SampleID::from(10u64));
+    client.update_sample(&sample).await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(
+        body["params"],
+        json!({ "sample_id": 10, "group": "val", "frame_number": 3 })
+    );
+
+    // Nothing to change: no request.
+    client
+        .update_sample(&edgefirst_client::Sample {
+            id: Some(SampleID::from(10u64)),
+            ..edgefirst_client::Sample::new()
+        })
+        .await
+        .unwrap();
+    assert_eq!(request_count(&server).await, 1);
+
+    sample.id = Some(SampleID::from(11u64));
+    let err = client.update_sample(&sample).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::NotFound(id) if *id == SampleID::from(11u64).to_string()),
+        "{err:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::download_snapshot` status check
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn download_snapshot_rejects_snapshot_still_processing() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("snapshots.get"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 5,
+            "description": "drive",
+            "status": "pending",
+            "path": "",
+            "date": "2026-01-01T00:00:00Z"
+        }))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("snapshots.create_download_url"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({}))))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("snapshot");
+    let err = client_for(&server.uri())
+        .download_snapshot(
+            edgefirst_client::SnapshotID::from(5u64),
+            output.clone(),
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::SnapshotNotReady { status } if status == "pending"),
+        "{err:?}"
+    );
+    assert!(!output.exists());
+}
+
+// ---------------------------------------------------------------------------
+// Server-rotated tokens
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct TokenRefreshObserver {
+    tokens: std::sync::Mutex<Vec<String>>,
+}
+
+impl edgefirst_client::ClientObserver for TokenRefreshObserver {
+    fn on_token_refresh(&self, token: &str) {
+        self.tokens.lock().unwrap().push(token.to_string());
+    }
+}
+
+#[tokio::test]
+async fn refreshed_token_header_replaces_and_persists_token() {
+    use edgefirst_client::{MemoryTokenStorage, TokenStorage};
+
+    let server = MockServer::start().await;
+    let original = fake_jwt();
+    let (claims, _) = original.rsplit_once('.').unwrap();
+    let rotated = format!("{claims}.{}", b64(b"rotated"));
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(header(
+            "Authorization",
+            format!("Bearer {original}").as_str(),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-Refreshed-Token", rotated.as_str())
+                .set_body_json(rpc_result(json!([]))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    // The server keeps announcing the current token; that is not a rotation.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(header(
+            "Authorization",
+            format!("Bearer {rotated}").as_str(),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-Refreshed-Token", rotated.as_str())
+                .set_body_json(rpc_result(json!([]))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let storage = std::sync::Arc::new(MemoryTokenStorage::new());
+    let observer = std::sync::Arc::new(TokenRefreshObserver::default());
+    let client = Client::new()
+        .unwrap()
+        .with_storage(storage.clone())
+        .with_token(&original)
+        .unwrap()
+        .with_url(&server.uri())
+        .unwrap()
+        .with_observer(observer.clone());
+
+    client.projects(None, None, false).await.unwrap();
+    assert_eq!(client.token().await, rotated);
+    assert_eq!(storage.load().unwrap(), Some(rotated.clone()));
+
+    // The next request authenticates with the rotated token.
+    client.projects(None, None, false).await.unwrap();
+    assert_eq!(*observer.tokens.lock().unwrap(), [rotated]);
+}
+
+#[tokio::test]
+async fn refreshed_token_for_another_server_or_malformed_is_ignored() {
+    let server = MockServer::start().await;
+    let original = fake_jwt();
+    let foreign = format!(
+        "{}.{}.{}",
+        b64(b"{\"alg\":\"none\",\"typ\":\"JWT\"}"),
+        b64(b"{\"server\":\"https://elsewhere.example.com\",\"exp\":2000000000}"),
+        b64(b"signature")
+    );
+    for token in [foreign.as_str(), "not-a-jwt"] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Refreshed-Token", token)
+                    .set_body_json(rpc_result(json!([]))),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+    }
+
+    let observer = std::sync::Arc::new(TokenRefreshObserver::default());
+    let client = client_for(&server.uri()).with_observer(observer.clone());
+    for _ in 0..2 {
+        client.projects(None, None, false).await.unwrap();
+        assert_eq!(client.token().await, original);
+        assert_eq!(client.url(), server.uri());
+    }
+    assert!(observer.tokens.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn refreshed_token_header_is_adopted_from_downloads() {
+    let server = MockServer::start().await;
+    let original = fake_jwt();
+    let (claims, _) = original.rsplit_once('.').unwrap();
+    let rotated = format!("{claims}.{}", b64(b"rotated"));
+    let download_rotated = format!("{claims}.{}", b64(b"download"));
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("task.data.download"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-Refreshed-Token", rotated.as_str())
+                .insert_header("content-type", "application/octet-stream")
+                .set_body_bytes(b"blob".to_vec()),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/download_model"))
+        .and(header(
+            "Authorization",
+            format!("Bearer {rotated}").as_str(),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-Refreshed-Token", download_rotated.as_str())
+                .set_body_bytes(b"model".to_vec()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let task = mock_task_info(&server, 0x42).await;
+    let client = client_for(&server.uri());
+    let tmp = tempfile::tempdir().unwrap();
+    task.download_data(
+        &client,
+        "blob.bin",
+        None,
+        &tmp.path().join("blob.bin"),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(client.token().await, rotated);
+
+    client
+        .download_artifact(
+            edgefirst_client::TrainingSessionID::from(7u64),
+            "model.tflite",
+            Some(tmp.path().join("model.tflite")),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(client.token().await, download_rotated);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::update_annotations_by_object`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn update_annotations_by_object_rewrites_only_the_objects_frames() {
+    use edgefirst_client::{AnnotationChanges, Box2d};
+
+    let server = MockServer::start().await;
+    let frame = |id: u64, objects: &[(&str, &str)]| {
+        json!({
+            "id": id,
+            "image_name": format!("track_{id:03}.jpg"),
+            "sequence_name": "track",
+            "frame_number": id,
+            "annotations": objects
+                .iter()
+                .map(|(label, object)| json!({
+                    "label_name": label,
+                    "object_reference": object,
+                    "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    };
+    mount_paged_annotation_set(
+        &server,
+        json!({
+            "samples": [
+                frame(1, &[("cat", "cat-1"), ("dog", "dog-1")]),
+                frame(2, &[("dog", "dog-1")]),
+            ],
+            "continue_token": "page-2"
+        }),
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [frame(3, &[("cat", "cat-1"), ("dog", "dog-2")])],
+            "continue_token": null
+        }))),
+    )
+    .await;
+    for (name, result) in [
+        ("annotation.bulk.del", json!("ok")),
+        ("annotation.add_bulk", json!([])),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let set = AnnotationSetID::from(7u64);
+    let moved = |sample: u64, left: f32| AnnotationChanges {
+        sample_id: Some(SampleID::from(sample)),
+        box2d: Some(Box2d::new(left, 0.5, 0.2, 0.2)),
+        ..Default::default()
+    };
+    let updated = client
+        .update_annotations_by_object(set, "cat-1", &[moved(1, 0.3), moved(3, 0.6)])
+        .await
+        .expect("update_annotations_by_object via mock");
+    assert_eq!(updated, 2);
+
+    let requests = server.received_requests().await.unwrap();
+    let bodies: Vec<serde_json::Value> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice(&r.body).ok())
+        .collect();
+    let deleted: Vec<&serde_json::Value> = bodies
+        .iter()
+        .filter(|body| body["method"] == "annotation.bulk.del")
+        .collect();
+    assert_eq!(deleted.len(), 1);
+    let mut frames: Vec<u64> = deleted[0]["params"]["image_ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_u64().unwrap())
+        .collect();
+    frames.sort();
+    assert_eq!(frames, [1, 3], "frame 2 does not hold cat-1");
+
+    let mut added: Vec<(u64, String, f64)> = bodies
+        .iter()
+        .filter(|body| body["method"] == "annotation.add_bulk")
+        .flat_map(|body| body["params"]["annotations"].as_array().cloned().unwrap())
+        .map(|a| {
+            (
+                a["image_id"].as_u64().unwrap(),
+                a["object_reference"].as_str().unwrap().to_string(),
+                (a["x"].as_f64().unwrap() * 10.0).round() / 10.0,
+            )
+        })
+        .collect();
+    added.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(
+        added,
+        [
+            (1, "cat-1".to_string(), 0.3),
+            (1, "dog-1".to_string(), 0.1),
+            (3, "cat-1".to_string(), 0.6),
+            (3, "dog-2".to_string(), 0.1),
+        ]
+    );
+
+    let err = client
+        .update_annotations_by_object(set, "moose-9", &[moved(1, 0.3)])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound(id) if id == "moose-9"));
+}
+
+#[tokio::test]
+async fn update_annotations_by_object_restores_frames_when_the_add_fails() {
+    use edgefirst_client::AnnotationChanges;
+
+    let server = MockServer::start().await;
+    let frame = json!({
+        "id": 1,
+        "image_name": "track_001.jpg",
+        "annotations": [
+            {
+                "label_name": "cat", "object_reference": "cat-1",
+                "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2,
+            },
+            {
+                "label_name": "dog", "object_reference": "dog-1",
+                "x": 0.5, "y": 0.5, "w": 0.2, "h": 0.2,
+                "attributes": { "occluded": true },
+            },
+        ],
+    });
+    mount_paged_annotation_set(
+        &server,
+        json!({ "samples": [frame], "continue_token": null }),
+        ResponseTemplate::new(200),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("annotation.bulk.del"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("annotation.add_bulk"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_error(500, "add_bulk failed")))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("annotation.add_bulk"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let relabel = AnnotationChanges {
+        label: Some("dog".to_string()),
+        ..Default::default()
+    };
+    client
+        .update_annotations_by_object(AnnotationSetID::from(7u64), "cat-1", &[relabel])
+        .await
+        .unwrap_err();
+
+    let requests = server.received_requests().await.unwrap();
+    let bodies: Vec<serde_json::Value> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice(&r.body).ok())
+        .collect();
+    let writes: Vec<&str> = bodies
+        .iter()
+        .filter_map(|body| body["method"].as_str())
+        .filter(|method| method.starts_with("annotation."))
+        .collect();
+    assert_eq!(
+        writes,
+        [
+            "annotation.bulk.del",
+            "annotation.add_bulk",
+            "annotation.bulk.del",
+            "annotation.add_bulk",
+        ]
+    );
+
+    // The last add puts back the original labels, with their attributes.
+    let restored = bodies
+        .iter()
+        .rfind(|body| body["method"] == "annotation.add_bulk")
+        .unwrap()["params"]["annotations"]
+        .as_array()
+        .cloned()
+        .unwrap();
+    let labels: Vec<(&str, &serde_json::Value)> = restored
+        .iter()
+        .map(|a| (a["label_name"].as_str().unwrap(), &a["attributes"]))
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            ("cat", &serde_json::Value::Null),
+            ("dog", &json!({ "occluded": true })),
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// `Client::export_index`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn export_index_writes_a_row_per_sample_with_file_urls() {
+    let server = MockServer::start().await;
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 3 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [
+                    {
+                        "id": 1,
+                        "uuid": "u-1",
+                        "group_name": "train",
+                        "image_name": "a.jpg",
+                        "image_url": "https://bucket/a.jpg?sig=1",
+                        "sensors": [{ "lidar.pcd": "https://bucket/a.pcd?sig=1" }],
+                    },
+                    { "id": 2, "uuid": "u-2", "image_name": "b, \"copy\".jpg" },
+                    { "id": 3, "uuid": "u-3", "group_name": "val", "image_name": "c.jpg" },
+                ],
+                "continue_token": null
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+
+    let client = client_for(&server.uri());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index.csv");
+    let rows = client
+        .export_index(DatasetID::from(1u64), &path, None)
+        .await
+        .expect("export_index via mock");
+    assert_eq!(rows, 3);
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), rows + 1, "header plus one row per sample");
+    assert_eq!(
+        lines[0],
+        "sample_id,uuid,group,image_name,image_url,lidar.pcd_url,lidar.depth_url,\
+         lidar.reflect_url,radar.pcd_url,radar.png_url"
+    );
+    assert_eq!(
+        lines[1],
+        format!(
+            "{},u-1,train,a.jpg,https://bucket/a.jpg?sig=1,https://bucket/a.pcd?sig=1,,,,",
+            SampleID::from(1u64)
+        )
+    );
+    assert!(
+        lines[2].contains(",\"b, \"\"copy\"\".jpg\","),
+        "{}",
+        lines[2]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Paged listings (`Page` / `PagedResult`)
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn projects_page_windows_the_sorted_listing() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(rpc_method_body("project.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "id": 2, "name": "beta", "description": "" },
+            { "id": 3, "name": "Alpha", "description": "" },
+            { "id": 1, "name": "gamma", "description": "" },
+            { "id": 4, "name": "delta", "description": "" },
+            { "id": 5, "name": "epsilon", "description": "" },
+        ]))))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let first = client
+        .projects_page(None, Some(SortKey::Name), false, Page::new(2, 0))
+        .await
+        .expect("projects_page via mock");
+    let names: Vec<&str> = first.items.iter().map(|p| p.name()).collect();
+    assert_eq!(names, ["Alpha", "beta"]);
+    assert_eq!((first.total, first.next_offset), (5, Some(2)));
+    assert!(first.has_more());
+
+    let last = client
+        .projects_page(None, Some(SortKey::Name), false, Page::new(2, 4))
+        .await
+        .unwrap();
+    let names: Vec<&str> = last.items.iter().map(|p| p.name()).collect();
+    assert_eq!(names, ["gamma"]);
+    assert_eq!((last.total, last.next_offset), (5, None));
+    assert!(!last.has_more());
+
+    let past_end = client
+        .projects_page(None, None, false, Page::new(2, 10))
+        .await
+        .unwrap();
+    assert!(past_end.items.is_empty());
+    assert_eq!((past_end.total, past_end.next_offset), (5, None));
+}
+
+// ---------------------------------------------------------------------------
+// `Client::create_experiment` / `Client::delete_experiment`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn create_experiment_returns_the_new_experiment() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.create",
+            "params": { "project_id": 7, "name": "detector", "description": "" }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "id": 12 }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.get",
+            "params": { "trainer_id": 12 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "id": 12, "project_id": 7, "name": "detector", "description": ""
+        }))))
+        .mount(&server)
+        .await;
+
+    let experiment = client_for(&server.uri())
+        .create_experiment(ProjectID::from(7u64), "detector", None)
+        .await
+        .expect("create_experiment via mock");
+    assert_eq!(experiment.id(), ExperimentID::from(12u64));
+    assert_eq!(experiment.name(), "detector");
+}
+
+#[tokio::test]
+async fn create_experiment_reports_duplicate_name_as_invalid_parameters() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("trainer.create"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_error(3, "experiment detector already exists")),
+        )
+        .mount(&server)
+        .await;
+
+    let Err(err) = client_for(&server.uri())
+        .create_experiment(ProjectID::from(7u64), "detector", Some("again"))
+        .await
+    else {
+        panic!("duplicate experiment was created");
+    };
+    assert!(
+        matches!(&err, Error::InvalidParameters(msg) if msg.contains("already exists")),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn delete_experiment_maps_missing_experiment_to_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.delete",
+            "params": { "trainer_id": 12 }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!(true))))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.delete",
+            "params": { "trainer_id": 13 }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_error(101, "trainer not found in DB")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.delete",
+            "params": { "trainer_id": 14 }
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_error(-32004, "trainer not found")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    client
+        .delete_experiment(ExperimentID::from(12u64))
+        .await
+        .expect("delete_experiment via mock");
+    for id in [13u64, 14] {
+        let err = client
+            .delete_experiment(ExperimentID::from(id))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::NotFound(missing) if *missing == ExperimentID::from(id).to_string()),
+            "{err:?}"
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// `Client::presign_uploads`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn presign_uploads_returns_a_url_per_requested_file() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.presign",
+            "params": {
+                "dataset_id": 5,
+                "files": [
+                    { "filename": "a.jpg", "type": "image" },
+                    { "filename": "a.pcd", "type": "lidar.pcd" },
+                ]
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([
+            { "filename": "a.jpg", "key": "ds-5/a.jpg", "url": "https://bucket/ds-5/a.jpg?sig=1" },
+            { "filename": "a.pcd", "key": "ds-5/a.pcd", "url": "https://bucket/ds-5/a.pcd?sig=2" },
+        ]))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let files = [
+        ("a.jpg".to_string(), edgefirst_client::FileType::Image),
+        ("a.pcd".to_string(), edgefirst_client::FileType::LidarPcd),
+    ];
+    let urls = client
+        .presign_uploads(DatasetID::from(5u64), &files)
+        .await
+        .expect("presign_uploads via mock");
+    assert_eq!(urls.len(), files.len());
+    for ((filename, _), url) in files.iter().zip(&urls) {
+        assert_eq!(&url.filename, filename);
+        assert_eq!(url.key, format!("ds-5/{filename}"));
+        assert!(url.url.starts_with("https://bucket/ds-5/"), "{}", url.url);
+    }
+
+    let err = client
+        .presign_uploads(DatasetID::from(5u64), &[])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidParameters(_)), "{err:?}");
+    let err = client
+        .presign_uploads(
+            DatasetID::from(5u64),
+            &[("a".to_string(), edgefirst_client::FileType::All)],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidFileType(_)), "{err:?}");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::clone_dataset`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn clone_dataset_reuploads_files_and_copies_annotations() {
+    let server = MockServer::start().await;
+    let rpc = |name: &str, params: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(body_partial_json(
+                json!({ "method": name, "params": params }),
+            ))
+    };
+    let ok =
+        |result: serde_json::Value| ResponseTemplate::new(200).set_body_json(rpc_result(result));
+
+    rpc("dataset.get", json!({ "dataset_id": 1 }))
+        .respond_with(ok(json!({
+            "id": 1, "project_id": 1, "name": "source", "description": "",
+            "cloud_key": "k", "createdAt": "2026-01-01T00:00:00Z"
+        })))
+        .mount(&server)
+        .await;
+    rpc(
+        "dataset.create",
+        json!({ "project_id": "p-1", "name": "copy" }),
+    )
+    .respond_with(ok(json!({ "id": 2 })))
+    .expect(1)
+    .mount(&server)
+    .await;
+    rpc("label.list", json!({}))
+        .respond_with(ok(json!([{ "id": 10, "index": 0, "name": "deer" }])))
+        .mount(&server)
+        .await;
+    rpc("label.add2", json!({}))
+        .respond_with(ok(json!("ok")))
+        .mount(&server)
+        .await;
+    rpc("annset.list", json!({ "dataset_id": 1 }))
+        .respond_with(ok(json!([
+            { "id": 20, "name": "Default", "description": "" },
+            { "id": 21, "name": "Review", "description": "" },
+        ])))
+        .mount(&server)
+        .await;
+    rpc("annset.list", json!({ "dataset_id": 2 }))
+        .respond_with(ok(json!([
+            { "id": 40, "name": "Default", "description": "" },
+            { "id": 41, "name": "Review", "description": "" },
+        ])))
+        .mount(&server)
+        .await;
+    rpc("annset.get", json!({ "annotation_set_id": 41 }))
+        .respond_with(ok(
+            json!({ "id": 41, "dataset_id": 2, "name": "Review", "description": "" }),
+        ))
+        .mount(&server)
+        .await;
+    rpc("samples.count", json!({}))
+        .respond_with(ok(json!({ "total": 2 })))
+        .mount(&server)
+        .await;
+
+    // Two frames share an image name in different sequences.
+    let uri = server.uri();
+    let source = move |sequence: &str, id: u64, annotation: serde_json::Value| {
+        json!({
+            "id": id,
+            "image_name": "a.jpg",
+            "sequence_name": sequence,
+            "frame_number": 1,
+            "image_url": format!("{uri}/src/{sequence}/a.jpg"),
+            "width": 640,
+            "height": 480,
+            "sensors": [{ "lidar.pcd": format!("{uri}/src/{sequence}/a.pcd") }],
+            "annotations": [annotation],
+        })
+    };
+    let deer = |x: f64| json!({ "label_name": "deer", "x": x, "y": 0.1, "w": 0.2, "h": 0.2 });
+    let mut pose = deer(0.1);
+    pose["keypoints"] = json!([[0.15, 0.15, 2], [0.2, 0.2, 1]]);
+    rpc(
+        "samples.list",
+        json!({ "dataset_id": 1, "annotation_set_id": 20 }),
+    )
+    .respond_with(ok(json!({
+        "samples": [source("left", 1, pose), source("right", 2, deer(0.5))],
+        "continue_token": null
+    })))
+    .mount(&server)
+    .await;
+    rpc(
+        "samples.list",
+        json!({ "dataset_id": 1, "annotation_set_id": 21 }),
+    )
+    .respond_with(ok(json!({
+        "samples": [source("left", 1, deer(0.3)), source("right", 2, deer(0.7))],
+        "continue_token": null
+    })))
+    .mount(&server)
+    .await;
+    for sequence in ["left", "right"] {
+        for (file, body) in [("a.jpg", "jpeg"), ("a.pcd", "pcd")] {
+            Mock::given(method("GET"))
+                .and(path(format!("/src/{sequence}/{file}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+                .mount(&server)
+                .await;
+        }
+    }
+
+    // Hand out an upload URL for every file the copied samples name, and
+    // list the copies back under the UUIDs they were populated with.
+    let populated = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let uri = server.uri();
+    let seen = populated.clone();
+    rpc(
+        "samples.populate2",
+        json!({ "dataset_id": 2, "annotation_set_id": 40 }),
+    )
+    .respond_with(move |req: &wiremock::Request| {
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+        let results: Vec<_> = body["params"]["samples"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sample| {
+                seen.lock().unwrap().push(sample.clone());
+                let urls: Vec<_> = sample["files"]
+                    .as_object()
+                    .unwrap()
+                    .values()
+                    .map(|name| {
+                        let name = name.as_str().unwrap();
+                        json!({ "filename": name, "key": name, "url": format!("{uri}/dst/{name}") })
+                    })
+                    .collect();
+                json!({ "uuid": sample["uuid"], "urls": urls })
+            })
+            .collect();
+        ResponseTemplate::new(200).set_body_json(rpc_result(json!(results)))
+    })
+    .expect(1)
+    .mount(&server)
+    .await;
+    let listed = populated.clone();
+    rpc("samples.list", json!({ "dataset_id": 2 }))
+        .respond_with(move |_: &wiremock::Request| {
+            let samples: Vec<_> = listed
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|sample| {
+                    let id = if sample["sequence_name"] == "left" {
+                        100
+                    } else {
+                        101
+                    };
+                    json!({ "id": id, "uuid": sample["uuid"], "image_name": "a.jpg" })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(rpc_result(
+                json!({ "samples": samples, "continue_token": null }),
+            ))
+        })
+        .mount(&server)
+        .await;
+    for (file, body) in [("a.jpg", "jpeg"), ("a.lidar.pcd", "pcd")] {
+        Mock::given(method("PUT"))
+            .and(path(format!("/dst/{file}")))
+            .and(body_bytes(body.as_bytes().to_vec()))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+    }
+    rpc("annotation.add_bulk", json!({ "annotation_set_id": 41 }))
+        .respond_with(ok(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let id = client
+        .clone_dataset(DatasetID::from(1u64), "copy", true, Some(tx))
+        .await
+        .expect("clone_dataset via mock");
+    assert_eq!(id, DatasetID::from(2u64));
+
+    let progress = rx.recv().await.expect("progress event");
+    assert_eq!((progress.current, progress.total), (2, 2));
+
+    // The first set travels with the samples, keypoints included.
+    let populated = populated.lock().unwrap().clone();
+    assert_eq!(populated.len(), 2);
+    assert_eq!(
+        populated[0]["annotations"][0]["keypoints"],
+        json!([[0.15, 0.15, 2], [0.2, 0.2, 1]])
+    );
+    assert_eq!(populated[1]["annotations"][0]["box2d"]["x"], 0.5);
+
+    // The other set finds each copy by UUID, not by the shared image name.
+    let requests = server.received_requests().await.unwrap_or_default();
+    let mut sent: Vec<(u64, f64)> = requests
+        .iter()
+        .filter_map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).ok())
+        .filter(|body| body["method"] == "annotation.add_bulk")
+        .flat_map(|body| body["params"]["annotations"].as_array().cloned().unwrap())
+        .map(|a| {
+            let x = (a["x"].as_f64().unwrap() * 10.0).round() / 10.0;
+            (a["image_id"].as_u64().unwrap(), x)
+        })
+        .collect();
+    sent.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(sent, [(100, 0.3), (101, 0.7)]);
+}
+
+// ---------------------------------------------------------------------------
+// `Client::merge_labels`
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn merge_labels_relabels_annotations_and_compacts_indices() {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    let server = MockServer::start().await;
+    let rpc = |name: &str| {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+    };
+    let ok =
+        |result: serde_json::Value| ResponseTemplate::new(200).set_body_json(rpc_result(result));
+
+    // "dog" disappears from the listing once it has been deleted.
+    let deleted = Arc::new(AtomicBool::new(false));
+    let listed = deleted.clone();
+    rpc("label.list")
+        .respond_with(move |_: &wiremock::Request| {
+            let mut labels = vec![
+                json!({ "id": 1, "index": 0, "name": "cat" }),
+                json!({ "id": 3, "index": 2, "name": "elk" }),
+                json!({ "id": 4, "index": 3, "name": "moose" }),
+            ];
+            if !listed.load(Ordering::SeqCst) {
+                labels.push(json!({ "id": 2, "index": 1, "name": "dog" }));
+            }
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!(labels)))
+        })
+        .mount(&server)
+        .await;
+    rpc("label.del")
+        .respond_with(move |_: &wiremock::Request| {
+            deleted.store(true, Ordering::SeqCst);
+            ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok")))
+        })
+        .mount(&server)
+        .await;
+    rpc("annset.list")
+        .respond_with(ok(
+            json!([{ "id": 7, "name": "Default", "description": "" }]),
+        ))
+        .mount(&server)
+        .await;
+    rpc("annset.get")
+        .respond_with(ok(
+            json!({ "id": 7, "dataset_id": 1, "name": "Default", "description": "" }),
+        ))
+        .mount(&server)
+        .await;
+    rpc("samples.count")
+        .respond_with(ok(json!({ "total": 2 })))
+        .mount(&server)
+        .await;
+    let ann = |label: &str| json!({ "label_name": label, "x": 0.1, "y": 0.1, "w": 0.2, "h": 0.2 });
+    rpc("samples.list")
+        .respond_with(ok(json!({
+            "samples": [
+                { "id": 1, "image_name": "a.jpg", "annotations": [ann("cat"), ann("dog")] },
+                { "id": 2, "image_name": "b.jpg", "annotations": [ann("elk")] },
+            ],
+            "continue_token": null
+        })))
+        .mount(&server)
+        .await;
+    for name in ["annotation.bulk.del", "label.update"] {
+        rpc(name).respond_with(ok(json!("ok"))).mount(&server).await;
+    }
+    rpc("annotation.add_bulk")
+        .respond_with(ok(json!([])))
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri());
+    let dataset = DatasetID::from(1u64);
+
+    // Merging a label into itself and unknown labels change nothing.
+    client.merge_labels(dataset, &[1], 1).await.unwrap();
+    let err = client.merge_labels(dataset, &[99], 1).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::MissingLabel(id) if id == "99"),
+        "{err:?}"
+    );
+    let err = client.merge_labels(dataset, &[2], 98).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::MissingLabel(id) if id == "98"),
+        "{err:?}"
+    );
+    let methods = |bodies: &[serde_json::Value]| -> Vec<String> {
+        bodies
+            .iter()
+            .filter_map(|body| body["method"].as_str().map(str::to_owned))
+            .collect()
+    };
+    let bodies = |requests: Vec<wiremock::Request>| -> Vec<serde_json::Value> {
+        requests
+            .iter()
+            .filter_map(|r| serde_json::from_slice(&r.body).ok())
+            .collect()
+    };
+    let before = bodies(server.received_requests().await.unwrap());
+    assert!(
+        methods(&before).iter().all(|m| m == "label.list"),
+        "{:?}",
+        methods(&before)
+    );
+
+    client
+        .merge_labels(dataset, &[2], 1)
+        .await
+        .expect("merge_labels via mock");
+    let sent = bodies(server.received_requests().await.unwrap());
+    let with = |name: &str| -> Vec<&serde_json::Value> {
+        sent.iter()
+            .filter(|body| body["method"] == name)
+            .map(|body| &body["params"])
+            .collect()
+    };
+
+    let deleted_frames = with("annotation.bulk.del");
+    assert_eq!(deleted_frames.len(), 1);
+    assert_eq!(
+        deleted_frames[0]["image_ids"],
+        json!([1]),
+        "b.jpg has no dog"
+    );
+    let added: Vec<(u64, u64)> = with("annotation.add_bulk")
+        .iter()
+        .flat_map(|params| params["annotations"].as_array().unwrap().clone())
+        .map(|a| {
+            (
+                a["image_id"].as_u64().unwrap(),
+                a["label_id"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(added, [(1, 1), (1, 1)]);
+    assert_eq!(with("label.del"), [&json!({ "label_id": 2 })]);
+
+    // The last update of each label leaves the indices contiguous.
+    let mut indices = std::collections::BTreeMap::new();
+    for params in with("label.update") {
+        indices.insert(
+            params["label_name"].as_str().unwrap().to_string(),
+            params["label_index"].as_u64().unwrap(),
+        );
+    }
+    assert_eq!(
+        indices.into_iter().collect::<Vec<_>>(),
+        [("elk".to_string(), 1), ("moose".to_string(), 2)]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Checksum verification of downloaded files
+// ---------------------------------------------------------------------------
+
+/// Mounts a one-image dataset whose stored image reports the SHA-256 of
+/// `stored` but serves `served`. As on S3, a presigned URL signed with
+/// `x-amz-checksum-mode` (`signed`) returns the checksum to requests
+/// carrying `x-amz-checksum-mode: ENABLED`, and one signed without it
+/// rejects such requests with 403. Unsigned-mode URLs model storage that
+/// sends the checksum unasked.
+async fn mount_checksummed_image(server: &MockServer, stored: &[u8], served: &[u8], signed: bool) {
+    use sha2::{Digest, Sha256};
+
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("label.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!([]))))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({ "total": 1 }))))
+        .mount(server)
+        .await;
+    let signed_headers = if signed {
+        "host%3Bx-amz-checksum-mode"
+    } else {
+        "host"
+    };
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("samples.list"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "samples": [{
+                "id": 10,
+                "image_name": "frame.jpg",
+                "image_url": format!(
+                    "{}/files/image?X-Amz-SignedHeaders={signed_headers}&X-Amz-Signature=abc",
+                    server.uri()
+                ),
+            }],
+            "continue_token": null
+        }))))
+        .mount(server)
+        .await;
+    let with_checksum = ResponseTemplate::new(200)
+        .insert_header(
+            "x-amz-checksum-sha256",
+            base64::engine::general_purpose::STANDARD.encode(Sha256::digest(stored)),
+        )
+        .set_body_bytes(served.to_vec());
+    let (asked, unasked) = if signed {
+        (
+            with_checksum,
+            ResponseTemplate::new(200).set_body_bytes(served.to_vec()),
+        )
+    } else {
+        (ResponseTemplate::new(403), with_checksum)
+    };
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .and(header("x-amz-checksum-mode", "ENABLED"))
+        .respond_with(asked)
+        .with_priority(1)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files/image"))
+        .respond_with(unasked)
+        .mount(server)
+        .await;
+}
+
+/// Whether each download of the mounted image asked for its checksum.
+async fn checksum_modes(server: &MockServer) -> Vec<bool> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/files/image")
+        .map(|r| {
+            r.headers
+                .get("x-amz-checksum-mode")
+                .is_some_and(|v| v == "ENABLED")
+        })
+        .collect()
+}
+
+async fn download_checksummed_image(
+    server: &MockServer,
+    dir: &std::path::Path,
+    verify_checksums: bool,
+) -> Result<(), Error> {
+    client_for(&server.uri())
+        .download_dataset_with_file_events(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.to_path_buf(),
+            false,
+            None,
+            None,
+            None,
+            DownloadOptions {
+                verify_checksums,
+                ..Default::default()
+            },
+        )
+        .await
+}
+
+#[tokio::test]
+async fn download_dataset_retries_then_rejects_checksum_mismatch() {
+    let server = MockServer::start().await;
+    mount_checksummed_image(&server, b"original", b"corrupted", true).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = download_checksummed_image(&server, dir.path(), true)
+        .await
+        .expect_err("corrupted file should fail verification");
+    assert!(
+        matches!(&err, Error::ChecksumMismatch { expected, actual }
+            if expected.starts_with("sha256:") && actual.starts_with("sha256:") && expected != actual),
+        "{err:?}"
+    );
+    let fetches = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|r| r.url.path() == "/files/image")
+        .count();
+    assert_eq!(fetches, 2, "a mismatch is downloaded once more");
+    assert!(!dir.path().join("frame.jpg").exists());
+}
+
+#[tokio::test]
+async fn download_dataset_checks_matching_checksums_and_can_skip_them() {
+    let server = MockServer::start().await;
+    mount_checksummed_image(&server, b"original", b"original", true).await;
+    let dir = tempfile::tempdir().unwrap();
+    download_checksummed_image(&server, dir.path(), true)
+        .await
+        .expect("matching checksum");
+    assert_eq!(
+        std::fs::read(dir.path().join("frame.jpg")).unwrap(),
+        b"original"
+    );
+    assert_eq!(checksum_modes(&server).await, vec![true]);
+
+    let server = MockServer::start().await;
+    mount_checksummed_image(&server, b"original", b"corrupted", true).await;
+    let dir = tempfile::tempdir().unwrap();
+    download_checksummed_image(&server, dir.path(), false)
+        .await
+        .expect("verification disabled");
+    assert_eq!(
+        std::fs::read(dir.path().join("frame.jpg")).unwrap(),
+        b"corrupted"
+    );
+    assert_eq!(checksum_modes(&server).await, vec![false]);
+}
+
+#[tokio::test]
+async fn download_dataset_only_asks_for_checksums_on_urls_signed_for_it() {
+    let server = MockServer::start().await;
+    mount_checksummed_image(&server, b"original", b"corrupted", false).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    // The header would be rejected, so it is not sent; the checksum storage
+    // sends unasked is still checked.
+    let err = download_checksummed_image(&server, dir.path(), true)
+        .await
+        .expect_err("corrupted file should fail verification");
+    assert!(matches!(err, Error::ChecksumMismatch { .. }), "{err:?}");
+    assert_eq!(checksum_modes(&server).await, vec![false, false]);
+}
+
+#[tokio::test]
+async fn download_dataset_verifies_checksums_by_default() {
+    let server = MockServer::start().await;
+    mount_checksummed_image(&server, b"original", b"corrupted", true).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = client_for(&server.uri())
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+        .expect_err("the default options should verify checksums");
+    assert!(matches!(err, Error::ChecksumMismatch { .. }), "{err:?}");
+}
+
+// ---------------------------------------------------------------------------
+// `Client::download_dataset` pipelining
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn download_dataset_starts_files_before_listing_completes() {
+    use std::time::{Duration, Instant};
+
+    let server = MockServer::start().await;
+    let page_delay = Duration::from_millis(500);
+    let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::<(String, Instant)>::new()));
+    let record = |seen: &std::sync::Arc<std::sync::Mutex<Vec<(String, Instant)>>>, what: &str| {
+        seen.lock()
+            .unwrap()
+            .push((what.to_string(), Instant::now()));
+    };
+
+    for (name, result) in [
+        ("label.list", json!([])),
+        ("samples.count", json!({ "total": 2 })),
+        (
+            "samples.list",
+            json!({
+                "samples": [{
+                    "id": 1,
+                    "image_name": "a.jpg",
+                    "image_url": format!("{}/files/a", server.uri()),
+                }],
+                "continue_token": "page-2"
+            }),
+        ),
+    ] {
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .and(rpc_method_body(name))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(result)))
+            .mount(&server)
+            .await;
+    }
+    let page_2 = json!({
+        "samples": [{
+            "id": 2,
+            "image_name": "b.jpg",
+            "image_url": format!("{}/files/b", server.uri()),
+        }],
+        "continue_token": null
+    });
+    let log = seen.clone();
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "samples.list",
+            "params": { "continue_token": "page-2" }
+        })))
+        .respond_with(move |_: &wiremock::Request| {
+            record(&log, "page-2");
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(page_2.clone()))
+                .set_delay(page_delay)
+        })
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    let log = seen.clone();
+    Mock::given(method("GET"))
+        .and(path_regex("^/files/"))
+        .respond_with(move |req: &wiremock::Request| {
+            record(&log, req.url.path());
+            ResponseTemplate::new(200).set_body_bytes(b"jpeg".to_vec())
+        })
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    client_for(&server.uri())
+        .download_dataset(
+            DatasetID::from(1u64),
+            &[],
+            &[edgefirst_client::FileType::Image],
+            dir.path().to_path_buf(),
+            false,
+            None,
+            None,
+            DownloadOptions::default(),
+        )
+        .await
+        .expect("download should succeed");
+    assert!(dir.path().join("a.jpg").exists());
+    assert!(dir.path().join("b.jpg").exists());
+
+    let seen = seen.lock().unwrap();
+    let at = |what: &str| seen.iter().find(|(w, _)| w == what).unwrap().1;
+    assert!(
+        at("/files/a") < at("page-2") + page_delay,
+        "the first page's file should download while the second page is listed: {seen:?}"
+    );
+    assert!(at("/files/b") >= at("page-2") + page_delay);
+}
+
+// ----------------------------------------------------------------------------
+// Upload compression
+// ----------------------------------------------------------------------------
+
+/// Splits a multipart/form-data request into `(part headers, part body)`.
+fn multipart_parts(req: &wiremock::Request) -> Vec<(String, Vec<u8>)> {
+    let content_type = req.headers.get("content-type").unwrap().to_str().unwrap();
+    let boundary = format!("--{}", content_type.split("boundary=").nth(1).unwrap());
+    let body = &req.body;
+    let starts: Vec<usize> = (0..body.len())
+        .filter(|&i| body[i..].starts_with(boundary.as_bytes()))
+        .collect();
+    starts
+        .windows(2)
+        .map(|pair| {
+            let part = &body[pair[0] + boundary.len() + 2..pair[1] - 2];
+            let split = part.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+            (
+                String::from_utf8_lossy(&part[..split]).to_lowercase(),
+                part[split + 4..].to_vec(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn upload_compression_gzips_text_parts_only() {
+    use std::io::Read as _;
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(query_param("method", "task.data.upload"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "message": "ok",
+            "path": "/",
+            "size": 0,
+        }))))
+        .mount(&server)
+        .await;
+
+    let task = mock_task_info(&server, 0x42).await;
+    let client = client_for(&server.uri())
+        .with_upload_compression(Some(6))
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("train.log");
+    let text = "epoch 1/10 loss=0.4821 mAP=0.312\n".repeat(200);
+    std::fs::write(&log, &text).unwrap();
+    let weights = dir.path().join("model.tflite");
+    std::fs::write(&weights, b"TFL3 binary").unwrap();
+
+    task.upload_data(&client, &log, None, None).await.unwrap();
+    task.upload_data(&client, &weights, None, None)
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let uploads: Vec<_> = requests
+        .iter()
+        .filter(|req| req.url.query() == Some("method=task.data.upload"))
+        .map(multipart_parts)
+        .collect();
+    assert_eq!(uploads.len(), 2);
+
+    let (headers, body) = uploads[0]
+        .iter()
+        .find(|(headers, _)| headers.contains("filename=\"train.log\""))
+        .unwrap();
+    assert!(headers.contains("content-encoding: gzip"), "{headers}");
+    assert!(body.len() < text.len());
+    let mut decoded = String::new();
+    flate2::read::GzDecoder::new(&body[..])
+        .read_to_string(&mut decoded)
+        .unwrap();
+    assert_eq!(decoded, text);
+
+    let (headers, body) = uploads[1]
+        .iter()
+        .find(|(headers, _)| headers.contains("filename=\"model.tflite\""))
+        .unwrap();
+    assert!(!headers.contains("content-encoding"), "{headers}");
+    assert_eq!(body, b"TFL3 binary");
+
+    assert!(matches!(
+        client_for(&server.uri()).with_upload_compression(Some(10)),
+        Err(Error::InvalidParameters(_))
+    ));
+}
+
+// ----------------------------------------------------------------------------
+// Multipart uploads of training session files
+// ----------------------------------------------------------------------------
+
+#[tokio::test]
+async fn training_session_upload_sends_large_files_in_resumable_parts() {
+    const MIB: usize = 1024 * 1024;
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(
+            json!({ "method": "trainer.session.get" }),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(training_session_json(0x111, "session", ""))),
+        )
+        .mount(&server)
+        .await;
+    // The server resumes an earlier upload that already stored part 1.
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.upload.create_multipart",
+            "params": {
+                "session_id": 0x111,
+                "key": "checkpoints/best.pt",
+                "file_size": 11 * MIB,
+                "part_size": 5 * MIB,
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!({
+            "upload_id": "upload-1",
+            "urls": (1..=3)
+                .map(|n| format!("{}/parts/{}", server.uri(), n))
+                .collect::<Vec<_>>(),
+            "uploaded": [{ "ETag": "etag-1", "PartNumber": 1 }],
+        }))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(json!({
+            "method": "trainer.upload.complete_multipart",
+            "params": {
+                "session_id": 0x111,
+                "key": "checkpoints/best.pt",
+                "upload_id": "upload-1",
+                "etag_list": [
+                    { "ETag": "etag-1", "PartNumber": 1 },
+                    { "ETag": "etag-2", "PartNumber": 2 },
+                    { "ETag": "etag-3", "PartNumber": 3 },
+                ],
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(query_param("method", "trainer.upload.files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Part 2 fails once and is retried on its own.
+    Mock::given(method("PUT"))
+        .and(path("/parts/2"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path_regex("^/parts/[23]$"))
+        .respond_with(|req: &wiremock::Request| {
+            let part = req.url.path().rsplit('/').next().unwrap().to_string();
+            ResponseTemplate::new(200).insert_header("ETag", format!("\"etag-{}\"", part))
+        })
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri())
+        .with_upload_part_size(5 * MIB)
+        .unwrap();
+    let session = client
+        .training_session(TrainingSessionID::from(0x111u64))
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let weights = dir.path().join("best.pt");
+    std::fs::write(&weights, vec![7u8; 11 * MIB]).unwrap();
+    let log = dir.path().join("train.log");
+    std::fs::write(&log, b"epoch 1\n").unwrap();
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let collector = tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            let event: edgefirst_client::Progress = event;
+            events.push((event.current, event.total));
+        }
+        events
+    });
+    session
+        .upload(
+            &client,
+            &[
+                ("checkpoints/best.pt".to_string(), weights),
+                ("logs/train.log".to_string(), log),
+            ],
+            Some(tx),
+        )
+        .await
+        .unwrap();
+
+    let events = collector.await.unwrap();
+    let total = 11 * MIB + 8;
+    assert_eq!(events.last(), Some(&(total, total)));
+    // Part 1 is counted as soon as the upload resumes; the retried part 2
+    // rewinds its own bytes but never past what is confirmed.
+    assert!(
+        events
+            .iter()
+            .skip(1)
+            .all(|(current, _)| (5 * MIB + 8..=total).contains(current))
+    );
+
+    let requests = server.received_requests().await.unwrap();
+    let puts: Vec<&str> = requests
+        .iter()
+        .filter(|req| req.method == wiremock::http::Method::PUT)
+        .map(|req| req.url.path())
+        .collect();
+    assert!(!puts.contains(&"/parts/1"), "stored part re-sent: {puts:?}");
+    assert_eq!(puts.iter().filter(|p| **p == "/parts/2").count(), 2);
+    let part_3 = requests
+        .iter()
+        .find(|req| req.url.path() == "/parts/3")
+        .unwrap();
+    assert_eq!(part_3.body.len(), MIB);
+
+    assert!(matches!(
+        client.with_upload_part_size(MIB),
+        Err(Error::InvalidParameters(_))
+    ));
+}
+
+#[tokio::test]
+async fn training_session_upload_posts_large_files_when_multipart_is_unsupported() {
+    const MIB: usize = 1024 * 1024;
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(body_partial_json(
+            json!({ "method": "trainer.session.get" }),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(rpc_result(training_session_json(0x111, "session", ""))),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(rpc_method_body("trainer.upload.create_multipart"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(rpc_error(-32601, "Method not found")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api"))
+        .and(query_param("method", "trainer.upload.files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(rpc_result(json!("ok"))))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client_for(&server.uri())
+        .with_upload_part_size(5 * MIB)
+        .unwrap();
+    let session = client
+        .training_session(TrainingSessionID::from(0x111u64))
+        .await
+        .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let weights = dir.path().join("best.pt");
+    std::fs::write(&weights, vec![7u8; 6 * MIB]).unwrap();
+    let log = dir.path().join("train.log");
+    std::fs::write(&log, b"epoch 1\n").unwrap();
+
+    session
+        .upload(
+            &client,
+            &[
+                ("checkpoints/best.pt".to_string(), weights),
+                ("logs/train.log".to_string(), log),
+            ],
+            None,
+        )
+        .await
+        .expect("upload should fall back to trainer.upload.files");
+
+    let requests = server.received_requests().await.unwrap();
+    let posted: Vec<_> = requests
+        .iter()
+        .filter(|req| req.url.query() == Some("method=trainer.upload.files"))
+        .map(multipart_parts)
+        .collect();
+    let (_, body) = posted[1]
+        .iter()
+        .find(|(headers, _)| headers.contains("filename=\"checkpoints/best.pt\""))
+        .expect("large file posted after multipart was refused");
+    assert_eq!(body.len(), 6 * MIB);
+}
@@ -115,7 +115,7 @@ def main() -> None:
         annotation_set_id,
         [sample],
         progress=progress,
-    )
+    ).succeeded
     print(f"populate_samples: {len(results)} result(s), uuid={results[0].uuid}")
 
     time.sleep(2)
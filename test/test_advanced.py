@@ -223,7 +223,7 @@ class TestSequences(unittest.TestCase):
 
                 results1 = client.populate_samples(
                     dataset_id, annset_id, seq1_samples, None
-                )
+                ).succeeded
                 self.assertEqual(
                     len(results1), 10, "Should upload 10 samples for sequence 1"
                 )
@@ -251,7 +251,7 @@ class TestSequences(unittest.TestCase):
 
                 results2 = client.populate_samples(
                     dataset_id, annset_id, seq2_samples, None
-                )
+                ).succeeded
                 self.assertEqual(
                     len(results2), 10, "Should upload 10 samples for sequence 2"
                 )
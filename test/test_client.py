@@ -8,10 +8,11 @@ These tests verify the core client functionality including version checking,
 authentication token management, and organization information retrieval.
 """
 
+import asyncio
 from time import sleep
 from unittest import TestCase
 
-from edgefirst_client import Client
+from edgefirst_client import Client, SortKey
 from test import get_client
 
 
@@ -83,6 +84,40 @@ class ClientTest(TestCase):
         self.assertEqual(project.name, first_project.name)
         print(f"Retrieved project: {project.name} (ID: {project.id.value})")
 
+    def test_projects_async(self):
+        """projects_async() should return the same projects as projects()."""
+        client = get_client()
+        projects = asyncio.run(client.projects_async())
+        self.assertEqual(
+            [p.id.value for p in projects],
+            [p.id.value for p in client.projects()],
+        )
+
+    def test_projects_limit_offset(self):
+        """limit and offset should window the ordered project listing."""
+        client = get_client()
+        projects = [p.id.value for p in client.projects(sort_by=SortKey.Name)]
+        page = client.projects(sort_by=SortKey.Name, limit=1, offset=1)
+        self.assertEqual([p.id.value for p in page], projects[1:2])
+        self.assertEqual(client.projects(offset=len(projects)), [])
+
+    def test_context_manager_logs_out(self):
+        """Leaving a with block should log the client out."""
+        token = get_client().token()
+        client = Client().with_memory_storage().with_token(token)
+        with client as entered:
+            self.assertIs(entered, client)
+            self.assertNotEqual(client.token(), "")
+        self.assertEqual(client.token(), "")
+
+        async def run():
+            client = Client().with_memory_storage().with_token(token)
+            async with client as entered:
+                self.assertIs(entered, client)
+            return client.token()
+
+        self.assertEqual(asyncio.run(run()), "")
+
     def test_with_url_accepts_https(self):
         """with_url should accept an https:// URL and preserve chaining."""
         client = get_client().with_url("https://test.edgefirst.studio")
@@ -93,6 +128,35 @@ class ClientTest(TestCase):
         with self.assertRaises(Exception):
             get_client().with_url("http://example.com")
 
+    def test_with_server_url_keeps_custom_port(self):
+        """with_server_url should keep the port and report the full URL."""
+        client = Client().with_memory_storage().with_server_url(
+            "https://studio.internal.corp:8443"
+        )
+        self.assertEqual(client.url, "https://studio.internal.corp:8443")
+        self.assertEqual(client.server, "https://studio.internal.corp:8443")
+        with self.assertRaises(Exception):
+            client.with_server_url("test")
+
+    def test_with_header_rejects_authorization(self):
+        """with_header should refuse to override the Authorization header."""
+        client = Client().with_header("X-Trace-Id", "7f3c9a")
+        with self.assertRaises(Exception):
+            client.with_header("Authorization", "Bearer other")
+
+    def test_with_proxy_rejects_unsupported_scheme(self):
+        """with_proxy should accept socks5 proxies and reject other schemes."""
+        client = Client().with_proxy("socks5://proxy.corp:1080")
+        with self.assertRaises(Exception):
+            client.with_proxy("ftp://proxy.corp")
+
+    def test_with_timeout(self):
+        """with_timeout should set the request timeout and reject zero."""
+        client = Client().with_timeout(seconds=12.5)
+        self.assertEqual(client.timeout, 12.5)
+        with self.assertRaises(Exception):
+            client.with_timeout(0)
+
     def test_usage_summary(self):
         """usage_summary should return credits/funds/total as floats."""
         client = get_client()
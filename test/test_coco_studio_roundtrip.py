@@ -509,7 +509,7 @@ class TestCocoStudioRoundtrip(unittest.TestCase):
                     annotation_set_id,
                     samples,
                     progress=on_progress,
-                )
+                ).succeeded
                 print(f"   Populated {len(results)} samples")
                 print(f"   Progress updates: {len(progress_updates)}")
 
@@ -28,6 +28,7 @@ from edgefirst_client import (
     Polygon,
     Sample,
     SampleFile,
+    Severity,
 )
 from PIL import Image, ImageDraw
 from test import get_client, get_test_data_dir, skip_if_known_group_by_bug
@@ -152,7 +153,7 @@ class DatasetTest(TestCase):
         try:
             results = client.populate_samples(
                 dataset_id, annotation_set.id, [sample], progress=progress
-            )
+            ).succeeded
 
             assert len(results) == 1
             result = results[0]
@@ -245,6 +246,18 @@ class DatasetTest(TestCase):
 
             print(f"✓ Downloaded image matches original ({len(downloaded_data)} bytes)")
 
+            try:
+                import numpy  # noqa: F401
+            except ImportError:
+                print("numpy not installed, skipping download_image check")
+            else:
+                array = created_sample.download_image()
+                original = Image.open(test_image_path)
+                assert array.shape == (original.height, original.width, 3)
+                assert array.dtype.name == "uint8"
+                assert created_sample.download_image(as_array=False) == original_data
+                print(f"✓ Decoded image array {array.shape}")
+
             print("\n✓ Test passed: populate_samples with automatic upload")
 
         finally:
@@ -736,7 +749,7 @@ class DatasetTest(TestCase):
             new_annotation_set_id,
             samples_payload,
             progress=capture_upload,
-        )
+        ).succeeded
 
         self.assertEqual(len(results), len(samples_payload))
         self.assertGreater(len(upload_progress), 0)
@@ -849,7 +862,9 @@ class DatasetTest(TestCase):
         sample.add_file(SampleFile("image", str(img_path)))
 
         try:
-            results = client.populate_samples(dataset_id, annotation_set_id, [sample])
+            results = client.populate_samples(
+                dataset_id, annotation_set_id, [sample]
+            ).succeeded
             self.assertEqual(len(results), 1)
             print("✓ Sample with image name works")
         finally:
@@ -890,7 +905,9 @@ class DatasetTest(TestCase):
         sample.add_file(SampleFile("image", str(img_path)))
 
         try:
-            results = client.populate_samples(dataset_id, annotation_set_id, [sample])
+            results = client.populate_samples(
+                dataset_id, annotation_set_id, [sample]
+            ).succeeded
             self.assertEqual(len(results), 1)
             print("✓ Annotation image key works")
         finally:
@@ -932,7 +949,9 @@ class DatasetTest(TestCase):
         sample.add_file(SampleFile("image", str(img_path)))
 
         try:
-            results = client.populate_samples(dataset_id, annotation_set_id, [sample])
+            results = client.populate_samples(
+                dataset_id, annotation_set_id, [sample]
+            ).succeeded
             self.assertEqual(len(results), 1)
             print("✓ Export files scenario works")
         finally:
@@ -973,7 +992,9 @@ class DatasetTest(TestCase):
         sample.add_file(SampleFile("image", str(img_path)))
 
         try:
-            results = client.populate_samples(dataset_id, annotation_set_id, [sample])
+            results = client.populate_samples(
+                dataset_id, annotation_set_id, [sample]
+            ).succeeded
             self.assertEqual(len(results), 1)
             print("✓ Annotation signature with bbox works")
         finally:
@@ -1042,7 +1063,9 @@ class DatasetTest(TestCase):
         sample.add_file(SampleFile("image", str(img_path)))
 
         try:
-            results = client.populate_samples(dataset_id, annotation_set_id, [sample])
+            results = client.populate_samples(
+                dataset_id, annotation_set_id, [sample]
+            ).succeeded
             self.assertEqual(len(results), 1)
             print("✓ Multiple annotations for same image works")
         finally:
@@ -1271,6 +1294,25 @@ class TestLabels(TestCase):
             )
             print("✓ Verified count matches actual samples")
 
+    def test_validate(self):
+        """validate() should return issues with errors ahead of warnings."""
+        client = get_client()
+        projects = client.projects("Unit Testing")
+        self.assertGreater(len(projects), 0)
+        dataset = client.datasets(projects[0].id)[0]
+        annotation_sets = client.annotation_sets(dataset.id)
+        if len(annotation_sets) == 0:
+            print("No annotation sets found, skipping validate test")
+            return
+
+        issues = dataset.validate(annotation_sets[0].id)
+        severities = [issue.severity for issue in issues]
+        errors = severities.count(Severity.Error)
+        self.assertTrue(all(s == Severity.Error for s in severities[:errors]))
+        for issue in issues:
+            self.assertNotEqual(issue.subject, "")
+            print(issue)
+
     def _download_dataset(self, client, dataset_id, output_dir, flatten=False):
         """Download dataset from EdgeFirst Studio.
 
@@ -98,6 +98,21 @@ class TestTrainingSession(unittest.TestCase):
 class TestValidate(unittest.TestCase):
     """Test validation session operations."""
 
+    def test_experiment_summary(self):
+        """Test summarizing an experiment's sessions by a metric."""
+        client = get_client()
+        project = client.projects("Unit Testing")[0]
+        experiment = client.experiments(project.id, "Unit Testing")[0]
+
+        summary = experiment.summary(metric="epochs")
+        self.assertEqual(
+            summary.session_count,
+            len(client.training_sessions(experiment.id)))
+        if summary.best_session is not None:
+            self.assertIsInstance(summary.best_metric, float)
+        else:
+            self.assertIsNone(summary.best_metric)
+
     def test_validation_session(self):
         """Test validation session metrics."""
         from edgefirst_client import Parameter